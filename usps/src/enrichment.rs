@@ -1,23 +1,33 @@
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::io::{self, Write as IoWrite};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine};
 
+use crate::fetch::Fetcher;
+use crate::progress::ProgressBar;
+use usps_rates::utils::{extract_image_filename, strip_url_query};
+
 const ENRICHMENT_DIR: &str = "enrichment/images";
+const STAMP_API_CACHE: &str = "admin.stampsforever.com/api/stamp-issuances";
 const LOGS_DIR: &str = "logs";
 const PRICING_FILE: &str = "data/llms/model_prices_and_context_window.json";
 const PRICING_URL: &str = "https://raw.githubusercontent.com/BerriAI/litellm/refs/heads/main/model_prices_and_context_window.json";
 const PRICING_MAX_AGE_DAYS: u64 = 7;
 
-const GEMINI_MODEL: &str = "gemini-2.5-flash-lite-preview-09-2025";
+/// Used when neither `--model` nor the `GEMINI_MODEL` env var is set
+const DEFAULT_GEMINI_MODEL: &str = "gemini-2.5-flash-lite-preview-09-2025";
 const GEMINI_API_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
 const PARALLEL_REQUESTS: usize = 5;
+/// `--compare` outputs go here instead of enrichment/images/
+const COMPARE_DIR: &str = "logs/compare";
+/// Images that failed in earlier runs, for `--retry-errors`
+const ERRORS_FILE: &str = "logs/enrichment-errors.json";
 
 /// Stamp enrichment data from AI analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +62,8 @@ pub struct StampEnrichment {
 /// Usage statistics from API response
 #[derive(Debug, Default, Clone)]
 struct UsageStats {
+    /// Images analyzed (one API request each)
+    images: u64,
     prompt_tokens: u64,
     cached_tokens: u64,
     output_tokens: u64,
@@ -59,15 +71,66 @@ struct UsageStats {
 
 impl UsageStats {
     fn add(&mut self, other: &UsageStats) {
+        self.images += other.images;
         self.prompt_tokens += other.prompt_tokens;
         self.cached_tokens += other.cached_tokens;
         self.output_tokens += other.output_tokens;
     }
 }
 
+/// Agreement between the primary and `--compare` model outputs
+#[derive(Debug, Default)]
+struct CompareStats {
+    compared: u32,
+    value_type_agree: u32,
+    shape_agree: u32,
+}
+
+impl CompareStats {
+    fn record(&mut self, primary: &StampEnrichment, other: &StampEnrichment) {
+        let same = |a: &Option<String>, b: &Option<String>| {
+            a.as_deref().map(str::to_lowercase) == b.as_deref().map(str::to_lowercase)
+        };
+        self.compared += 1;
+        if same(&primary.value_type, &other.value_type) {
+            self.value_type_agree += 1;
+        }
+        if same(&primary.shape, &other.shape) {
+            self.shape_agree += 1;
+        }
+    }
+
+    fn print(&self, model: &str, compare_model: &str) {
+        let pct = |n: u32| {
+            if self.compared == 0 {
+                0.0
+            } else {
+                n as f64 * 100.0 / self.compared as f64
+            }
+        };
+        println!(
+            "\n{} vs {} ({} images):",
+            model, compare_model, self.compared
+        );
+        println!(
+            "  value_type agree: {}/{} ({:.0}%)",
+            self.value_type_agree,
+            self.compared,
+            pct(self.value_type_agree)
+        );
+        println!(
+            "  shape agree:      {}/{} ({:.0}%)",
+            self.shape_agree,
+            self.compared,
+            pct(self.shape_agree)
+        );
+    }
+}
+
 /// Pricing info for a model
 #[derive(Debug, Clone)]
 struct ModelPricing {
+    model: String,
     input_cost_per_token: f64,
     output_cost_per_token: f64,
     cache_read_cost_per_token: f64,
@@ -166,6 +229,14 @@ struct GeminiAnalysis {
     full_bleed: bool,
 }
 
+/// Resolve the Gemini model: `--model`, then `GEMINI_MODEL`, then the default
+fn resolve_model(model: Option<String>) -> String {
+    model
+        .or_else(|| std::env::var("GEMINI_MODEL").ok())
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_GEMINI_MODEL.to_string())
+}
+
 fn get_api_key() -> Result<String> {
     std::env::var("GEMINI_API_KEY")
         .or_else(|_| std::env::var("GOOGLE_API_KEY"))
@@ -182,6 +253,26 @@ fn get_mime_type(path: &str) -> &'static str {
     }
 }
 
+/// Image filename without its extension (any of png/jpg/jpeg/gif/webp)
+fn image_stem(filename: &str) -> &str {
+    Path::new(filename)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(filename)
+}
+
+/// Load saved enrichment for a stamp image (enrichment/images/{year}/{api_slug}/{stem}.json)
+///
+/// Returns None if the image hasn't been enriched or the file can't be parsed.
+pub fn load_enrichment(year: u32, api_slug: &str, image_filename: &str) -> Option<StampEnrichment> {
+    let path = PathBuf::from(ENRICHMENT_DIR)
+        .join(year.to_string())
+        .join(api_slug)
+        .join(format!("{}.json", image_stem(image_filename)));
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
 /// Write JSON with sorted keys, compact arrays, trailing newline
 fn write_json_file<T: Serialize>(path: &PathBuf, value: &T) -> Result<()> {
     let json_value = serde_json::to_value(value)?;
@@ -193,7 +284,7 @@ fn write_json_file<T: Serialize>(path: &PathBuf, value: &T) -> Result<()> {
 }
 
 /// Recursively sort JSON object keys
-fn sort_json_value(value: Value) -> Value {
+pub(crate) fn sort_json_value(value: Value) -> Value {
     match value {
         Value::Object(map) => {
             let sorted: BTreeMap<String, Value> = map
@@ -243,7 +334,7 @@ fn format_json_compact_arrays(value: &Value, indent: usize) -> String {
 }
 
 /// Load or fetch pricing data
-fn load_pricing() -> Result<ModelPricing> {
+fn load_pricing(model: &str) -> Result<ModelPricing> {
     let pricing_path = PathBuf::from(PRICING_FILE);
 
     // Check if file exists and is fresh enough
@@ -274,12 +365,17 @@ fn load_pricing() -> Result<ModelPricing> {
     let pricing: Value = serde_json::from_str(&content)?;
 
     // Look for our model with gemini/ prefix
-    let model_key = format!("gemini/{}", GEMINI_MODEL);
-    let model_pricing = pricing
-        .get(&model_key)
-        .context(format!("Model {} not found in pricing data", model_key))?;
+    let model_key = format!("gemini/{}", model);
+    let Some(model_pricing) = pricing.get(&model_key) else {
+        bail!(
+            "Model '{}' not found in LiteLLM pricing data ({}). Check --model / GEMINI_MODEL.",
+            model_key,
+            PRICING_FILE
+        );
+    };
 
     Ok(ModelPricing {
+        model: model.to_string(),
         input_cost_per_token: model_pricing["input_cost_per_token"]
             .as_f64()
             .unwrap_or(0.0),
@@ -299,16 +395,7 @@ struct ImageToProcess {
     image_data: Vec<u8>,
 }
 
-/// Analyze a single stamp image (for parallel processing)
-fn analyze_single_stamp(
-    client: &reqwest::blocking::Client,
-    api_key: &str,
-    image: &ImageToProcess,
-) -> Result<(StampEnrichment, UsageStats)> {
-    let base64_image = BASE64_STANDARD.encode(&image.image_data);
-    let mime_type = get_mime_type(&image.image_filename);
-
-    let prompt = r#"Analyze this US postage stamp image and provide the following information as a JSON object:
+const ENRICHMENT_PROMPT: &str = r#"Analyze this US postage stamp image and provide the following information as a JSON object:
 
 {
   "year": integer or null,
@@ -335,17 +422,19 @@ Field descriptions:
 
 Respond with ONLY the JSON object."#;
 
-    let request = GeminiRequest {
+/// Build the Gemini request for one image, with `data` as its base64 payload
+fn build_request(image: &ImageToProcess, data: String) -> GeminiRequest {
+    GeminiRequest {
         contents: vec![GeminiContent {
             parts: vec![
                 GeminiPart::InlineData {
                     inline_data: InlineData {
-                        mime_type: mime_type.to_string(),
-                        data: base64_image,
+                        mime_type: get_mime_type(&image.image_filename).to_string(),
+                        data,
                     },
                 },
                 GeminiPart::Text {
-                    text: prompt.to_string(),
+                    text: ENRICHMENT_PROMPT.to_string(),
                 },
             ],
         }],
@@ -354,11 +443,42 @@ Respond with ONLY the JSON object."#;
             response_mime_type: "application/json".to_string(),
             thinking_config: ThinkingConfig { thinking_budget: 0 },
         },
-    };
+    }
+}
+
+/// Print the prompt and request JSON for `image` without sending it
+///
+/// The base64 image data is replaced by its size.
+fn dump_prompt_for(image: &ImageToProcessWithYear, model: &str) -> Result<()> {
+    let elided = format!(
+        "<{} bytes of {} elided>",
+        image.image.image_data.len(),
+        image.image.image_filename
+    );
+    let request = build_request(&image.image, elided);
+
+    println!("Image: {} ({})", image.image_url, image.api_slug);
+    println!("Model: {}", model);
+    println!("\n--- Prompt ---\n{}", ENRICHMENT_PROMPT);
+    println!(
+        "\n--- Request JSON ---\n{}",
+        serde_json::to_string_pretty(&request)?
+    );
+    Ok(())
+}
+
+/// Analyze a single stamp image (for parallel processing)
+fn analyze_single_stamp(
+    client: &reqwest::blocking::Client,
+    api_key: &str,
+    model: &str,
+    image: &ImageToProcess,
+) -> Result<(StampEnrichment, UsageStats)> {
+    let request = build_request(image, BASE64_STANDARD.encode(&image.image_data));
 
     let url = format!(
         "{}/{}:generateContent?key={}",
-        GEMINI_API_URL, GEMINI_MODEL, api_key
+        GEMINI_API_URL, model, api_key
     );
 
     let response = client
@@ -375,14 +495,18 @@ Respond with ONLY the JSON object."#;
         bail!("Gemini API error: {}", error.message);
     }
 
-    let usage = gemini_response
-        .usage_metadata
-        .as_ref()
-        .map_or(UsageStats::default(), |u| UsageStats {
+    let usage = gemini_response.usage_metadata.as_ref().map_or(
+        UsageStats {
+            images: 1,
+            ..UsageStats::default()
+        },
+        |u| UsageStats {
+            images: 1,
             prompt_tokens: u.prompt_token_count.unwrap_or(0),
             cached_tokens: u.cached_content_token_count.unwrap_or(0),
             output_tokens: u.candidates_token_count.unwrap_or(0),
-        });
+        },
+    );
 
     let candidates = gemini_response
         .candidates
@@ -421,19 +545,26 @@ Respond with ONLY the JSON object."#;
 pub struct EnrichmentClient {
     client: reqwest::blocking::Client,
     cache_dir: PathBuf,
+    /// Skip cache reads (responses are still written to the cache)
+    no_cache: bool,
 }
 
 impl EnrichmentClient {
-    pub fn new() -> Result<Self> {
+    pub fn new(cache_dir: &Path, no_cache: bool) -> Result<Self> {
         let client = reqwest::blocking::Client::builder()
             .user_agent("Mozilla/5.0 (compatible; USPSStampEnricher/1.0)")
             .build()?;
-        let cache_dir = PathBuf::from("cache");
-        Ok(Self { client, cache_dir })
+        fs::create_dir_all(cache_dir)
+            .with_context(|| format!("Failed to create cache dir: {:?}", cache_dir))?;
+        Ok(Self {
+            client,
+            cache_dir: cache_dir.to_path_buf(),
+            no_cache,
+        })
     }
 
     fn url_to_cache_path(&self, url: &str) -> PathBuf {
-        let url = url.split('?').next().unwrap_or(url);
+        let url = strip_url_query(url);
         if let Some(stripped) = url.strip_prefix("https://") {
             self.cache_dir.join(stripped)
         } else if let Some(stripped) = url.strip_prefix("http://") {
@@ -442,11 +573,17 @@ impl EnrichmentClient {
             self.cache_dir.join(url)
         }
     }
+}
+
+impl Fetcher for EnrichmentClient {
+    fn fetch_text(&self, url: &str) -> Result<String> {
+        Ok(String::from_utf8(self.fetch_binary(url)?)?)
+    }
 
-    pub fn fetch_binary(&self, url: &str) -> Result<Vec<u8>> {
+    fn fetch_binary(&self, url: &str) -> Result<Vec<u8>> {
         let cache_path = self.url_to_cache_path(url);
 
-        if cache_path.exists() {
+        if !self.no_cache && cache_path.exists() {
             return fs::read(&cache_path)
                 .with_context(|| format!("Failed to read cache: {:?}", cache_path));
         }
@@ -478,20 +615,25 @@ struct ImageToProcessWithYear {
     api_slug: String,
 }
 
-/// Get image info for a stamp slug, returns None if should skip
+/// Collect a stamp's images to analyze: the first, or all of them with `all_images`
+///
+/// Images that already have enrichment are skipped unless `force`, so an empty
+/// list means there's nothing to do for this stamp.
 fn get_stamp_image_info(
-    client: &EnrichmentClient,
+    client: &dyn Fetcher,
+    cache_dir: &Path,
     slug: &str,
     force: bool,
     quiet: bool,
-) -> Result<Option<ImageToProcessWithYear>> {
-    let cache_path = PathBuf::from("cache/admin.stampsforever.com/api/stamp-issuances").join(slug);
+    all_images: bool,
+) -> Result<Vec<ImageToProcessWithYear>> {
+    let cache_path = cache_dir.join(STAMP_API_CACHE).join(slug);
 
     if !cache_path.exists() {
         if !quiet {
             eprintln!("  Cache not found for {}, skipping", slug);
         }
-        return Ok(None);
+        return Ok(Vec::new());
     }
 
     let json_content = fs::read_to_string(&cache_path)?;
@@ -503,56 +645,62 @@ fn get_stamp_image_info(
         .unwrap_or("unknown")
         .to_string();
 
-    let images = stamp_data["images"].as_array();
-    let first_image = images
-        .and_then(|arr| arr.first())
-        .and_then(|img| img["path"].as_str());
-
-    let Some(image_url) = first_image else {
+    let mut image_urls: Vec<&str> = stamp_data["images"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|img| img["path"].as_str()).collect())
+        .unwrap_or_default();
+    if image_urls.is_empty() {
         if !quiet {
             eprintln!("  No stamp images found for {}", slug);
         }
-        return Ok(None);
-    };
+        return Ok(Vec::new());
+    }
+    if !all_images {
+        image_urls.truncate(1);
+    }
 
-    let clean_url = image_url.split('?').next().unwrap_or(image_url);
-    let image_filename = clean_url
-        .rsplit('/')
-        .next()
-        .unwrap_or("image.png")
-        .to_string();
+    let mut images = Vec::new();
+    let mut seen_filenames = HashSet::new();
+    for image_url in image_urls {
+        let clean_url = strip_url_query(image_url);
+        let image_filename = extract_image_filename(clean_url);
+        if !seen_filenames.insert(image_filename.clone()) {
+            continue;
+        }
 
-    // Check if enrichment already exists (unless force) - now in year subdirectory
-    if !force {
-        let base_filename = image_filename
-            .trim_end_matches(".png")
-            .trim_end_matches(".jpg");
-        let enrichment_path = PathBuf::from(ENRICHMENT_DIR)
-            .join(&year)
-            .join(format!("{}.json", base_filename));
-        if enrichment_path.exists() {
-            if !quiet {
-                let image_link = osc8_link(clean_url, &image_filename);
-                let json_name = format!("{}/{}.json", year, base_filename);
-                let json_link = osc8_link(&file_url(&enrichment_path), &json_name);
-                println!("  Skipped: {} -> {}", image_link, json_link);
+        // Check if enrichment already exists (unless force), where it's saved: {year}/{slug}/
+        if !force {
+            let base_filename = image_stem(&image_filename);
+            let enrichment_path = PathBuf::from(ENRICHMENT_DIR)
+                .join(&year)
+                .join(slug)
+                .join(format!("{}.json", base_filename));
+            if enrichment_path.exists() {
+                if !quiet {
+                    let image_link = osc8_link(clean_url, &image_filename);
+                    let json_name = format!("{}/{}/{}.json", year, slug, base_filename);
+                    let json_link = osc8_link(&file_url(&enrichment_path), &json_name);
+                    println!("  Skipped: {} -> {}", image_link, json_link);
+                }
+                continue;
             }
-            return Ok(None);
         }
-    }
 
-    // Fetch the image
-    let image_data = client.fetch_binary(clean_url)?;
+        // Fetch the image
+        let image_data = client.fetch_binary(clean_url)?;
+
+        images.push(ImageToProcessWithYear {
+            image: ImageToProcess {
+                image_filename,
+                image_data,
+            },
+            year: year.clone(),
+            image_url: clean_url.to_string(),
+            api_slug: slug.to_string(),
+        });
+    }
 
-    Ok(Some(ImageToProcessWithYear {
-        image: ImageToProcess {
-            image_filename,
-            image_data,
-        },
-        year,
-        image_url: clean_url.to_string(),
-        api_slug: slug.to_string(),
-    }))
+    Ok(images)
 }
 
 /// Create an OSC8 hyperlink for terminal output
@@ -570,13 +718,50 @@ fn file_url(path: &PathBuf) -> String {
     format!("file://{}", abs_path.display())
 }
 
-/// Print cost summary table
-fn print_summary(usage: &UsageStats, pricing: &ModelPricing) {
+/// Dollar cost of usage as (input, cached, output, total)
+fn usage_cost(usage: &UsageStats, pricing: &ModelPricing) -> (f64, f64, f64, f64) {
     let input_cost =
         (usage.prompt_tokens as f64 - usage.cached_tokens as f64) * pricing.input_cost_per_token;
     let cache_cost = usage.cached_tokens as f64 * pricing.cache_read_cost_per_token;
     let output_cost = usage.output_tokens as f64 * pricing.output_cost_per_token;
     let total_cost = input_cost + cache_cost + output_cost;
+    (input_cost, cache_cost, output_cost, total_cost)
+}
+
+/// Emit one JSONL record to stdout (for --json-logs)
+fn json_log(record: Value) {
+    println!("{}", record);
+}
+
+/// Per-image JSONL record; usage/cost are included when available
+fn image_log(
+    slug: &str,
+    filename: Option<&str>,
+    status: &str,
+    usage: Option<(&UsageStats, &ModelPricing)>,
+    error: Option<String>,
+) -> Value {
+    let mut record = serde_json::json!({
+        "type": "image",
+        "slug": slug,
+        "filename": filename,
+        "status": status,
+    });
+    if let Some((usage, pricing)) = usage {
+        record["prompt_tokens"] = usage.prompt_tokens.into();
+        record["cached_tokens"] = usage.cached_tokens.into();
+        record["output_tokens"] = usage.output_tokens.into();
+        record["cost"] = usage_cost(usage, pricing).3.into();
+    }
+    if let Some(error) = error {
+        record["error"] = error.into();
+    }
+    record
+}
+
+/// Print cost summary table
+fn print_summary(usage: &UsageStats, pricing: &ModelPricing) {
+    let (input_cost, cache_cost, output_cost, total_cost) = usage_cost(usage, pricing);
 
     println!();
     println!("┌──────────┬──────────────┬──────────────┬──────────────┐");
@@ -607,25 +792,181 @@ fn print_summary(usage: &UsageStats, pricing: &ModelPricing) {
         total_cost
     );
     println!("└──────────┴──────────────┴──────────────┴──────────────┘");
-    println!("Model: {}", GEMINI_MODEL);
+    println!("Model: {}", pricing.model);
+    if usage.images > 0 {
+        println!(
+            "Images: {} (${:.4} per image)",
+            usage.images,
+            total_cost / usage.images as f64
+        );
+    }
+}
+
+/// A failed image recorded in `ERRORS_FILE`
+#[derive(Debug, Serialize, Deserialize)]
+struct EnrichmentError {
+    slug: String,
+    /// None when the failure happened before an image was found
+    filename: Option<String>,
+    reason: String,
+}
+
+/// Load recorded failures; a missing file means there are none
+fn load_errors() -> Result<Vec<EnrichmentError>> {
+    match fs::read_to_string(ERRORS_FILE) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", ERRORS_FILE)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", ERRORS_FILE)),
+    }
+}
+
+/// Report an image whose worker thread gave no result, as an entry for `ERRORS_FILE`
+fn image_failure(
+    image: &ImageToProcessWithYear,
+    reason: &str,
+    quiet: bool,
+    json_logs: bool,
+) -> EnrichmentError {
+    let filename = &image.image.image_filename;
+    if json_logs {
+        json_log(image_log(
+            &image.api_slug,
+            Some(filename),
+            "error",
+            None,
+            Some(reason.to_string()),
+        ));
+    }
+    if !quiet {
+        let image_link = osc8_link(&image.image_url, filename);
+        eprintln!("  Error: {} - {}", image_link, reason);
+    }
+    EnrichmentError {
+        slug: image.api_slug.clone(),
+        filename: Some(filename.clone()),
+        reason: reason.to_string(),
+    }
+}
+
+/// Update `ERRORS_FILE` after a run
+fn save_errors(
+    resolved: &HashSet<(String, Option<String>)>,
+    failures: Vec<EnrichmentError>,
+) -> Result<()> {
+    let errors = merge_errors(load_errors()?, resolved, failures);
+    // One entry per line; write_json_file would put the whole list on one line
+    fs::write(ERRORS_FILE, serde_json::to_string_pretty(&errors)? + "\n")?;
+    Ok(())
+}
+
+/// Apply a run's results to the recorded failures, per `(slug, filename)`
+///
+/// `resolved` holds the images this run enriched, or `(slug, None)` for stamps with
+/// nothing left to enrich. Those entries are cleared and this run's `failures`
+/// replace the entries they repeat. A failure before any image was found is cleared
+/// once a run gets to the stamp's images, and everything else is kept.
+fn merge_errors(
+    mut errors: Vec<EnrichmentError>,
+    resolved: &HashSet<(String, Option<String>)>,
+    failures: Vec<EnrichmentError>,
+) -> Vec<EnrichmentError> {
+    let reached_images = |slug: &str| {
+        let found = |f: &EnrichmentError| f.slug == slug && f.filename.is_some();
+        resolved.iter().any(|(s, _)| s == slug) || failures.iter().any(found)
+    };
+    errors.retain(|e| {
+        let whole_stamp = (e.slug.clone(), None);
+        let image = (e.slug.clone(), e.filename.clone());
+        let repeated = |f: &EnrichmentError| f.slug == e.slug && f.filename == e.filename;
+        let cleared = resolved.contains(&whole_stamp)
+            || resolved.contains(&image)
+            || failures.iter().any(repeated)
+            || (e.filename.is_none() && reached_images(&e.slug));
+        !cleared
+    });
+    errors.extend(failures);
+    errors.sort_by(|a, b| {
+        a.slug
+            .cmp(&b.slug)
+            .then_with(|| a.filename.cmp(&b.filename))
+    });
+    errors
+}
+
+/// Options for `stamps enrich`
+pub struct EnrichOptions {
+    /// Specific stamp slug or year
+    pub filter: Option<String>,
+    pub quiet: bool,
+    /// Regenerate existing enrichment data
+    pub force: bool,
+    /// Emit JSONL records instead of progress output
+    pub json_logs: bool,
+    /// Gemini model (resolved via `resolve_model`)
+    pub model: Option<String>,
+    /// Second model to run on each image for `--compare`
+    pub compare_model: Option<String>,
+    /// Only process images recorded in `ERRORS_FILE`
+    pub retry_errors: bool,
+    /// Analyze every image of a stamp rather than only the first
+    pub all_images: bool,
+    /// Print the request for the first image instead of sending anything
+    pub dump_prompt: bool,
 }
 
 /// Run the enrichment command
-pub fn run_enrich(filter: Option<String>, quiet: bool, force: bool) -> Result<()> {
-    let api_key = get_api_key()?;
-    let client = EnrichmentClient::new()?;
+pub fn run_enrich(options: EnrichOptions, cache_root: &Path, no_cache: bool) -> Result<()> {
+    let EnrichOptions {
+        filter,
+        quiet,
+        force,
+        json_logs,
+        model,
+        compare_model,
+        retry_errors,
+        all_images,
+        dump_prompt,
+    } = options;
+
+    // JSONL replaces the human-readable progress output
+    let quiet = quiet || json_logs;
+    // Comparing re-analyzes images that already have enrichment
+    let force = force || compare_model.is_some();
+
+    // Check for scraped data up front, before touching the API key or network
+    let cache_dir = cache_root.join(STAMP_API_CACHE);
+    if !cache_dir.exists() {
+        bail!(
+            "Cache directory {:?} not found. Run 'stamps sync' and 'stamps scrape' first.",
+            cache_dir
+        );
+    }
+
+    // Nothing is sent with --dump-prompt, so it works without a key
+    let api_key = if dump_prompt {
+        String::new()
+    } else {
+        get_api_key()?
+    };
+    let client = EnrichmentClient::new(cache_root, no_cache)?;
 
     // Load pricing data
-    let pricing = load_pricing()?;
+    let model = resolve_model(model);
+    let pricing = load_pricing(&model)?;
+    let compare_pricing = compare_model.as_deref().map(load_pricing).transpose()?;
+    if compare_model.as_deref() == Some(model.as_str()) {
+        bail!(
+            "--compare model is the same as the primary model ({})",
+            model
+        );
+    }
 
     // Ensure directories exist
     fs::create_dir_all(ENRICHMENT_DIR)?;
     fs::create_dir_all(LOGS_DIR)?;
-
-    // Get list of stamps to process
-    let cache_dir = PathBuf::from("cache/admin.stampsforever.com/api/stamp-issuances");
-    if !cache_dir.exists() {
-        bail!("Cache directory not found. Run 'stamps sync' and 'stamps scrape' first.");
+    if compare_model.is_some() {
+        fs::create_dir_all(COMPARE_DIR)?;
     }
 
     let mut entries: Vec<String> = fs::read_dir(&cache_dir)?
@@ -637,7 +978,7 @@ pub fn run_enrich(filter: Option<String>, quiet: bool, force: bool) -> Result<()
     entries.sort();
 
     // Filter if specified
-    let stamps: Vec<String> = match filter {
+    let mut stamps: Vec<String> = match filter {
         Some(f) => {
             if f.len() == 4 && f.chars().all(|c| c.is_ascii_digit()) {
                 let year_str = f.clone();
@@ -662,6 +1003,18 @@ pub fn run_enrich(filter: Option<String>, quiet: bool, force: bool) -> Result<()
         None => entries,
     };
 
+    if retry_errors {
+        let failed: HashSet<String> = load_errors()?.into_iter().map(|e| e.slug).collect();
+        if failed.is_empty() {
+            println!("No failed images recorded in {}", ERRORS_FILE);
+            return Ok(());
+        }
+        stamps.retain(|slug| failed.contains(slug));
+        if !quiet {
+            println!("Retrying {} stamps from {}", stamps.len(), ERRORS_FILE);
+        }
+    }
+
     if stamps.is_empty() {
         bail!("No stamps found matching filter");
     }
@@ -672,37 +1025,63 @@ pub fn run_enrich(filter: Option<String>, quiet: bool, force: bool) -> Result<()
             "Enriching {} stamps with Gemini AI analysis ({} parallel requests)...",
             total, PARALLEL_REQUESTS
         );
-        if force {
+        if let Some(compare_model) = &compare_model {
+            println!(
+                "Comparing {} against {}; writing results to {}/",
+                model, compare_model, COMPARE_DIR
+            );
+        } else if force {
             println!("Force mode enabled - regenerating all enrichment data");
         }
     }
 
     let mut total_usage = UsageStats::default();
+    let mut compare_usage = UsageStats::default();
+    let mut compare_stats = CompareStats::default();
     let mut processed = 0;
     let mut skipped = 0;
     let mut errors = 0;
+    // For ERRORS_FILE: images (or whole stamps) finished this run, and this run's failures
+    let mut resolved: HashSet<(String, Option<String>)> = HashSet::new();
+    let mut failures: Vec<EnrichmentError> = Vec::new();
 
     // Collect images to process (with year info)
     let mut images_to_process: Vec<ImageToProcessWithYear> = Vec::new();
 
     for (i, slug) in stamps.iter().enumerate() {
+        // --dump-prompt only needs one image
+        if dump_prompt && !images_to_process.is_empty() {
+            break;
+        }
         if !quiet {
             print!("\r[{}/{}] Collecting {}...", i + 1, total, slug);
             io::stdout().flush()?;
         }
 
-        match get_stamp_image_info(&client, slug, force, quiet) {
-            Ok(Some(img_with_year)) => {
-                images_to_process.push(img_with_year);
+        match get_stamp_image_info(&client, &client.cache_dir, slug, force, quiet, all_images) {
+            Ok(images) if !images.is_empty() => {
+                images_to_process.extend(images);
             }
-            Ok(None) => {
+            Ok(_) => {
                 skipped += 1;
+                resolved.insert((slug.clone(), None));
+                if json_logs {
+                    json_log(image_log(slug, None, "skipped", None, None));
+                }
             }
             Err(e) => {
                 errors += 1;
+                failures.push(EnrichmentError {
+                    slug: slug.clone(),
+                    filename: None,
+                    reason: e.to_string(),
+                });
                 if !quiet {
                     eprintln!("\n  Error: {}", e);
                 }
+                if json_logs {
+                    json_log(image_log(slug, None, "error", None, Some(e.to_string())));
+                }
             }
         }
     }
@@ -716,11 +1095,20 @@ pub fn run_enrich(filter: Option<String>, quiet: bool, force: bool) -> Result<()
         );
     }
 
+    if dump_prompt {
+        let Some(image) = images_to_process.first() else {
+            bail!("No images to process; use --force to dump the prompt for an enriched stamp");
+        };
+        return dump_prompt_for(image, &model);
+    }
+
     // Process images in parallel (PARALLEL_REQUESTS at a time, single image per request)
     let chunks: Vec<_> = images_to_process.chunks(PARALLEL_REQUESTS).collect();
     let total_images = images_to_process.len();
+    let mut progress = ProgressBar::new(total_images, quiet);
 
     for (chunk_idx, chunk) in chunks.into_iter().enumerate() {
+        progress.clear();
         if !quiet {
             println!(
                 "\nProcessing {}-{} of {} ({} parallel requests)...",
@@ -736,6 +1124,8 @@ pub fn run_enrich(filter: Option<String>, quiet: bool, force: bool) -> Result<()
             .iter()
             .map(|img_with_year| {
                 let api_key = api_key.clone();
+                let model = model.clone();
+                let compare_model = compare_model.clone();
                 let image = img_with_year.image.clone();
                 let year = img_with_year.year.clone();
                 let image_url = img_with_year.image_url.clone();
@@ -747,23 +1137,83 @@ pub fn run_enrich(filter: Option<String>, quiet: bool, force: bool) -> Result<()
                         .build()
                         .ok()?;
 
-                    let result = analyze_single_stamp(&thread_client, &api_key, &image);
-                    Some((result, year, image.image_filename.clone(), image_url, api_slug))
+                    let result = analyze_single_stamp(&thread_client, &api_key, &model, &image);
+                    let compare_result = compare_model
+                        .map(|m| analyze_single_stamp(&thread_client, &api_key, &m, &image));
+                    Some((
+                        result,
+                        compare_result,
+                        year,
+                        image.image_filename.clone(),
+                        image_url,
+                        api_slug,
+                    ))
                 })
             })
             .collect();
 
         // Collect results
-        for handle in handles {
+        for (img_with_year, handle) in chunk.iter().zip(handles) {
             match handle.join() {
-                Ok(Some((Ok((enrichment, usage)), year, _filename, image_url, api_slug))) => {
+                Ok(Some((
+                    Ok((enrichment, usage)),
+                    Some(compare_result),
+                    _,
+                    _,
+                    image_url,
+                    api_slug,
+                ))) => {
                     total_usage.add(&usage);
+                    let compare_model = compare_model.as_deref().unwrap_or_default();
+                    let (other, other_usage) = match compare_result {
+                        Ok(result) => result,
+                        Err(e) => {
+                            errors += 1;
+                            failures.push(EnrichmentError {
+                                slug: api_slug,
+                                filename: Some(enrichment.image_filename.clone()),
+                                reason: format!("{}: {}", compare_model, e),
+                            });
+                            if !quiet {
+                                let image_link = osc8_link(&image_url, &enrichment.image_filename);
+                                eprintln!("  Error ({}): {} - {}", compare_model, image_link, e);
+                            }
+                            continue;
+                        }
+                    };
+                    compare_usage.add(&other_usage);
+                    compare_stats.record(&enrichment, &other);
+
+                    // One file per model, named {slug}.{model}.json
+                    // ({slug}.{image}.{model}.json with --all-images)
+                    let stem = if all_images {
+                        format!("{}.{}", api_slug, image_stem(&enrichment.image_filename))
+                    } else {
+                        api_slug.clone()
+                    };
+                    for (m, result) in [(model.as_str(), &enrichment), (compare_model, &other)] {
+                        let path = PathBuf::from(COMPARE_DIR).join(format!("{}.{}.json", stem, m));
+                        write_json_file(&path, result)?;
+                    }
+                    processed += 1;
+                    resolved.insert((api_slug, Some(enrichment.image_filename.clone())));
 
-                    let output_filename = enrichment
-                        .image_filename
-                        .trim_end_matches(".png")
-                        .trim_end_matches(".jpg")
-                        .trim_end_matches(".jpeg");
+                    if !quiet {
+                        let image_link = osc8_link(&image_url, &enrichment.image_filename);
+                        println!(
+                            "  Compared: {} value_type {:?}/{:?}, shape {:?}/{:?}",
+                            image_link,
+                            enrichment.value_type,
+                            other.value_type,
+                            enrichment.shape,
+                            other.shape
+                        );
+                    }
+                }
+                Ok(Some((Ok((enrichment, usage)), None, year, _filename, image_url, api_slug))) => {
+                    total_usage.add(&usage);
+
+                    let output_filename = image_stem(&enrichment.image_filename);
 
                     // Create year/api_slug directory and save there
                     let year_dir = PathBuf::from(ENRICHMENT_DIR).join(&year).join(&api_slug);
@@ -772,7 +1222,17 @@ pub fn run_enrich(filter: Option<String>, quiet: bool, force: bool) -> Result<()
                     write_json_file(&output_path, &enrichment)?;
 
                     processed += 1;
-
+                    resolved.insert((api_slug.clone(), Some(enrichment.image_filename.clone())));
+
+                    if json_logs {
+                        json_log(image_log(
+                            &api_slug,
+                            Some(&enrichment.image_filename),
+                            "saved",
+                            Some((&usage, &pricing)),
+                            None,
+                        ));
+                    }
                     if !quiet {
                         let image_link = osc8_link(&image_url, &enrichment.image_filename);
                         let json_name = format!("{}/{}/{}.json", year, api_slug, output_filename);
@@ -780,27 +1240,40 @@ pub fn run_enrich(filter: Option<String>, quiet: bool, force: bool) -> Result<()
                         println!("  Saved: {} -> {}", image_link, json_link);
                     }
                 }
-                Ok(Some((Err(e), _year, filename, image_url, _api_slug))) => {
+                Ok(Some((Err(e), _, _year, filename, image_url, api_slug))) => {
                     errors += 1;
+                    if json_logs {
+                        json_log(image_log(
+                            &api_slug,
+                            Some(&filename),
+                            "error",
+                            None,
+                            Some(e.to_string()),
+                        ));
+                    }
                     if !quiet {
                         let image_link = osc8_link(&image_url, &filename);
                         eprintln!("  Error: {} - {}", image_link, e);
                     }
+                    failures.push(EnrichmentError {
+                        slug: api_slug,
+                        filename: Some(filename),
+                        reason: e.to_string(),
+                    });
                 }
                 Ok(None) => {
                     errors += 1;
-                    if !quiet {
-                        eprintln!("  Error: Failed to create HTTP client");
-                    }
+                    let reason = "Failed to create HTTP client";
+                    failures.push(image_failure(img_with_year, reason, quiet, json_logs));
                 }
                 Err(_) => {
                     errors += 1;
-                    if !quiet {
-                        eprintln!("  Error: Thread panicked");
-                    }
+                    let reason = "Thread panicked";
+                    failures.push(image_failure(img_with_year, reason, quiet, json_logs));
                 }
             }
         }
+        progress.advance(chunk.len());
     }
 
     if !quiet {
@@ -809,7 +1282,101 @@ pub fn run_enrich(filter: Option<String>, quiet: bool, force: bool) -> Result<()
             processed, skipped, errors
         );
         print_summary(&total_usage, &pricing);
+        if let (Some(compare_model), Some(compare_pricing)) = (&compare_model, &compare_pricing) {
+            print_summary(&compare_usage, compare_pricing);
+            compare_stats.print(&model, compare_model);
+        }
+    }
+
+    if json_logs {
+        let (input_cost, cache_cost, output_cost, total_cost) = usage_cost(&total_usage, &pricing);
+        json_log(serde_json::json!({
+            "type": "summary",
+            "processed": processed,
+            "images": total_usage.images,
+            "skipped": skipped,
+            "errors": errors,
+            "prompt_tokens": total_usage.prompt_tokens,
+            "cached_tokens": total_usage.cached_tokens,
+            "output_tokens": total_usage.output_tokens,
+            "input_cost": input_cost,
+            "cache_cost": cache_cost,
+            "output_cost": output_cost,
+            "total_cost": total_cost,
+            "model": pricing.model,
+        }));
+        if let Some(compare_pricing) = &compare_pricing {
+            json_log(serde_json::json!({
+                "type": "compare",
+                "model": pricing.model,
+                "compare_model": compare_pricing.model,
+                "compared": compare_stats.compared,
+                "value_type_agree": compare_stats.value_type_agree,
+                "shape_agree": compare_stats.shape_agree,
+                "compare_cost": usage_cost(&compare_usage, compare_pricing).3,
+            }));
+        }
+    }
+
+    let failed = failures.len();
+    save_errors(&resolved, failures)?;
+    if failed > 0 && !quiet {
+        println!(
+            "Recorded {} failed image(s) in {}; re-run with --retry-errors",
+            failed, ERRORS_FILE
+        );
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(slug: &str, filename: Option<&str>) -> EnrichmentError {
+        EnrichmentError {
+            slug: slug.to_string(),
+            filename: filename.map(str::to_string),
+            reason: "failed".to_string(),
+        }
+    }
+
+    fn keys(errors: &[EnrichmentError]) -> Vec<(&str, Option<&str>)> {
+        errors
+            .iter()
+            .map(|e| (e.slug.as_str(), e.filename.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_errors_per_image() {
+        let recorded = vec![
+            error("flag", Some("a.png")),
+            error("flag", Some("b.png")),
+            error("poppy", None),
+            error("skipped", Some("c.png")),
+            error("unreached", None),
+        ];
+        // a.png now enriches but b.png fails again; poppy's images were found this time
+        let resolved = HashSet::from([
+            ("flag".to_string(), Some("a.png".to_string())),
+            ("skipped".to_string(), None),
+        ]);
+        let failures = vec![error("flag", Some("b.png")), error("poppy", Some("d.png"))];
+        let merged = merge_errors(recorded, &resolved, failures);
+        assert_eq!(
+            keys(&merged),
+            [
+                ("flag", Some("b.png")),
+                ("poppy", Some("d.png")),
+                ("unreached", None)
+            ]
+        );
+
+        // Another image of the stamp enriching doesn't clear one that never did
+        let resolved = HashSet::from([("flag".to_string(), Some("a.png".to_string()))]);
+        let merged = merge_errors(merged, &resolved, Vec::new());
+        assert_eq!(keys(&merged)[0], ("flag", Some("b.png")));
+    }
+}
@@ -0,0 +1,94 @@
+//! Export database tables to CSV for tracking them over time
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::scrape::open_database;
+use usps_rates::utils::normalize_price;
+
+/// One row of the products CSV
+#[derive(Debug, Serialize)]
+struct ProductRow {
+    stamp_slug: String,
+    year: u32,
+    title: String,
+    price: Option<String>,
+    postal_store_url: Option<String>,
+}
+
+/// Write every product as CSV, sorted by stamp slug then title so snapshots diff cleanly
+fn write_products_csv(conn: &Connection, writer: impl Write) -> Result<usize> {
+    let mut stmt = conn.prepare(
+        "SELECT stamp_slug, year, title, price, postal_store_url FROM products
+         ORDER BY stamp_slug, title",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ProductRow {
+            stamp_slug: row.get(0)?,
+            year: row.get(1)?,
+            title: row.get(2)?,
+            price: row
+                .get::<_, Option<String>>(3)?
+                .map(|p| normalize_price(&p)),
+            postal_store_url: row.get(4)?,
+        })
+    })?;
+
+    let mut csv = csv::Writer::from_writer(writer);
+    let mut count = 0;
+    for row in rows {
+        csv.serialize(row?)?;
+        count += 1;
+    }
+    csv.flush()?;
+    Ok(count)
+}
+
+/// Dump the products table to CSV at `output`, or stdout if None
+pub fn run_export_products(output: Option<&Path>) -> Result<()> {
+    let conn = open_database()?;
+    match output {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            let count = write_products_csv(&conn, file)?;
+            println!("Exported {} products to {}", count, path.display());
+        }
+        None => {
+            write_products_csv(&conn, io::stdout().lock())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_database;
+
+    #[test]
+    fn test_write_products_csv() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO products (stamp_slug, year, title, price, postal_store_url) VALUES
+                ('love-2025', 2025, 'Pane of 20', '$14.60', 'https://store.usps.com/a'),
+                ('flags-2024', 2024, 'Coil of 100', '73', NULL),
+                ('flags-2024', 2024, 'Booklet of 20', '$1,460', NULL);",
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        assert_eq!(write_products_csv(&conn, &mut out).unwrap(), 3);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "stamp_slug,year,title,price,postal_store_url\n\
+             flags-2024,2024,Booklet of 20,\"$1,460.00\",\n\
+             flags-2024,2024,Coil of 100,$73.00,\n\
+             love-2025,2025,Pane of 20,$14.60,https://store.usps.com/a\n"
+        );
+    }
+}
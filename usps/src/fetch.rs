@@ -0,0 +1,237 @@
+//! Network access behind a trait, so API parsing can be tested against fixtures
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::io::Read;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Bodies with a `Content-Length` above this report download progress
+pub const PROGRESS_THRESHOLD_BYTES: u64 = 1024 * 1024;
+
+/// Called with (bytes read so far, `Content-Length`) while a large body downloads
+pub type Progress<'a> = &'a mut dyn FnMut(u64, u64);
+
+/// Source of HTTP responses: the live network, the response cache, or test fixtures
+pub trait Fetcher {
+    fn fetch_text(&self, url: &str) -> Result<String>;
+    fn fetch_binary(&self, url: &str) -> Result<Vec<u8>>;
+
+    /// `fetch_binary`, reporting progress for bodies over `PROGRESS_THRESHOLD_BYTES`
+    ///
+    /// Sources without a network download (cache hits, fixtures) never report progress.
+    fn fetch_binary_with_progress(&self, url: &str, progress: Progress) -> Result<Vec<u8>> {
+        let _ = progress;
+        self.fetch_binary(url)
+    }
+}
+
+impl dyn Fetcher + '_ {
+    /// Fetch `url` and parse the body as JSON
+    pub fn fetch_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let text = self.fetch_text(url)?;
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse JSON: {}", url))
+    }
+}
+
+/// HTTP client for stampsforever.com requests
+pub fn http_client() -> Result<reqwest::blocking::Client> {
+    Ok(reqwest::blocking::Client::builder()
+        .user_agent("Mozilla/5.0 (compatible; USPSStampScraper/1.0)")
+        .build()?)
+}
+
+impl Fetcher for reqwest::blocking::Client {
+    fn fetch_text(&self, url: &str) -> Result<String> {
+        self.get(url)
+            .send()
+            .with_context(|| format!("Failed to fetch: {}", url))?
+            .text()
+            .with_context(|| format!("Failed to read response: {}", url))
+    }
+
+    fn fetch_binary(&self, url: &str) -> Result<Vec<u8>> {
+        self.fetch_binary_with_progress(url, &mut |_, _| {})
+    }
+
+    fn fetch_binary_with_progress(&self, url: &str, progress: Progress) -> Result<Vec<u8>> {
+        fetch_binary_limited(self, url, None, progress)
+    }
+}
+
+/// Error for a body over the size limit, so callers can skip it rather than fail
+#[derive(Debug)]
+pub struct TooLarge {
+    pub url: String,
+    pub limit: u64,
+}
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is larger than {} bytes", self.url, self.limit)
+    }
+}
+
+impl std::error::Error for TooLarge {}
+
+/// `fetch_binary_with_progress`, giving up with `TooLarge` once the body passes `max_bytes`
+///
+/// An oversized `Content-Length` fails before anything is read; bodies without one
+/// are cut off as soon as they go over.
+pub fn fetch_binary_limited(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    max_bytes: Option<u64>,
+    progress: Progress,
+) -> Result<Vec<u8>> {
+    let response = client
+        .get(url)
+        .send()
+        .with_context(|| format!("Failed to fetch: {}", url))?;
+    let length = response.content_length();
+    read_body(response, url, length, max_bytes, progress)
+}
+
+/// Most `read_body` reserves up front from Content-Length; larger bodies grow as they stream
+const MAX_PREALLOCATE_BYTES: u64 = 8 * 1024 * 1024;
+
+fn read_body(
+    mut reader: impl Read,
+    url: &str,
+    length: Option<u64>,
+    max_bytes: Option<u64>,
+    progress: Progress,
+) -> Result<Vec<u8>> {
+    let limit = max_bytes.unwrap_or(u64::MAX);
+    let too_large = || TooLarge {
+        url: url.to_string(),
+        limit,
+    };
+    if length.is_some_and(|len| len > limit) {
+        return Err(too_large().into());
+    }
+
+    // Stream the body so the limit holds and large bodies can report progress
+    let total = length.filter(|&len| len > PROGRESS_THRESHOLD_BYTES);
+    // Content-Length is only a hint from the server; don't trust it for a big allocation
+    let capacity = length.unwrap_or(0).min(limit).min(MAX_PREALLOCATE_BYTES);
+    let mut body = Vec::with_capacity(capacity as usize);
+    let mut chunk = [0u8; 64 * 1024];
+    loop {
+        let n = reader
+            .read(&mut chunk)
+            .with_context(|| format!("Failed to read response: {}", url))?;
+        if n == 0 {
+            break;
+        }
+        if (body.len() + n) as u64 > limit {
+            return Err(too_large().into());
+        }
+        body.extend_from_slice(&chunk[..n]);
+        if let Some(total) = total {
+            progress(body.len() as u64, total);
+        }
+    }
+    Ok(body)
+}
+
+/// Spaces requests at least `interval` apart, shared by every thread using it
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Block until the next request may start
+    ///
+    /// The lock is held while sleeping, so waiting threads go one interval apart.
+    pub fn wait(&self) {
+        let mut next = self.next.lock().unwrap();
+        let now = Instant::now();
+        if *next > now {
+            thread::sleep(*next - now);
+        }
+        *next = Instant::now() + self.interval;
+    }
+}
+
+/// Canned responses keyed by URL; any other URL is an error
+#[cfg(test)]
+#[derive(Default)]
+pub struct FixtureFetcher {
+    responses: std::collections::HashMap<String, Vec<u8>>,
+}
+
+#[cfg(test)]
+impl FixtureFetcher {
+    pub fn with(mut self, url: &str, body: impl Into<Vec<u8>>) -> Self {
+        self.responses.insert(url.to_string(), body.into());
+        self
+    }
+}
+
+#[cfg(test)]
+impl Fetcher for FixtureFetcher {
+    fn fetch_text(&self, url: &str) -> Result<String> {
+        Ok(String::from_utf8(self.fetch_binary(url)?)?)
+    }
+
+    fn fetch_binary(&self, url: &str) -> Result<Vec<u8>> {
+        self.responses
+            .get(url)
+            .cloned()
+            .with_context(|| format!("No fixture for {}", url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_body_limit() {
+        let body = vec![7u8; 100_000];
+        let read = |length, max_bytes| {
+            read_body(
+                &body[..],
+                "https://example.com/a.jpg",
+                length,
+                max_bytes,
+                &mut |_, _| {},
+            )
+        };
+        assert_eq!(read(Some(100_000), None).unwrap(), body);
+        assert_eq!(read(None, Some(100_000)).unwrap(), body);
+        // A bogus Content-Length doesn't turn into a huge allocation
+        assert_eq!(read(Some(u64::MAX), None).unwrap(), body);
+
+        // Rejected up front from the header, or mid-stream without one
+        for length in [Some(100_000), None] {
+            let err = read(length, Some(99_999)).unwrap_err();
+            let err = err.downcast_ref::<TooLarge>().unwrap();
+            assert_eq!(err.url, "https://example.com/a.jpg");
+        }
+    }
+
+    #[test]
+    fn test_rate_limiter_spaces_requests_across_threads() {
+        let limiter = RateLimiter::new(Duration::from_millis(20));
+        let start = Instant::now();
+        thread::scope(|scope| {
+            for _ in 0..3 {
+                scope.spawn(|| limiter.wait());
+            }
+        });
+        // The first request goes immediately, the other two wait an interval each
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+}
@@ -1,12 +1,93 @@
-use anyhow::{Context, Result};
+use crate::enrichment::load_enrichment;
+use crate::images::image_dimensions;
+use anyhow::{bail, Context, Result};
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::os::unix::fs::symlink;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use usps_rates::rates::PostalRates;
+use usps_rates::slug::slugify;
+use usps_rates::types::{RateCategory, RateType};
+use usps_rates::utils::normalize_price;
+
+pub const OUTPUT_DIR: &str = "output";
+pub const DATA_DIR: &str = "data/stamps";
+pub const MIN_YEAR: u32 = 1995;
+pub const SERIES_FILE: &str = "enrichment/series.conl";
+
+/// Site-wide strings, so a fork can publish another postal catalog
+#[derive(Debug, Clone)]
+pub struct SiteConfig {
+    /// Site name, shown in the header and page titles
+    pub title: String,
+    /// `<html lang>` value
+    pub lang: String,
+    /// Public URL of the site (e.g. `https://stamps.example.com`) for canonical links
+    pub base_url: Option<String>,
+    /// Footer contents (raw HTML), e.g. a disclaimer
+    pub footer_html: String,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        Self {
+            title: "US Postage Stamps".to_string(),
+            lang: "en".to_string(),
+            base_url: None,
+            footer_html: r#"<p>Not affiliated with United States Postal Service.</p>
+            <p>This is a USPS fan project - Not responsible for errors or omissions.</p>
+            <p>Please see <a href="https://usps.com">USPS.com</a> for Official Rates, Regulations and Purchase.</p>"#
+                .to_string(),
+        }
+    }
+}
 
-const OUTPUT_DIR: &str = "output";
-const DATA_DIR: &str = "data/stamps";
-const MIN_YEAR: u32 = 1995;
+/// Options controlling which stamps `run_generate` includes
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    /// Include hidden rate types (Federal Duck, Presorted, Nonprofit)
+    pub include_hidden: bool,
+    /// Earliest issue year to include
+    pub min_year: u32,
+    /// Latest issue year to include (unbounded if None)
+    pub max_year: Option<u32>,
+    /// Scraped stamp data to read (normally `DATA_DIR`)
+    pub data_dir: PathBuf,
+    /// Series descriptions to render on series pages (normally `SERIES_FILE`)
+    pub series_file: PathBuf,
+    /// Directory to write the site into (normally `OUTPUT_DIR`); replaced wholesale
+    pub output_dir: PathBuf,
+    /// Minify the embedded CSS and collapse whitespace in generated HTML
+    pub minify: bool,
+    /// Only include stamps that still have product listings (purchasable)
+    pub only_with_products: bool,
+    /// Rate types to leave out, in addition to `HIDDEN_RATE_TYPES`
+    pub exclude_rate_types: Vec<String>,
+    /// Date for rate lookups (forever stamp values, "Value Today"); normally today
+    pub as_of: NaiveDate,
+    /// Rebuild only this stamp's page and image links, leaving the rest of the output alone
+    pub only: Option<String>,
+    /// Return an error instead of succeeding when no stamps load (e.g. wrong working directory)
+    pub fail_on_empty: bool,
+    /// Build into a sibling `<output>.tmp` and swap it in on success, so a failed
+    /// build leaves the previous site intact; otherwise wipe and rebuild in place
+    pub atomic: bool,
+    /// Leave out stamps not issued yet as of `as_of` (future or TBA issue date)
+    pub hide_upcoming: bool,
+    pub site: SiteConfig,
+}
+
+impl GenerateOptions {
+    fn includes_year(&self, year: u32) -> bool {
+        year >= self.min_year && self.max_year.is_none_or(|max| year <= max)
+    }
+}
+
+/// Image files linked into output/images/
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "avif"];
 
 // Rate types to hide
 const HIDDEN_RATE_TYPES: &[&str] = &[
@@ -17,6 +98,8 @@ const HIDDEN_RATE_TYPES: &[&str] = &[
 ];
 
 /// Parsed stamp metadata from CONL file
+///
+/// Equality and hashing are by `slug`, which identifies a stamp's pages on the site.
 #[derive(Debug, Clone)]
 pub struct Stamp {
     pub name: String,
@@ -25,31 +108,96 @@ pub struct Stamp {
     pub url: String,
     pub year: u32,
     pub issue_date: Option<String>,
+    pub issue_locations: Vec<String>, // First-day cities, or ["Nationwide"]
     pub rate: Option<f64>,
     pub rate_type: Option<String>,
     pub extra_cost: Option<f64>, // Semipostal donation amount
     pub forever: bool,           // Whether this is a forever stamp
     pub stamp_type: String,      // "stamp", "card", "envelope"
     pub series: Option<String>,
+    pub tags: Vec<String>, // Topics, deduped by slug at scrape time
     pub stamp_images: Vec<String>,
     pub sheet_image: Option<String>,
+    pub sheet_image_size: Option<(u32, u32)>, // Recorded at scrape time
     pub credits: Credits,
     pub about: Option<String>,
     pub products: Vec<Product>,
     pub background_color: Option<String>,
+    pub hidden: bool,              // Suppressed via `hidden: true` override
+    pub canceled: bool,            // Announced but never issued
+    pub shape: Option<String>,     // Enrichment shape (one of STAMP_SHAPES)
+    pub pair_with: Option<String>, // Slug of the same design at another rate
+}
+
+impl Stamp {
+    /// No longer sold: the stamp has no product listings
+    pub fn is_discontinued(&self) -> bool {
+        self.products.is_empty()
+    }
+
+    /// Not issued yet as of `today`: a future issue date, or a TBA one in this year or later
+    /// (canceled stamps never issue, and undated stamps from past years just lack a date)
+    pub fn is_upcoming(&self, today: NaiveDate) -> bool {
+        if self.canceled {
+            return false;
+        }
+        match &self.issue_date {
+            Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok_and(|d| d > today),
+            None => self.year as i32 >= today.year(),
+        }
+    }
+}
+
+impl PartialEq for Stamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.slug == other.slug
+    }
+}
+
+impl Eq for Stamp {}
+
+impl std::hash::Hash for Stamp {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.slug.hash(state);
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct Credits {
-    pub art_director: Option<String>,
-    pub artist: Option<String>,
-    pub designer: Option<String>,
-    pub typographer: Option<String>,
-    pub photographer: Option<String>,
-    pub illustrator: Option<String>,
+    pub art_director: Vec<String>,
+    pub artist: Vec<String>,
+    pub designer: Vec<String>,
+    pub typographer: Vec<String>,
+    pub photographer: Vec<String>,
+    pub illustrator: Vec<String>,
     pub sources: Vec<String>,
 }
 
+impl Credits {
+    /// Credited people grouped by role, in display order
+    ///
+    /// Designers who are also credited as artist are skipped; roles with nobody are omitted.
+    fn by_role(&self) -> Vec<(&'static str, Vec<&String>)> {
+        let designers: Vec<&String> = self
+            .designer
+            .iter()
+            .filter(|name| !self.artist.contains(name))
+            .collect();
+        [
+            ("Art Director", self.art_director.iter().collect()),
+            ("Artist", self.artist.iter().collect()),
+            ("Designer", designers),
+            ("Photographer", self.photographer.iter().collect()),
+            ("Illustrator", self.illustrator.iter().collect()),
+            ("Typographer", self.typographer.iter().collect()),
+            ("Source", self.sources.iter().collect()),
+        ]
+        .into_iter()
+        .filter(|(_, names)| !names.is_empty())
+        .collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Product {
     pub title: String,
@@ -69,6 +217,7 @@ pub struct ProductMetadata {
     pub style: Option<String>,
     pub closure: Option<String>,
     pub sided: Option<u32>,
+    pub panes: Option<u32>, // Panes per press sheet
 }
 
 impl Product {
@@ -171,15 +320,22 @@ impl Product {
 /// Year page category for custom grouping on year pages
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum YearPageCategory {
+    Upcoming,     // Future or TBA issue date (see `Stamp::is_upcoming`)
     Forever,      // Forever Stamps
     OtherForever, // Additional Postage + Global + Postcard Forever Stamps
     Denominated,  // Denominated Stamps + Priority Mail (sorted by value)
     Other,        // Stamped envelopes, cards, postcards
     Unknown,      // Catch-all for remaining stamps
+    NotIssued,    // Announced but canceled (`issued = canceled` override)
 }
 
 impl YearPageCategory {
     fn from_stamp(stamp: &Stamp) -> Self {
+        // Canceled stamps get their own section whatever their type
+        if stamp.canceled {
+            return YearPageCategory::NotIssued;
+        }
+
         // First check stamp_type for cards/envelopes
         if stamp.stamp_type == "card" || stamp.stamp_type == "envelope" {
             return YearPageCategory::Other;
@@ -190,27 +346,21 @@ impl YearPageCategory {
             return YearPageCategory::Denominated;
         }
 
-        let rate_type = stamp.rate_type.as_deref().unwrap_or("");
-
-        match rate_type {
-            "Forever" | "Semipostal" => YearPageCategory::Forever,
-            "Postcard"
-            | "International"
-            | "Global Forever"
-            | "Additional Ounce"
-            | "Two Ounce"
-            | "Three Ounce"
-            | "Nonmachineable Surcharge"
-            | "Additional Postage" => YearPageCategory::OtherForever,
-            "Priority Mail" | "Priority Mail Express" => YearPageCategory::Denominated,
-            "Definitive" | "Other Denomination" | "First Class" | "Special" => {
+        match stamp_rate_category(stamp) {
+            RateCategory::Forever => YearPageCategory::Forever,
+            RateCategory::AdditionalPostage
+            | RateCategory::Nonmachinable
+            | RateCategory::Global
+            | RateCategory::Postcard => YearPageCategory::OtherForever,
+            RateCategory::Priority => YearPageCategory::Denominated,
+            RateCategory::Denominated => {
                 if extract_denomination(&stamp.name).is_some() || stamp.rate.is_some() {
                     YearPageCategory::Denominated
                 } else {
                     YearPageCategory::Unknown
                 }
             }
-            _ => {
+            RateCategory::Presorted | RateCategory::Other => {
                 if extract_denomination(&stamp.name).is_some() {
                     YearPageCategory::Denominated
                 } else {
@@ -222,88 +372,179 @@ impl YearPageCategory {
 
     fn display_name(&self) -> &str {
         match self {
+            YearPageCategory::Upcoming => "Upcoming",
             YearPageCategory::Forever => "Forever Stamps",
             YearPageCategory::OtherForever => "Other Forever Stamps",
             YearPageCategory::Denominated => "Denominated Stamps",
             YearPageCategory::Other => "Other",
             YearPageCategory::Unknown => "Unknown",
+            YearPageCategory::NotIssued => "Announced but not issued",
+        }
+    }
+
+    /// Fragment id for the category's section on year pages (e.g. `/2025/#denominated`).
+    /// Fixed per variant so links stay valid if display names change.
+    fn anchor_id(&self) -> &'static str {
+        match self {
+            YearPageCategory::Upcoming => "upcoming",
+            YearPageCategory::Forever => "forever",
+            YearPageCategory::OtherForever => "other-forever",
+            YearPageCategory::Denominated => "denominated",
+            YearPageCategory::Other => "other",
+            YearPageCategory::Unknown => "unknown",
+            YearPageCategory::NotIssued => "not-issued",
         }
     }
 
     fn sort_order(&self) -> u8 {
         match self {
-            YearPageCategory::Forever => 0,
-            YearPageCategory::OtherForever => 1,
-            YearPageCategory::Denominated => 2,
-            YearPageCategory::Other => 3,
-            YearPageCategory::Unknown => 4,
+            YearPageCategory::Upcoming => 0,
+            YearPageCategory::Forever => 1,
+            YearPageCategory::OtherForever => 2,
+            YearPageCategory::Denominated => 3,
+            YearPageCategory::Other => 4,
+            YearPageCategory::Unknown => 5,
+            YearPageCategory::NotIssued => 6,
+        }
+    }
+}
+
+/// Maximum related stamps shown on a stamp page
+const MAX_RELATED_STAMPS: usize = 6;
+
+/// Enrichment shapes that get a `shape-*` class on stamp cards
+const STAMP_SHAPES: &[&str] = &["portrait", "landscape", "square", "circular", "triangle"];
+
+/// Unicode vulgar fractions found in historical denominations (e.g., "1½¢")
+const UNICODE_FRACTIONS: &[(char, f64)] = &[('½', 0.5), ('¼', 0.25), ('¾', 0.75)];
+
+/// Parse a cent amount with optional fraction ("10", "½", "1½", "1-1/2", "2.5")
+fn parse_fractional_cents(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let first = s.chars().next()?;
+    if !first.is_ascii_digit() && !UNICODE_FRACTIONS.iter().any(|(c, _)| *c == first) {
+        return None;
+    }
+
+    // Unicode fraction suffix: "½", "1½"
+    if let Some(last) = s.chars().last() {
+        if let Some((_, frac)) = UNICODE_FRACTIONS.iter().find(|(c, _)| *c == last) {
+            let whole = &s[..s.len() - last.len_utf8()];
+            let whole: f64 = if whole.is_empty() { 0.0 } else { whole.parse().ok()? };
+            return Some(whole + frac);
+        }
+    }
+
+    // Slash fraction: "1-1/2", "1 1/2", "1/2"
+    if let Some((rest, den)) = s.split_once('/') {
+        let (whole, num) = match rest.rsplit_once(['-', ' ']) {
+            Some((whole, num)) => (whole.parse::<f64>().ok()?, num),
+            None => (0.0, rest),
+        };
+        let num: f64 = num.parse().ok()?;
+        let den: f64 = den.parse().ok()?;
+        if den == 0.0 {
+            return None;
         }
+        return Some(whole + num / den);
     }
+
+    s.parse().ok()
 }
 
-/// Parse a denomination string into cents for sorting (e.g., "1¢" -> 1, "$1.00" -> 100)
-fn denomination_to_cents(denom: &str) -> u64 {
-    if denom.starts_with('$') {
-        // Parse dollar amount
-        let amount_str = denom.trim_start_matches('$');
-        if let Ok(dollars) = amount_str.parse::<f64>() {
-            return (dollars * 100.0) as u64;
+/// Parse a denomination string into cents for sorting
+/// (e.g., "1¢" -> 1, "1½¢" -> 1.5, "1-1/2¢" -> 1.5, "$1.00" -> 100, "$1,000" -> 100000)
+fn denomination_to_cents(denom: &str) -> f64 {
+    if let Some(amount_str) = denom.strip_prefix('$') {
+        // Parse dollar amount, ignoring thousands separators
+        if let Ok(dollars) = amount_str.replace(',', "").parse::<f64>() {
+            return dollars * 100.0;
         }
-    } else if denom.ends_with('¢') {
-        // Parse cent amount
-        let cents_str = denom.trim_end_matches('¢');
-        if let Ok(cents) = cents_str.parse::<u64>() {
+    } else if let Some(cents_str) = denom.strip_suffix('¢') {
+        // Parse cent amount, including fractional cents
+        if let Some(cents) = parse_fractional_cents(cents_str) {
             return cents;
         }
     }
-    u64::MAX // Unknown format sorts last
+    f64::MAX // Unknown format sorts last
 }
 
 /// Get sort key for a stamp within its category (for denominated stamps, sort by value)
-fn stamp_sort_key(stamp: &Stamp) -> u64 {
+fn stamp_sort_key(stamp: &Stamp) -> f64 {
     if let Some(denom) = extract_denomination(&stamp.name) {
         denomination_to_cents(&denom)
     } else if let Some(rate) = stamp.rate {
         // Include extra_cost for semipostals
         let total = rate + stamp.extra_cost.unwrap_or(0.0);
-        (total * 100.0) as u64
+        total * 100.0
     } else {
-        u64::MAX
+        f64::MAX
+    }
+}
+
+/// Split a raw `issue_location` into separate first-day locations
+///
+/// Locations are separated by `;`. Commas only separate locations when the text is
+/// unambiguously a list of "City, ST" pairs, so "Kansas City, MO, Chicago, IL" becomes
+/// two entries while "Sarasota, Florida" and "Berlin, Germany" stay whole.
+/// "Nationwide" is kept as its own bucket.
+fn parse_issue_locations(raw: &str) -> Vec<String> {
+    let raw = raw.trim();
+    if raw.is_empty() || raw.starts_with("TBA") {
+        return Vec::new();
+    }
+
+    let is_state_code = |s: &str| s.len() == 2 && s.chars().all(|c| c.is_ascii_uppercase());
+
+    let mut locations: Vec<String> = Vec::new();
+    for part in raw.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        let tokens: Vec<&str> = part.split(',').map(str::trim).collect();
+        let city_state_pairs = tokens.len() > 2
+            && tokens.len().is_multiple_of(2)
+            && tokens
+                .chunks(2)
+                .all(|pair| !pair[0].is_empty() && is_state_code(pair[1]));
+        if city_state_pairs {
+            locations.extend(tokens.chunks(2).map(|pair| pair.join(", ")));
+        } else {
+            locations.push(part.to_string());
+        }
+    }
+
+    for location in &mut locations {
+        if location
+            .trim_end_matches('.')
+            .eq_ignore_ascii_case("nationwide")
+        {
+            *location = "Nationwide".to_string();
+        }
     }
+    locations.dedup();
+    locations
 }
 
-/// Extract denomination from stamp name (e.g., "1¢ Apples" -> "1c", "$1 Liberty" -> "$1")
+/// Extract denomination from stamp name (e.g., "1¢ Apples" -> "1¢", "1½¢ Gallatin" -> "1½¢", "$1 Liberty" -> "$1")
 fn extract_denomination(name: &str) -> Option<String> {
     // Check for dollar prefix
     if name.starts_with('$') {
         if let Some(space_idx) = name.find(' ') {
             let amount = &name[1..space_idx];
-            if amount.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            if amount
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '.' || c == ',')
+            {
                 return Some(format!("${}", amount));
             }
         }
     }
 
-    // Check for cent prefix
-    let mut chars = name.chars().peekable();
-    let mut digits = String::new();
-    while let Some(&c) = chars.peek() {
-        if c.is_ascii_digit() {
-            digits.push(c);
-            chars.next();
-        } else {
-            break;
-        }
-    }
-    if !digits.is_empty() {
-        if let Some(next) = chars.next() {
-            if next == '¢' || (next == 'c' && chars.peek() == Some(&' ')) {
-                return Some(format!("{}¢", digits));
-            }
-        }
-    }
-
-    None
+    // Check for cent prefix ("1¢", "1½¢", "1-1/2¢", or "10c " spelled with a plain c)
+    let (token, rest) = name.split_once(' ').unwrap_or((name, ""));
+    let amount = token
+        .strip_suffix('¢')
+        .or_else(|| token.strip_suffix('c').filter(|_| !rest.is_empty()))?;
+    parse_fractional_cents(amount)?;
+    Some(format!("{}¢", amount))
 }
 
 /// Format rate as display string
@@ -315,6 +556,84 @@ fn format_rate(rate: f64) -> String {
     }
 }
 
+/// Value of a forever stamp's rate type on `date`, if there's a rate history for it
+///
+/// Global Forever and Nonmachineable have no history and keep their scraped rate.
+fn forever_rate_on(rates: &PostalRates, rate_type: &str, date: NaiveDate) -> Option<f64> {
+    match rate_type {
+        "Forever" | "Semipostal" | "First Class" => rates.letter.rate_on_date(date).ok(),
+        _ => rates.rate_for_type(rate_type, date).ok(),
+    }
+}
+
+/// Re-price forever stamps at `date` instead of the rate recorded when they were scraped
+fn apply_forever_rates(stamps: &mut [Stamp], rates: &PostalRates, date: NaiveDate) {
+    for stamp in stamps.iter_mut().filter(|s| s.forever && s.rate.is_some()) {
+        if let Some(rate) = stamp
+            .rate_type
+            .as_deref()
+            .and_then(|rt| forever_rate_on(rates, rt, date))
+        {
+            stamp.rate = Some(rate);
+        }
+    }
+}
+
+/// Describe what a denominated stamp is worth toward today's 1oz letter rate
+fn value_today_display(face_value: f64, letter_rate: f64) -> String {
+    let face = format_rate(face_value);
+    if face_value >= letter_rate {
+        format!(
+            "face value: {} (covers the ${:.2} letter rate)",
+            face, letter_rate
+        )
+    } else {
+        format!(
+            "face value: {} (still usable as {} toward the ${:.2} rate)",
+            face, face, letter_rate
+        )
+    }
+}
+
+/// Number of leading spaces on a line
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Read a `"""` block starting at `start`, returning the text and the next line index
+///
+/// The block ends at the first non-blank line indented no deeper than the key.
+/// The first content line sets the base indent, and exactly that much is
+/// stripped from each line so blank lines and nested indentation survive.
+fn parse_multiline(lines: &[&str], start: usize, key_indent: usize) -> (String, usize) {
+    let mut block: Vec<&str> = Vec::new();
+    let mut base_indent: Option<usize> = None;
+    let mut i = start;
+
+    while i < lines.len() {
+        let line = lines[i];
+        if line.trim().is_empty() {
+            block.push("");
+            i += 1;
+            continue;
+        }
+        let indent = indent_of(line);
+        if indent <= key_indent {
+            break;
+        }
+        let base = *base_indent.get_or_insert(indent);
+        block.push(&line[indent.min(base)..]);
+        i += 1;
+    }
+
+    // Blank lines between the block and the next key belong to neither
+    while block.last() == Some(&"") {
+        block.pop();
+    }
+
+    (block.join("\n"), i)
+}
+
 /// Simple CONL parser
 fn parse_conl(content: &str) -> Result<BTreeMap<String, ConlValue>> {
     let mut result = BTreeMap::new();
@@ -338,21 +657,9 @@ fn parse_conl(content: &str) -> Result<BTreeMap<String, ConlValue>> {
 
             // Check for multiline string
             if value.starts_with("\"\"\"") {
-                let mut multiline = String::new();
-                i += 1;
-                while i < lines.len() {
-                    let ml_line = lines[i];
-                    // End when we hit a line that's not indented or is a new key
-                    if !ml_line.starts_with("  ") && !ml_line.trim().is_empty() {
-                        break;
-                    }
-                    if !multiline.is_empty() {
-                        multiline.push('\n');
-                    }
-                    multiline.push_str(ml_line.trim());
-                    i += 1;
-                }
+                let (multiline, next) = parse_multiline(&lines, i + 1, indent_of(line));
                 result.insert(key.to_string(), ConlValue::String(multiline));
+                i = next;
                 continue;
             }
 
@@ -528,6 +835,15 @@ impl ConlValue {
         }
     }
 
+    /// A single value or an array, as a list
+    fn to_strings(&self) -> Vec<String> {
+        match self {
+            ConlValue::String(s) => vec![s.clone()],
+            ConlValue::Array(a) => a.clone(),
+            _ => Vec::new(),
+        }
+    }
+
     fn as_object(&self) -> Option<&BTreeMap<String, ConlValue>> {
         if let ConlValue::Object(o) = self {
             Some(o)
@@ -580,14 +896,20 @@ fn load_stamp(conl_path: &Path) -> Result<Stamp> {
         .get("issue_date")
         .and_then(|v| v.as_str())
         .map(String::from);
+    let issue_locations = data
+        .get("issue_location")
+        .and_then(|v| v.as_str())
+        .map(parse_issue_locations)
+        .unwrap_or_default();
     let rate = data
         .get("rate")
         .and_then(|v| v.as_str())
         .and_then(|s| s.parse().ok());
+    // Older scrapes may use an alias ("Additional Postage"); match on the canonical name
     let rate_type = data
         .get("rate_type")
         .and_then(|v| v.as_str())
-        .map(String::from);
+        .map(|rt| RateType::canonical_str(rt).to_string());
     let extra_cost = data
         .get("extra_cost")
         .and_then(|v| v.as_str())
@@ -606,6 +928,11 @@ fn load_stamp(conl_path: &Path) -> Result<Stamp> {
         .get("series")
         .and_then(|v| v.as_str())
         .map(String::from);
+    let tags = data
+        .get("tags")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
     let stamp_images = data
         .get("stamp_images")
         .and_then(|v| v.as_array())
@@ -615,44 +942,64 @@ fn load_stamp(conl_path: &Path) -> Result<Stamp> {
         .get("sheet_image")
         .and_then(|v| v.as_str())
         .map(String::from);
+    let sheet_image_size = data
+        .get("sheet_image_size")
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.split_once('x'))
+        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
     let about = data.get("about").and_then(|v| v.as_str()).map(String::from);
     let background_color = data
         .get("background_color")
         .and_then(|v| v.as_str())
+        .filter(|c| {
+            let valid = parse_hex_color(c).is_some();
+            if !valid {
+                eprintln!(
+                    "Warning: Ignoring invalid background_color '{}' in {}",
+                    c,
+                    conl_path.display()
+                );
+            }
+            valid
+        })
+        .map(String::from);
+    let hidden = data
+        .get("hidden")
+        .and_then(|v| v.as_str())
+        .map(|s| s == "true")
+        .unwrap_or(false);
+    let canceled = data
+        .get("canceled")
+        .and_then(|v| v.as_str())
+        .map(|s| s == "true")
+        .unwrap_or(false);
+    let pair_with = data
+        .get("pair_with")
+        .and_then(|v| v.as_str())
         .map(String::from);
+    let shape = stamp_images
+        .first()
+        .and_then(|img| load_enrichment(year, &api_slug, img))
+        .and_then(|e| e.shape)
+        .map(|s| s.to_lowercase())
+        .filter(|s| STAMP_SHAPES.contains(&s.as_str()));
 
     // Parse credits
     let mut credits = Credits::default();
     if let Some(credits_obj) = data.get("credits").and_then(|v| v.as_object()) {
-        credits.art_director = credits_obj
-            .get("art_director")
-            .and_then(|v| v.as_str())
-            .map(String::from);
-        credits.artist = credits_obj
-            .get("artist")
-            .and_then(|v| v.as_str())
-            .map(String::from);
-        credits.designer = credits_obj
-            .get("designer")
-            .and_then(|v| v.as_str())
-            .map(String::from);
-        credits.typographer = credits_obj
-            .get("typographer")
-            .and_then(|v| v.as_str())
-            .map(String::from);
-        credits.photographer = credits_obj
-            .get("photographer")
-            .and_then(|v| v.as_str())
-            .map(String::from);
-        credits.illustrator = credits_obj
-            .get("illustrator")
-            .and_then(|v| v.as_str())
-            .map(String::from);
-        credits.sources = credits_obj
-            .get("sources")
-            .and_then(|v| v.as_array())
-            .cloned()
-            .unwrap_or_default();
+        let names = |role: &str| {
+            credits_obj
+                .get(role)
+                .map(ConlValue::to_strings)
+                .unwrap_or_default()
+        };
+        credits.art_director = names("art_director");
+        credits.artist = names("artist");
+        credits.designer = names("designer");
+        credits.typographer = names("typographer");
+        credits.photographer = names("photographer");
+        credits.illustrator = names("illustrator");
+        credits.sources = names("sources");
     }
 
     // Parse products
@@ -707,6 +1054,10 @@ fn load_stamp(conl_path: &Path) -> Result<Stamp> {
                         .get("sided")
                         .and_then(|v| v.as_str())
                         .and_then(|s| s.parse().ok()),
+                    panes: meta
+                        .get("panes")
+                        .and_then(|v| v.as_str())
+                        .and_then(|s| s.parse().ok()),
                 });
 
             products.push(Product {
@@ -731,30 +1082,45 @@ fn load_stamp(conl_path: &Path) -> Result<Stamp> {
         url,
         year,
         issue_date,
+        issue_locations,
         rate,
         rate_type,
         extra_cost,
         forever,
         stamp_type,
         series,
+        tags,
         stamp_images,
         sheet_image,
+        sheet_image_size,
         credits,
         about,
         products,
         background_color,
+        hidden,
+        canceled,
+        shape,
+        pair_with,
     })
 }
 
 /// Load all stamps from the data directory
-fn load_all_stamps() -> Result<Vec<Stamp>> {
-    let mut stamps = Vec::new();
-    let data_dir = Path::new(DATA_DIR);
+///
+/// Stamps marked `hidden` via overrides are always skipped, as are years
+/// outside the options' year window. Stamps with a `HIDDEN_RATE_TYPES` rate
+/// type are skipped unless `include_hidden` is set, as are any in
+/// `exclude_rate_types`. Stamps without products are skipped when
+/// `only_with_products` is set.
+fn load_all_stamps(options: &GenerateOptions) -> Result<Vec<Stamp>> {
+    let data_dir = options.data_dir.as_path();
 
     if !data_dir.exists() {
-        return Ok(stamps);
+        return Ok(Vec::new());
     }
 
+    // Keyed by slug so two CONL files resolving to the same slug don't both produce pages
+    let mut by_slug: HashMap<String, (SystemTime, PathBuf, Stamp)> = HashMap::new();
+
     for year_entry in fs::read_dir(data_dir)? {
         let year_entry = year_entry?;
         let year_path = year_entry.path();
@@ -769,8 +1135,8 @@ fn load_all_stamps() -> Result<Vec<Stamp>> {
             Err(_) => continue,
         };
 
-        // Skip years before MIN_YEAR
-        if year < MIN_YEAR {
+        // Skip years outside the requested window
+        if !options.includes_year(year) {
             continue;
         }
 
@@ -789,13 +1155,31 @@ fn load_all_stamps() -> Result<Vec<Stamp>> {
 
             match load_stamp(&conl_path) {
                 Ok(stamp) => {
-                    // Filter out hidden rate types
-                    if let Some(ref rt) = stamp.rate_type {
-                        if HIDDEN_RATE_TYPES.contains(&rt.as_str()) {
-                            continue;
+                    let mtime = fs::metadata(&conl_path)
+                        .and_then(|m| m.modified())
+                        .unwrap_or(SystemTime::UNIX_EPOCH);
+                    match by_slug.get(&stamp.slug) {
+                        // Keep the newer CONL file
+                        Some((existing_mtime, existing_path, _)) if mtime <= *existing_mtime => {
+                            eprintln!(
+                                "Warning: Duplicate slug '{}': keeping {}, dropping {}",
+                                stamp.slug,
+                                existing_path.display(),
+                                conl_path.display()
+                            );
+                        }
+                        existing => {
+                            if let Some((_, existing_path, _)) = existing {
+                                eprintln!(
+                                    "Warning: Duplicate slug '{}': keeping {}, dropping {}",
+                                    stamp.slug,
+                                    conl_path.display(),
+                                    existing_path.display()
+                                );
+                            }
+                            by_slug.insert(stamp.slug.clone(), (mtime, conl_path, stamp));
                         }
                     }
-                    stamps.push(stamp);
                 }
                 Err(e) => {
                     eprintln!("Warning: Failed to load {}: {}", conl_path.display(), e);
@@ -804,17 +1188,69 @@ fn load_all_stamps() -> Result<Vec<Stamp>> {
         }
     }
 
-    // Sort by year (desc), then issue_date (desc), then name
+    let mut stamps: Vec<Stamp> = by_slug.into_values().map(|(_, _, stamp)| stamp).collect();
+
+    // Before filtering, so a sibling dropped below doesn't look like a one-sided pairing
+    validate_pairs(&mut stamps);
+
+    let mut stamps: Vec<Stamp> = stamps
+        .into_iter()
+        // Filter out stamps suppressed by override
+        .filter(|stamp| !stamp.hidden)
+        // Filter out hidden rate types
+        .filter(|stamp| {
+            options.include_hidden
+                || !stamp
+                    .rate_type
+                    .as_deref()
+                    .is_some_and(|rt| HIDDEN_RATE_TYPES.contains(&rt))
+        })
+        // Filter out rate types excluded on the command line
+        .filter(|stamp| {
+            !stamp
+                .rate_type
+                .as_ref()
+                .is_some_and(|rt| options.exclude_rate_types.contains(rt))
+        })
+        // Filter out discontinued stamps when only purchasable ones are wanted
+        .filter(|stamp| !options.only_with_products || !stamp.is_discontinued())
+        .filter(|stamp| !options.hide_upcoming || !stamp.is_upcoming(options.as_of))
+        .collect();
+
+    // Sort by year (desc), then issue_date (desc), then name, then slug
     stamps.sort_by(|a, b| {
         b.year
             .cmp(&a.year)
             .then_with(|| b.issue_date.cmp(&a.issue_date))
             .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.slug.cmp(&b.slug))
     });
 
     Ok(stamps)
 }
 
+/// Drop `pair_with` links that aren't reciprocated by the sibling stamp
+fn validate_pairs(stamps: &mut [Stamp]) {
+    let pairs: HashMap<String, Option<String>> = stamps
+        .iter()
+        .map(|s| (s.slug.clone(), s.pair_with.clone()))
+        .collect();
+
+    for stamp in stamps.iter_mut() {
+        let Some(sibling) = &stamp.pair_with else {
+            continue;
+        };
+        let reciprocal = pairs.get(sibling).and_then(|p| p.as_deref());
+        if reciprocal != Some(stamp.slug.as_str()) {
+            eprintln!(
+                "Warning: {} has pair_with '{}' but the pairing isn't mutual; ignoring",
+                stamp.slug, sibling
+            );
+            stamp.pair_with = None;
+        }
+    }
+}
+
 // HTML generation helpers
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -824,6 +1260,112 @@ fn html_escape(s: &str) -> String {
         .replace('\'', "&#39;")
 }
 
+/// Strip comments and insignificant whitespace from CSS
+fn minify_css(css: &str) -> String {
+    // Drop /* ... */ comments
+    let mut without_comments = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        without_comments.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    without_comments.push_str(rest);
+
+    // Collapse whitespace, then drop it next to punctuation where it's insignificant
+    let collapsed = without_comments
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut out = String::with_capacity(collapsed.len());
+    let chars: Vec<char> = collapsed.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let prev = out.chars().last();
+            let next = chars.get(i + 1).copied();
+            let tight = |ch: Option<char>| matches!(ch, Some('{' | '}' | ';' | ','));
+            if tight(prev) || tight(next) || prev == Some(':') {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out.replace(";}", "}")
+}
+
+/// Collapse whitespace runs in generated HTML to a single space
+///
+/// `<script>` contents are kept verbatim and `<style>` contents go through
+/// `minify_css`.
+fn minify_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    loop {
+        let next_block = ["<script", "<style"]
+            .iter()
+            .filter_map(|tag| rest.find(tag).map(|i| (i, *tag)))
+            .min();
+        let Some((start, tag)) = next_block else {
+            out.push_str(&collapse_whitespace(rest));
+            break;
+        };
+        out.push_str(&collapse_whitespace(&rest[..start]));
+
+        let close = format!("</{}>", &tag[1..]);
+        let Some(open_end) = rest[start..].find('>').map(|i| start + i + 1) else {
+            out.push_str(&rest[start..]);
+            break;
+        };
+        let close_start = rest[open_end..]
+            .find(&close)
+            .map_or(rest.len(), |i| open_end + i);
+        out.push_str(&rest[start..open_end]);
+        if tag == "<style" {
+            out.push_str(&minify_css(&rest[open_end..close_start]));
+        } else {
+            out.push_str(&rest[open_end..close_start]);
+        }
+        rest = &rest[close_start..];
+    }
+    out.trim().to_string()
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut prev_was_space = false;
+    for c in s.chars() {
+        if c.is_ascii_whitespace() {
+            if !prev_was_space {
+                out.push(' ');
+            }
+            prev_was_space = true;
+        } else {
+            out.push(c);
+            prev_was_space = false;
+        }
+    }
+    out
+}
+
+/// Minify every generated HTML file under `dir`
+fn minify_output(dir: &Path) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_symlink() {
+            continue;
+        }
+        if path.is_dir() {
+            minify_output(&path)?;
+        } else if path.extension().is_some_and(|e| e == "html") {
+            let html = fs::read_to_string(&path)?;
+            fs::write(&path, minify_html(&html))?;
+        }
+    }
+    Ok(())
+}
+
 fn markdown_to_html(md: &str) -> String {
     // Simple markdown to HTML conversion
     let mut html = String::new();
@@ -946,10 +1488,15 @@ header nav a {
     transition: color 0.2s;
 }
 
-header nav a:hover {
+header nav a:hover, header nav a[aria-current="page"] {
     color: white;
 }
 
+header nav a[aria-current="page"] {
+    text-decoration: underline;
+    text-underline-offset: 4px;
+}
+
 /* Main content */
 main {
     padding: 48px 0;
@@ -1012,6 +1559,21 @@ h3 {
     padding: 16px;
 }
 
+/* Non-rectangular stamps fill more of the frame instead of floating in it */
+.stamp-card.shape-circular .stamp-card-image img,
+.stamp-card.shape-square .stamp-card-image img,
+.stamp-card.shape-triangle .stamp-card-image img {
+    padding: 8px;
+}
+
+.stamp-card.shape-circular .stamp-card-image img {
+    border-radius: 50%;
+}
+
+.stamp-card.shape-portrait .stamp-card-image img {
+    padding: 12px 16px;
+}
+
 .stamp-card-content {
     padding: 16px;
 }
@@ -1028,6 +1590,17 @@ h3 {
     color: var(--text-muted);
 }
 
+/* Cards on a custom background_color take its computed text color */
+.stamp-card.custom-bg .stamp-card-title,
+.stamp-card.custom-bg .stamp-card-meta,
+.stamp-card.custom-bg .stamp-card-type {
+    color: inherit;
+}
+
+.stamp-card.custom-bg .stamp-card-meta {
+    opacity: 0.8;
+}
+
 .stamp-card-badge {
     padding: 0 16px 16px;
     text-align: right;
@@ -1136,6 +1709,8 @@ h3 {
     box-shadow: var(--shadow);
     padding: 24px;
     display: flex;
+    flex-direction: column;
+    gap: 12px;
     align-items: center;
     justify-content: center;
 }
@@ -1146,6 +1721,11 @@ h3 {
     object-fit: contain;
 }
 
+.sheet-resolution {
+    font-size: 0.875rem;
+    color: var(--text-muted);
+}
+
 /* Mobile carousel for thumbnails */
 @media (max-width: 768px) {
     .stamp-thumbnails {
@@ -1191,6 +1771,14 @@ h3 {
     color: var(--text);
 }
 
+.stamp-pair {
+    margin-top: 16px;
+}
+
+.stamp-pair a {
+    color: var(--primary);
+}
+
 .stamp-meta-grid {
     display: grid;
     grid-template-columns: auto 1fr;
@@ -1321,6 +1909,49 @@ h3 {
     margin-bottom: 0;
 }
 
+/* Previous/next stamp navigation */
+.stamp-pager {
+    display: flex;
+    gap: 16px;
+    margin-top: 48px;
+    padding-top: 24px;
+    border-top: 1px solid var(--border);
+}
+
+.stamp-pager a {
+    color: var(--primary);
+    text-decoration: none;
+    font-weight: 500;
+}
+
+.stamp-pager a:hover {
+    text-decoration: underline;
+}
+
+.stamp-pager-next {
+    margin-left: auto;
+    text-align: right;
+}
+
+/* Related stamps (mini cards) */
+.related-section {
+    margin-top: 48px;
+}
+
+.related-section .stamp-grid {
+    grid-template-columns: repeat(auto-fill, minmax(160px, 1fr));
+    gap: 16px;
+    margin-bottom: 0;
+}
+
+.related-section .stamp-card-content {
+    padding: 8px 12px;
+}
+
+.related-section .stamp-card-title {
+    font-size: 0.875rem;
+}
+
 /* Year navigation */
 .year-nav {
     display: grid;
@@ -1347,19 +1978,37 @@ h3 {
     color: white;
 }
 
-/* Section divider */
-.section-divider {
-    margin: 48px 0;
-    border: 0;
-    border-top: 1px solid var(--border);
-}
-
-/* Breadcrumb */
-.breadcrumb {
+/* Jump links to sections within a page */
+.section-nav {
     display: flex;
-    gap: 8px;
+    flex-wrap: wrap;
+    gap: 16px;
     margin-bottom: 24px;
-    font-size: 0.875rem;
+}
+
+.section-nav a {
+    color: var(--primary);
+    text-decoration: none;
+    font-weight: 500;
+}
+
+.section-nav a:hover {
+    text-decoration: underline;
+}
+
+/* Section divider */
+.section-divider {
+    margin: 48px 0;
+    border: 0;
+    border-top: 1px solid var(--border);
+}
+
+/* Breadcrumb */
+.breadcrumb {
+    display: flex;
+    gap: 8px;
+    margin-bottom: 24px;
+    font-size: 0.875rem;
     color: var(--text-muted);
 }
 
@@ -1388,6 +2037,13 @@ h3 {
 .category-badge.postcard { background: #fef3c7; color: #92400e; }
 .category-badge.additional { background: #e9d8fd; color: #553c9a; }
 .category-badge.denominated { background: #fed7e2; color: #97266d; }
+.category-badge.nonmachinable { background: #bee3f8; color: #2a4365; }
+.category-badge.priority { background: #fed7d7; color: #9b2c2c; }
+.category-badge.definitive { background: #c6f6d5; color: #276749; }
+.category-badge.presorted { background: #e2e8f0; color: #4a5568; }
+
+a.category-badge { text-decoration: none; }
+a.category-badge:hover { filter: brightness(0.95); }
 
 /* People index */
 .people-grid {
@@ -1422,6 +2078,28 @@ h3 {
     color: var(--text-muted);
 }
 
+/* Series cover images */
+.series-cover {
+    display: block;
+    width: 100%;
+    height: 140px;
+    object-fit: contain;
+    margin-bottom: 12px;
+}
+
+.series-description {
+    max-width: 720px;
+    margin-bottom: 8px;
+}
+
+.series-banner {
+    display: block;
+    max-width: 100%;
+    max-height: 240px;
+    object-fit: contain;
+    margin-bottom: 16px;
+}
+
 /* Footer */
 footer {
     background: var(--primary);
@@ -1450,6 +2128,14 @@ footer a {
     font-weight: 600;
 }
 
+.discontinued-label.available {
+    background: #22543d;
+}
+
+.discontinued-label.upcoming {
+    background: var(--primary-light);
+}
+
 /* Role badges for credits pages */
 .role-badge {
     display: inline-block;
@@ -1478,66 +2164,111 @@ footer a {
 }
 
 /// Generate page header HTML
-fn page_header(title: &str, current_path: &str) -> String {
+///
+/// `page_path` is the page's URL path: it marks the matching nav item active
+/// and, with a `base_url`, becomes the canonical link.
+fn page_header(site: &SiteConfig, title: &str, page_path: &str) -> String {
+    // (path, label, title)
     let nav_items = [
-        ("/forever-stamps/", "Forever"),
-        ("/postcard-forever-stamps/", "Postcard"),
-        ("/global-forever-stamps/", "Global"),
-        ("/additional-postage-forever-stamps/", "Additional"),
-        ("/denominated-postage-stamps/", "Denominated"),
-        ("/cards/", "Cards"),
-        ("/envelopes/", "Envelopes"),
-        ("/series/", "Series"),
-        ("/credits/", "Credits"),
+        (
+            "/forever-stamps/",
+            "Forever",
+            "Forever stamps for 1oz First-Class letters",
+        ),
+        (
+            "/postcard-forever-stamps/",
+            "Postcard",
+            "Postcard Forever stamps",
+        ),
+        (
+            "/global-forever-stamps/",
+            "Global",
+            "Global Forever stamps for international mail",
+        ),
+        (
+            "/additional-postage-forever-stamps/",
+            "Additional",
+            "Additional ounce, two and three ounce, and nonmachinable stamps",
+        ),
+        (
+            "/denominated-postage-stamps/",
+            "Denominated",
+            "Stamps with a printed value",
+        ),
+        ("/cards/", "Cards", "Stamped cards"),
+        ("/envelopes/", "Envelopes", "Stamped envelopes"),
+        ("/series/", "Series", "Stamps grouped by series"),
+        ("/topics/", "Topics", "Stamps grouped by subject"),
+        (
+            "/credits/",
+            "Credits",
+            "Artists, designers and photographers",
+        ),
     ];
 
     let nav_html: String = nav_items
         .iter()
-        .map(|(path, label)| {
-            let active = if *path == current_path {
-                " class=\"active\""
+        .map(|(path, label, title)| {
+            let active = if *path == page_path {
+                r#" class="active" aria-current="page""#
             } else {
                 ""
             };
-            format!("<a href=\"{}\"{}>{}  </a>", path, active, label)
+            format!(
+                r#"<a href="{}" title="{}"{}>{}</a>"#,
+                path, title, active, label
+            )
         })
         .collect();
 
+    let canonical = match &site.base_url {
+        Some(base_url) => format!(
+            "\n    <link rel=\"canonical\" href=\"{}{}\">",
+            html_escape(base_url.trim_end_matches('/')),
+            page_path
+        ),
+        None => String::new(),
+    };
+
     format!(
         r#"<!DOCTYPE html>
-<html lang="en">
+<html lang="{}">
 <head>
     <meta charset="UTF-8">
     <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>{} - US Postage Stamps</title>
+    <title>{} - {}</title>{}
     <style>{}</style>
 </head>
 <body>
     <header>
         <div class="container">
-            <h1><a href="/">US Postage Stamps</a></h1>
+            <h1><a href="/">{}</a></h1>
             <nav>{}</nav>
         </div>
     </header>
     <main>
         <div class="container">
 "#,
+        html_escape(&site.lang),
         html_escape(title),
+        html_escape(&site.title),
+        canonical,
         css_styles(),
+        html_escape(&site.title),
         nav_html
     )
 }
 
 /// Generate page footer HTML
-fn page_footer() -> &'static str {
-    r#"
+fn page_footer(site: &SiteConfig) -> String {
+    // Not format!: the script below is full of braces
+    let footer = r#"
         </div>
     </main>
     <footer>
         <div class="container">
-            <p>Not affiliated with United States Postal Service.</p>
-            <p>This is a USPS fan project - Not responsible for errors or omissions.</p>
-            <p>Please see <a href="https://usps.com">USPS.com</a> for Official Rates, Regulations and Purchase.</p>
+            "#;
+    let script = r#"
         </div>
     </footer>
     <script>
@@ -1565,25 +2296,96 @@ fn page_footer() -> &'static str {
     </script>
 </body>
 </html>
-"#
+"#;
+    [footer, &site.footer_html, script].concat()
 }
 
 /// Map rate_type to category URL and display label for non-denominated stamps
 fn rate_type_to_category(rate_type: Option<&str>) -> Option<(&'static str, &'static str)> {
-    match rate_type {
-        Some("Forever") | Some("Semipostal") => Some(("forever-stamps", "Forever")),
-        Some("Additional Ounce")
-        | Some("Two Ounce")
-        | Some("Three Ounce")
-        | Some("Additional Postage") => {
-            Some(("additional-postage-forever-stamps", "Additional Postage"))
-        }
-        Some("Nonmachineable Surcharge") => {
-            Some(("non-machinable-forever-stamps", "Non-Machinable"))
+    rate_category(rate_type?).forever_page()
+}
+
+/// Site category for a `rate_type` string (aliases included)
+fn rate_category(rate_type: &str) -> RateCategory {
+    RateType::from_str(rate_type).category()
+}
+
+/// A stamp's category for its year page section and badge
+///
+/// First Class is denominated before 2011 and forever from then on, so unlike the
+/// rate type alone this follows the stamp's `forever` flag.
+fn stamp_rate_category(stamp: &Stamp) -> RateCategory {
+    match RateType::from_str(stamp.rate_type.as_deref().unwrap_or("")) {
+        RateType::FirstClass if stamp.forever => RateCategory::Forever,
+        rate_type => rate_type.category(),
+    }
+}
+
+/// Parse a `background_color` hex value ("4b5d2c" or "#4b5d2c") into RGB
+fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// WCAG relative luminance of an sRGB color, from 0.0 (black) to 1.0 (white)
+fn relative_luminance(rgb: [u8; 3]) -> f64 {
+    let linear = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
         }
-        Some("International") | Some("Global Forever") => Some(("global-forever-stamps", "Global")),
-        Some("Postcard") => Some(("postcard-forever-stamps", "Postcard")),
-        _ => None,
+    };
+    0.2126 * linear(rgb[0]) + 0.7152 * linear(rgb[1]) + 0.0722 * linear(rgb[2])
+}
+
+/// Black or white text, whichever has the higher contrast ratio on `background`
+fn text_color_for(background: [u8; 3]) -> &'static str {
+    let lum = relative_luminance(background);
+    let black_contrast = (lum + 0.05) / 0.05;
+    let white_contrast = 1.05 / (lum + 0.05);
+    if black_contrast >= white_contrast {
+        "#000000"
+    } else {
+        "#ffffff"
+    }
+}
+
+/// Inline style for a stamp's custom background with a readable text color
+///
+/// Empty when the stamp has no background color or it isn't valid hex.
+fn background_style(background_color: Option<&str>) -> String {
+    background_color
+        .and_then(parse_hex_color)
+        .map(|rgb| {
+            format!(
+                r#" style="background-color: #{:02x}{:02x}{:02x}; color: {}""#,
+                rgb[0],
+                rgb[1],
+                rgb[2],
+                text_color_for(rgb)
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// CSS class for a category's `.category-badge` pill, matching the category colors
+fn rate_category_badge_class(category: RateCategory) -> &'static str {
+    match category {
+        RateCategory::Forever => "forever",
+        RateCategory::AdditionalPostage => "additional",
+        RateCategory::Nonmachinable => "nonmachinable",
+        RateCategory::Global => "global",
+        RateCategory::Postcard => "postcard",
+        RateCategory::Priority => "priority",
+        RateCategory::Denominated => "definitive",
+        RateCategory::Presorted => "presorted",
+        RateCategory::Other => "denominated",
     }
 }
 
@@ -1616,7 +2418,7 @@ fn stamp_card_html(stamp: &Stamp, image_base: &str) -> String {
         // Show combined rate for semipostals with extra_cost
         let total_rate = rate + stamp.extra_cost.unwrap_or(0.0);
         let rate_str = format_rate(total_rate);
-        let available_class = if !stamp.products.is_empty() {
+        let available_class = if !stamp.is_discontinued() {
             " available"
         } else {
             ""
@@ -1643,8 +2445,20 @@ fn stamp_card_html(stamp: &Stamp, image_base: &str) -> String {
         String::new()
     };
 
+    let shape_class = stamp
+        .shape
+        .as_ref()
+        .map(|s| format!(" shape-{}", s))
+        .unwrap_or_default();
+    let bg_style = background_style(stamp.background_color.as_deref());
+    let bg_class = if bg_style.is_empty() {
+        ""
+    } else {
+        " custom-bg"
+    };
+
     format!(
-        r#"<div class="stamp-card">
+        r#"<div class="stamp-card{}{}"{}>
     <a href="/stamps/{}/">
         <div class="stamp-card-image">{}</div>
         <div class="stamp-card-content">
@@ -1655,6 +2469,9 @@ fn stamp_card_html(stamp: &Stamp, image_base: &str) -> String {
     </a>
     {}
 </div>"#,
+        shape_class,
+        bg_class,
+        bg_style,
         stamp.slug,
         image_html,
         html_escape(&stamp.name),
@@ -1664,12 +2481,97 @@ fn stamp_card_html(stamp: &Stamp, image_base: &str) -> String {
     )
 }
 
+/// Sort a year's stamps by issue order (issue_date asc, undated last, then name)
+fn sort_by_issue_order(stamps: &mut [&Stamp]) {
+    stamps.sort_by(|a, b| match (&a.issue_date, &b.issue_date) {
+        (Some(da), Some(db)) => da.cmp(db).then_with(|| a.name.cmp(&b.name)),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.name.cmp(&b.name),
+    });
+}
+
+/// Rank other stamps related to `stamp`: same series first, then shared artist/designer
+///
+/// Deduplicated, excludes `stamp` itself, and capped at `MAX_RELATED_STAMPS`.
+fn related_stamps<'a>(stamp: &Stamp, all_stamps: &'a [Stamp]) -> Vec<&'a Stamp> {
+    let creators: Vec<&str> = [&stamp.credits.artist, &stamp.credits.designer]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+
+    let same_series = all_stamps
+        .iter()
+        .filter(|s| stamp.series.is_some() && s.series == stamp.series);
+    let same_creator = all_stamps.iter().filter(|s| {
+        [&s.credits.artist, &s.credits.designer]
+            .into_iter()
+            .flatten()
+            .any(|c| creators.contains(&c.as_str()))
+    });
+
+    let mut related: Vec<&Stamp> = Vec::new();
+    for candidate in same_series.chain(same_creator) {
+        if candidate != stamp && !related.contains(&candidate) {
+            related.push(candidate);
+            if related.len() == MAX_RELATED_STAMPS {
+                break;
+            }
+        }
+    }
+    related
+}
+
+/// Generate previous/next links to the adjacent stamps in the year's issue order
+fn stamp_pager_html(stamp: &Stamp, year_stamps: &[&Stamp]) -> String {
+    let Some(pos) = year_stamps.iter().position(|s| s.slug == stamp.slug) else {
+        return String::new();
+    };
+    let prev = pos.checked_sub(1).and_then(|i| year_stamps.get(i));
+    let next = year_stamps.get(pos + 1);
+
+    if prev.is_none() && next.is_none() {
+        return String::new();
+    }
+
+    let mut html = String::from(r#"<nav class="stamp-pager">"#);
+    if let Some(prev) = prev {
+        html.push_str(&format!(
+            r#"<a href="/stamps/{}/" class="stamp-pager-prev" rel="prev">&larr; {}</a>"#,
+            prev.slug,
+            html_escape(&prev.name)
+        ));
+    }
+    if let Some(next) = next {
+        html.push_str(&format!(
+            r#"<a href="/stamps/{}/" class="stamp-pager-next" rel="next">{} &rarr;</a>"#,
+            next.slug,
+            html_escape(&next.name)
+        ));
+    }
+    html.push_str("</nav>");
+    html
+}
+
 /// Generate an individual stamp page
-fn generate_stamp_page(stamp: &Stamp, output_dir: &Path) -> Result<()> {
+///
+/// `year_stamps` is the stamp's year in issue order, used for previous/next links;
+/// `all_stamps` is searched for related stamps. `letter_rate` is today's 1oz
+/// letter rate, shown against denominated face values.
+fn generate_stamp_page(
+    stamp: &Stamp,
+    year_stamps: &[&Stamp],
+    all_stamps: &[Stamp],
+    letter_rate: Option<f64>,
+    today: NaiveDate,
+    site: &SiteConfig,
+    output_dir: &Path,
+) -> Result<()> {
     let page_dir = output_dir.join("stamps").join(&stamp.slug);
     fs::create_dir_all(&page_dir)?;
 
-    let mut html = page_header(&stamp.name, "");
+    let mut html = page_header(site, &stamp.name, &format!("/stamps/{}/", stamp.slug));
 
     // Breadcrumb
     html.push_str(&format!(
@@ -1693,11 +2595,7 @@ fn generate_stamp_page(stamp: &Stamp, output_dir: &Path) -> Result<()> {
     // Main image
     let main_image = stamp.stamp_images.first().or(stamp.sheet_image.as_ref());
     if let Some(img) = main_image {
-        let bg_style = stamp
-            .background_color
-            .as_ref()
-            .map(|c| format!(r#" style="background-color: #{}""#, c))
-            .unwrap_or_default();
+        let bg_style = background_style(stamp.background_color.as_deref());
         html.push_str(&format!(
             r#"<div class="stamp-main-image"{}>
     <img src="/images/{}/{}/{}" alt="{}">
@@ -1724,14 +2622,20 @@ fn generate_stamp_page(stamp: &Stamp, output_dir: &Path) -> Result<()> {
 
     // Sheet image in separate container
     if let Some(sheet) = &stamp.sheet_image {
-        let bg_style = stamp
-            .background_color
-            .as_ref()
-            .map(|c| format!(r#" style="background-color: #{}""#, c))
+        let bg_style = background_style(stamp.background_color.as_deref());
+        let src = format!("/images/{}/{}/{}", stamp.year, stamp.slug, sheet);
+        let resolution = stamp
+            .sheet_image_size
+            .map(|(width, height)| {
+                format!(
+                    r#"<a class="sheet-resolution" href="{}">Full resolution, {} × {} px</a>"#,
+                    src, width, height
+                )
+            })
             .unwrap_or_default();
         html.push_str(&format!(
-            r#"<div class="stamp-sheet-image"{}><img src="/images/{}/{}/{}" alt="Stamp sheet"></div>"#,
-            bg_style, stamp.year, stamp.slug, sheet
+            r#"<div class="stamp-sheet-image"{}><img src="{}" alt="Stamp sheet">{}</div>"#,
+            bg_style, src, resolution
         ));
     }
 
@@ -1761,6 +2665,13 @@ fn generate_stamp_page(stamp: &Stamp, output_dir: &Path) -> Result<()> {
         ));
     }
 
+    if !stamp.issue_locations.is_empty() {
+        html.push_str(&format!(
+            r#"<span class="stamp-meta-label">Issue Location</span><span>{}</span>"#,
+            html_escape(&stamp.issue_locations.join("; "))
+        ));
+    }
+
     if let Some(rate) = stamp.rate {
         let rate_display = if let Some(extra) = stamp.extra_cost {
             // Semipostal: show total with breakdown
@@ -1778,16 +2689,39 @@ fn generate_stamp_page(stamp: &Stamp, output_dir: &Path) -> Result<()> {
             r#"<span class="stamp-meta-label">Rate</span><span>{}</span>"#,
             rate_display
         ));
+
+        // Denominated stamps count their face value toward today's postage
+        if let (false, Some(letter_rate)) = (stamp.forever, letter_rate) {
+            html.push_str(&format!(
+                r#"<span class="stamp-meta-label">Value Today</span><span>{}</span>"#,
+                value_today_display(rate, letter_rate)
+            ));
+        }
     }
 
     if let Some(rate_type) = &stamp.rate_type {
         html.push_str(&format!(
-            r#"<span class="stamp-meta-label">Type</span><span><a href="/rates/{}/">{}</a></span>"#,
+            r#"<span class="stamp-meta-label">Type</span><span><a href="/rates/{}/" class="category-badge {}">{}</a></span>"#,
             slugify(rate_type),
+            rate_category_badge_class(stamp_rate_category(stamp)),
             html_escape(rate_type)
         ));
     }
 
+    let status = if stamp.canceled {
+        r#"<span class="discontinued-label">Announced but not issued</span>"#
+    } else if stamp.is_upcoming(today) {
+        r#"<span class="discontinued-label upcoming">Upcoming</span>"#
+    } else if stamp.is_discontinued() {
+        r#"<span class="discontinued-label">Discontinued</span>"#
+    } else {
+        r#"<span class="discontinued-label available">Currently Available</span>"#
+    };
+    html.push_str(&format!(
+        r#"<span class="stamp-meta-label">Status</span><span>{}</span>"#,
+        status
+    ));
+
     if let Some(series) = &stamp.series {
         html.push_str(&format!(
             r#"<span class="stamp-meta-label">Series</span><span><a href="/series/{}/">{}</a></span>"#,
@@ -1796,42 +2730,73 @@ fn generate_stamp_page(stamp: &Stamp, output_dir: &Path) -> Result<()> {
         ));
     }
 
-    // Credits
-    if let Some(ad) = &stamp.credits.art_director {
-        html.push_str(&format!(
-            r#"<span class="stamp-meta-label">Art Director</span><span><a href="/credits/{}/">{}</a></span>"#,
-            slugify(ad), html_escape(ad)
-        ));
-    }
-    if let Some(artist) = &stamp.credits.artist {
+    if !stamp.tags.is_empty() {
+        let links: Vec<String> = stamp
+            .tags
+            .iter()
+            .map(|tag| {
+                format!(
+                    r#"<a href="/topics/{}/">{}</a>"#,
+                    slugify(tag),
+                    html_escape(tag)
+                )
+            })
+            .collect();
         html.push_str(&format!(
-            r#"<span class="stamp-meta-label">Artist</span><span><a href="/credits/{}/">{}</a></span>"#,
-            slugify(artist), html_escape(artist)
+            r#"<span class="stamp-meta-label">Topics</span><span>{}</span>"#,
+            links.join(", ")
         ));
     }
-    if let Some(designer) = &stamp.credits.designer {
-        if stamp.credits.artist.as_deref() != Some(designer) {
-            html.push_str(&format!(
-                r#"<span class="stamp-meta-label">Designer</span><span><a href="/credits/{}/">{}</a></span>"#,
-                slugify(designer), html_escape(designer)
-            ));
+
+    // Credits
+    for (role, names) in stamp.credits.by_role() {
+        if matches!(role, "Typographer" | "Source") {
+            continue;
         }
-    }
-    if let Some(photographer) = &stamp.credits.photographer {
+        let label = if names.len() > 1 {
+            format!("{}s", role)
+        } else {
+            role.to_string()
+        };
+        let links: Vec<String> = names
+            .iter()
+            .map(|name| {
+                format!(
+                    r#"<a href="/credits/{}/">{}</a>"#,
+                    slugify(name),
+                    html_escape(name)
+                )
+            })
+            .collect();
         html.push_str(&format!(
-            r#"<span class="stamp-meta-label">Photographer</span><span><a href="/credits/{}/">{}</a></span>"#,
-            slugify(photographer), html_escape(photographer)
+            r#"<span class="stamp-meta-label">{}</span><span>{}</span>"#,
+            label,
+            links.join(", ")
         ));
     }
-    if let Some(illustrator) = &stamp.credits.illustrator {
+
+    html.push_str("</div>"); // stamp-meta-grid
+
+    // Same design issued at another rate
+    if let Some(sibling) = stamp
+        .pair_with
+        .as_ref()
+        .and_then(|slug| all_stamps.iter().find(|s| &s.slug == slug))
+    {
+        let sibling_rate = match sibling.rate {
+            Some(rate) => format_rate(rate + sibling.extra_cost.unwrap_or(0.0)),
+            None => sibling
+                .rate_type
+                .clone()
+                .unwrap_or_else(|| "other".to_string()),
+        };
         html.push_str(&format!(
-            r#"<span class="stamp-meta-label">Illustrator</span><span><a href="/credits/{}/">{}</a></span>"#,
-            slugify(illustrator), html_escape(illustrator)
+            r#"<p class="stamp-pair">See also the <a href="/stamps/{}/">{} version</a></p>"#,
+            sibling.slug,
+            html_escape(&sibling_rate)
         ));
     }
 
-    html.push_str("</div>"); // stamp-meta-grid
-
     // About
     if let Some(about) = &stamp.about {
         html.push_str(r#"<div class="stamp-about">"#);
@@ -1839,13 +2804,15 @@ fn generate_stamp_page(stamp: &Stamp, output_dir: &Path) -> Result<()> {
         html.push_str("</div>");
     }
 
-    // External links
-    html.push_str(r#"<div style="margin-top: 24px; padding-top: 24px; border-top: 1px solid var(--border);">"#);
-    html.push_str(&format!(
-        r#"<a href="{}" target="_blank" rel="noopener" style="color: var(--primary); margin-right: 16px;">View on StampsForever.com</a>"#,
-        stamp.url
-    ));
-    html.push_str("</div>");
+    // External links (stamps imported from CSV have no stampsforever.com page)
+    if !stamp.url.is_empty() {
+        html.push_str(r#"<div style="margin-top: 24px; padding-top: 24px; border-top: 1px solid var(--border);">"#);
+        html.push_str(&format!(
+            r#"<a href="{}" target="_blank" rel="noopener" style="color: var(--primary); margin-right: 16px;">View on StampsForever.com</a>"#,
+            stamp.url
+        ));
+        html.push_str("</div>");
+    }
 
     html.push_str("</div>"); // stamp-info
     html.push_str("</div>"); // stamp-detail
@@ -1881,10 +2848,15 @@ fn generate_stamp_page(stamp: &Stamp, output_dir: &Path) -> Result<()> {
                 html_escape(&display_title)
             ));
 
-            if let Some(price) = &product.price {
+            if let Some(price) = product.price.as_deref().map(normalize_price) {
+                // Show panes per press sheet alongside its price
+                let price_display = match product.metadata.as_ref().and_then(|m| m.panes) {
+                    Some(panes) => format!("{} ({} panes)", price, panes),
+                    None => price,
+                };
                 html.push_str(&format!(
                     r#"<div class="product-card-price">{}</div>"#,
-                    html_escape(price)
+                    html_escape(&price_display)
                 ));
             }
 
@@ -1902,7 +2874,22 @@ fn generate_stamp_page(stamp: &Stamp, output_dir: &Path) -> Result<()> {
         html.push_str("</div></section>");
     }
 
-    html.push_str(page_footer());
+    // Related stamps (same series, then same artist/designer)
+    let related = related_stamps(stamp, all_stamps);
+    if !related.is_empty() {
+        html.push_str(r#"<section class="related-section">"#);
+        html.push_str("<h2>Related Stamps</h2>");
+        html.push_str(r#"<div class="stamp-grid">"#);
+        for related_stamp in related {
+            html.push_str(&stamp_card_html(related_stamp, "/images"));
+        }
+        html.push_str("</div></section>");
+    }
+
+    // Previous/next stamp in issue order
+    html.push_str(&stamp_pager_html(stamp, year_stamps));
+
+    html.push_str(&page_footer(site));
 
     let page_path = page_dir.join("index.html");
     fs::write(&page_path, html)?;
@@ -1915,12 +2902,14 @@ fn generate_year_page(
     year: u32,
     stamps: &[&Stamp],
     all_years: &[u32],
+    today: NaiveDate,
+    site: &SiteConfig,
     output_dir: &Path,
 ) -> Result<()> {
     let page_dir = output_dir.join(year.to_string());
     fs::create_dir_all(&page_dir)?;
 
-    let mut html = page_header(&format!("{} Stamps", year), "");
+    let mut html = page_header(site, &format!("{} Stamps", year), &format!("/{}/", year));
 
     // Breadcrumb
     html.push_str(&format!(
@@ -1949,7 +2938,12 @@ fn generate_year_page(
     // Group by year page category with custom ordering
     let mut by_category: HashMap<YearPageCategory, Vec<&Stamp>> = HashMap::new();
     for stamp in stamps {
-        let cat = YearPageCategory::from_stamp(stamp);
+        // Upcoming stamps are grouped apart until they issue, whatever their type
+        let cat = if stamp.is_upcoming(today) {
+            YearPageCategory::Upcoming
+        } else {
+            YearPageCategory::from_stamp(stamp)
+        };
         by_category.entry(cat).or_default().push(stamp);
     }
 
@@ -1957,17 +2951,34 @@ fn generate_year_page(
     let mut categories: Vec<YearPageCategory> = by_category.keys().cloned().collect();
     categories.sort_by_key(|c| c.sort_order());
 
+    // Jump links to each category section
+    if categories.len() > 1 {
+        html.push_str(r#"<div class="section-nav">"#);
+        for cat in &categories {
+            html.push_str(&format!(
+                r##"<a href="#{}">{}</a>"##,
+                cat.anchor_id(),
+                cat.display_name()
+            ));
+        }
+        html.push_str("</div>");
+    }
+
     for cat in categories {
         if let Some(mut cat_stamps) = by_category.remove(&cat) {
             // Sort denominated stamps by value (ascending)
             if cat == YearPageCategory::Denominated {
-                cat_stamps.sort_by_key(|s| stamp_sort_key(s));
+                cat_stamps.sort_by(|a, b| stamp_sort_key(a).total_cmp(&stamp_sort_key(b)));
             }
             // Skip empty categories
             if cat_stamps.is_empty() {
                 continue;
             }
-            html.push_str(&format!("<h3>{}</h3>", cat.display_name()));
+            html.push_str(&format!(
+                r#"<h3 id="{}">{}</h3>"#,
+                cat.anchor_id(),
+                cat.display_name()
+            ));
             html.push_str(r#"<div class="stamp-grid">"#);
             for stamp in &cat_stamps {
                 html.push_str(&stamp_card_html(stamp, "/images"));
@@ -1984,7 +2995,7 @@ fn generate_year_page(
     }
     html.push_str("</div>");
 
-    html.push_str(page_footer());
+    html.push_str(&page_footer(site));
 
     let page_path = page_dir.join("index.html");
     fs::write(&page_path, html)?;
@@ -2011,12 +3022,18 @@ fn generate_category_page(
     filter_fn: impl Fn(&Stamp) -> bool,
     sort_mode: CategorySort,
     stamps: &[Stamp],
+    options: &GenerateOptions,
     output_dir: &Path,
 ) -> Result<()> {
+    let site = &options.site;
     let page_dir = output_dir.join(category);
     fs::create_dir_all(&page_dir)?;
 
-    let mut filtered: Vec<&Stamp> = stamps.iter().filter(|s| filter_fn(s)).collect();
+    // Canceled stamps only appear on their year page and their own page
+    let mut filtered: Vec<&Stamp> = stamps
+        .iter()
+        .filter(|s| !s.canceled && filter_fn(s))
+        .collect();
     let total_count = filtered.len();
 
     // Apply category-specific sorting
@@ -2041,7 +3058,7 @@ fn generate_category_page(
             filtered.sort_by(|a, b| {
                 let type_order = |rt: Option<&str>| -> u8 {
                     match rt {
-                        Some("Additional Ounce") | Some("Additional Postage") => 0,
+                        Some("Additional Ounce") => 0,
                         Some("Two Ounce") => 1,
                         Some("Three Ounce") => 2,
                         Some("Nonmachineable Surcharge") => 3,
@@ -2070,11 +3087,16 @@ fn generate_category_page(
         }
     }
 
+    // Upcoming stamps are neither available nor discontinued yet
+    let (upcoming, issued): (Vec<&Stamp>, Vec<&Stamp>) = filtered
+        .into_iter()
+        .partition(|s| s.is_upcoming(options.as_of));
+
     // Split into available (has products) and discontinued
     let (available, discontinued): (Vec<&Stamp>, Vec<&Stamp>) =
-        filtered.into_iter().partition(|s| !s.products.is_empty());
+        issued.into_iter().partition(|s| !s.is_discontinued());
 
-    let mut html = page_header(title, &format!("/{}/", category));
+    let mut html = page_header(site, title, &format!("/{}/", category));
 
     // Breadcrumb
     html.push_str(&format!(
@@ -2087,14 +3109,32 @@ fn generate_category_page(
     ));
 
     html.push_str(&format!("<h2>{}</h2>", title));
+    let mut counts = vec![format!("{} available", available.len())];
+    if !discontinued.is_empty() {
+        counts.push(format!("{} discontinued", discontinued.len()));
+    }
+    if !upcoming.is_empty() {
+        counts.push(format!("{} upcoming", upcoming.len()));
+    }
     html.push_str(&format!(
-        "<p style=\"margin-bottom: 24px; color: var(--text-muted);\">{} stamps ({} available, {} discontinued)</p>",
-        total_count, available.len(), discontinued.len()
+        "<p style=\"margin-bottom: 24px; color: var(--text-muted);\">{} stamps ({})</p>",
+        total_count,
+        counts.join(", ")
     ));
 
+    // Upcoming stamps
+    if !upcoming.is_empty() {
+        html.push_str(r#"<h3 id="upcoming">Upcoming</h3>"#);
+        html.push_str(r#"<div class="stamp-grid">"#);
+        for stamp in &upcoming {
+            html.push_str(&stamp_card_html(stamp, "/images"));
+        }
+        html.push_str("</div>");
+    }
+
     // Available stamps
     if !available.is_empty() {
-        html.push_str("<h3>Currently Available</h3>");
+        html.push_str(r#"<h3 id="available">Currently Available</h3>"#);
         html.push_str(r#"<div class="stamp-grid">"#);
         for stamp in &available {
             html.push_str(&stamp_card_html(stamp, "/images"));
@@ -2106,7 +3146,7 @@ fn generate_category_page(
     if !discontinued.is_empty() {
         html.push_str(r#"<hr class="section-divider">"#);
         html.push_str(r#"<div class="discontinued-section">"#);
-        html.push_str("<h3>Discontinued</h3>");
+        html.push_str(r#"<h3 id="discontinued">Discontinued</h3>"#);
         html.push_str(r#"<div class="stamp-grid">"#);
         for stamp in &discontinued {
             html.push_str(&stamp_card_html(stamp, "/images"));
@@ -2114,7 +3154,7 @@ fn generate_category_page(
         html.push_str("</div></div>");
     }
 
-    html.push_str(page_footer());
+    html.push_str(&page_footer(site));
 
     let page_path = page_dir.join("index.html");
     fs::write(&page_path, html)?;
@@ -2122,47 +3162,15 @@ fn generate_category_page(
     Ok(())
 }
 
-/// Slugify a name for URL use
-fn slugify(name: &str) -> String {
-    name.to_lowercase()
-        .chars()
-        .map(|c| if c.is_alphanumeric() { c } else { '-' })
-        .collect::<String>()
-        .split('-')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join("-")
-}
-
 /// Get roles for a person on a specific stamp
 fn get_roles_for_person(name: &str, stamp: &Stamp) -> Vec<&'static str> {
-    let mut roles = Vec::new();
-
-    if stamp.credits.art_director.as_deref() == Some(name) {
-        roles.push("Art Director");
-    }
-    if stamp.credits.artist.as_deref() == Some(name) {
-        roles.push("Artist");
-    }
-    if stamp.credits.designer.as_deref() == Some(name)
-        && stamp.credits.artist.as_deref() != Some(name)
-    {
-        roles.push("Designer");
-    }
-    if stamp.credits.photographer.as_deref() == Some(name) {
-        roles.push("Photographer");
-    }
-    if stamp.credits.illustrator.as_deref() == Some(name) {
-        roles.push("Illustrator");
-    }
-    if stamp.credits.typographer.as_deref() == Some(name) {
-        roles.push("Typographer");
-    }
-    if stamp.credits.sources.contains(&name.to_string()) {
-        roles.push("Source");
-    }
-
-    roles
+    stamp
+        .credits
+        .by_role()
+        .into_iter()
+        .filter(|(_, names)| names.iter().any(|n| *n == name))
+        .map(|(role, _)| role)
+        .collect()
 }
 
 /// Generate a stamp card with role badges
@@ -2204,10 +3212,16 @@ fn stamp_card_with_roles_html(stamp: &Stamp, roles: &[&str], image_base: &str) -
             format!(r#"<span class="role-badge {}">{}</span>"#, class, role)
         })
         .collect();
+    let bg_style = background_style(stamp.background_color.as_deref());
+    let bg_class = if bg_style.is_empty() {
+        ""
+    } else {
+        " custom-bg"
+    };
 
     format!(
-        r#"<div class="stamp-card">
-    <a href="/{}/{}/">
+        r#"<div class="stamp-card{}"{}>
+    <a href="/stamps/{}/">
         <div class="stamp-card-image">{}</div>
         <div class="stamp-card-content">
             <div class="stamp-card-title">{}</div>
@@ -2216,7 +3230,8 @@ fn stamp_card_with_roles_html(stamp: &Stamp, roles: &[&str], image_base: &str) -
         </div>
     </a>
 </div>"#,
-        stamp.year,
+        bg_class,
+        bg_style,
         stamp.slug,
         image_html,
         html_escape(&stamp.name),
@@ -2225,59 +3240,306 @@ fn stamp_card_with_roles_html(stamp: &Stamp, roles: &[&str], image_base: &str) -
     )
 }
 
-/// Generate credits index and individual pages
-fn generate_people_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
-    // Collect all people and their stamps (with roles tracking)
+/// Get all credited people on a stamp (designer is skipped when same as artist)
+fn stamp_people(stamp: &Stamp) -> Vec<&String> {
+    stamp
+        .credits
+        .by_role()
+        .into_iter()
+        .flat_map(|(_, names)| names)
+        .collect()
+}
+
+/// Collect all people and the stamps they are credited on
+fn collect_people(stamps: &[Stamp]) -> HashMap<String, Vec<&Stamp>> {
     let mut people: HashMap<String, Vec<&Stamp>> = HashMap::new();
 
     for stamp in stamps {
-        if let Some(name) = &stamp.credits.art_director {
-            people.entry(name.clone()).or_default().push(stamp);
-        }
-        if let Some(name) = &stamp.credits.artist {
+        for name in stamp_people(stamp) {
             people.entry(name.clone()).or_default().push(stamp);
         }
-        if let Some(name) = &stamp.credits.designer {
-            if stamp.credits.artist.as_deref() != Some(name) {
-                people.entry(name.clone()).or_default().push(stamp);
+    }
+
+    people
+}
+
+/// Person entry in credits.json
+#[derive(Serialize)]
+struct CreditsPerson {
+    name: String,
+    slug: String,
+    roles: Vec<&'static str>,
+    stamps: Vec<String>,
+}
+
+/// Co-occurrence edge in credits.json (two people credited on the same stamps)
+#[derive(Serialize)]
+struct CreditsEdge {
+    source: String,
+    target: String,
+    stamps: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct CreditsExport {
+    people: Vec<CreditsPerson>,
+    edges: Vec<CreditsEdge>,
+}
+
+/// Write output/credits.json with people, their roles, and collaboration edges
+fn generate_credits_json(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
+    let people = collect_people(stamps);
+
+    let mut export_people: Vec<CreditsPerson> = people
+        .iter()
+        .map(|(name, person_stamps)| {
+            let mut roles: Vec<&'static str> = Vec::new();
+            let mut slugs: Vec<String> = Vec::new();
+            for stamp in person_stamps {
+                for role in get_roles_for_person(name, stamp) {
+                    if !roles.contains(&role) {
+                        roles.push(role);
+                    }
+                }
+                if !slugs.contains(&stamp.slug) {
+                    slugs.push(stamp.slug.clone());
+                }
+            }
+            slugs.sort();
+            CreditsPerson {
+                name: name.clone(),
+                slug: slugify(name),
+                roles,
+                stamps: slugs,
+            }
+        })
+        .collect();
+    export_people.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Pairs of people appearing on the same stamp, keyed by (source, target) in name order
+    let mut edges: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+    for stamp in stamps {
+        let mut names = stamp_people(stamp);
+        names.sort();
+        names.dedup();
+        for (i, a) in names.iter().enumerate() {
+            for b in &names[i + 1..] {
+                let slugs = edges.entry(((*a).clone(), (*b).clone())).or_default();
+                if !slugs.contains(&stamp.slug) {
+                    slugs.push(stamp.slug.clone());
+                }
             }
-        }
-        if let Some(name) = &stamp.credits.photographer {
-            people.entry(name.clone()).or_default().push(stamp);
-        }
-        if let Some(name) = &stamp.credits.illustrator {
-            people.entry(name.clone()).or_default().push(stamp);
-        }
-        if let Some(name) = &stamp.credits.typographer {
-            people.entry(name.clone()).or_default().push(stamp);
-        }
-        for source in &stamp.credits.sources {
-            people.entry(source.clone()).or_default().push(stamp);
         }
     }
 
-    // Sort by name
-    let mut sorted_people: Vec<_> = people.into_iter().collect();
-    sorted_people.sort_by(|a, b| a.0.cmp(&b.0));
+    let export = CreditsExport {
+        people: export_people,
+        edges: edges
+            .into_iter()
+            .map(|((source, target), stamps)| CreditsEdge {
+                source,
+                target,
+                stamps,
+            })
+            .collect(),
+    };
 
-    // Generate index page
-    let credits_dir = output_dir.join("credits");
-    fs::create_dir_all(&credits_dir)?;
+    let json = serde_json::to_string_pretty(&export)?;
+    fs::write(output_dir.join("credits.json"), json)?;
 
-    let mut html = page_header("Credits", "/credits/");
+    Ok(())
+}
 
-    html.push_str(
-        r#"<nav class="breadcrumb">
-    <a href="/">Home</a> <span>/</span>
-    <span>Credits</span>
-</nav>
-"#,
-    );
+/// A stamp and the roles one person held on it, in people.json
+#[derive(Serialize)]
+struct PersonStampRoles {
+    slug: String,
+    name: String,
+    year: u32,
+    roles: Vec<&'static str>,
+}
 
-    html.push_str("<h2>Artists, Designers & Photographers</h2>");
-    html.push_str(&format!(
-        "<p style=\"margin-bottom: 24px; color: var(--text-muted);\">{} people</p>",
-        sorted_people.len()
+/// Person entry in people.json
+#[derive(Serialize)]
+struct PersonCredits {
+    name: String,
+    stamps: Vec<PersonStampRoles>,
+}
+
+/// Write output/people.json: per person (keyed by slug), each stamp and their roles on it
+///
+/// Names that share a slug ("Mary Jane Doe", "Mary-Jane Doe") are merged under the
+/// first in sorted order, with a warning.
+fn generate_people_json(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
+    // Sorted names, BTreeMap and sorted stamps keep the file stable across runs
+    let mut people: Vec<(String, Vec<&Stamp>)> = collect_people(stamps).into_iter().collect();
+    people.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut export: BTreeMap<String, PersonCredits> = BTreeMap::new();
+    for (name, person_stamps) in people {
+        let stamps = person_stamps.iter().map(|stamp| PersonStampRoles {
+            slug: stamp.slug.clone(),
+            name: stamp.name.clone(),
+            year: stamp.year,
+            roles: get_roles_for_person(&name, stamp),
+        });
+        let slug = slugify(&name);
+        match export.get_mut(&slug) {
+            Some(person) => {
+                eprintln!(
+                    "Warning: '{}' and '{}' share the slug '{}'; merging them in people.json",
+                    person.name, name, slug
+                );
+                person.stamps.extend(stamps);
+            }
+            None => {
+                let stamps = stamps.collect();
+                export.insert(slug, PersonCredits { name, stamps });
+            }
+        }
+    }
+
+    for person in export.values_mut() {
+        person.stamps.sort_by(|a, b| a.slug.cmp(&b.slug));
+        // A stamp credited under both spellings keeps the roles from each
+        person.stamps.dedup_by(|a, b| {
+            if a.slug != b.slug {
+                return false;
+            }
+            for role in a.roles.drain(..) {
+                if !b.roles.contains(&role) {
+                    b.roles.push(role);
+                }
+            }
+            true
+        });
+    }
+
+    let json = serde_json::to_string_pretty(&export)?;
+    fs::write(output_dir.join("people.json"), json)?;
+
+    Ok(())
+}
+
+/// Escape text for an iCalendar property value (RFC 5545 section 3.3.11)
+fn ical_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets, continuing with a leading space (RFC 5545 section 3.1)
+fn ical_fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+/// Write output/upcoming.ics: an all-day event for each stamp issued after `today`
+///
+/// Undated (TBA) and canceled stamps are left out. Events link to the stamp page
+/// when the site has a `base_url`. Returns the number of events.
+fn generate_calendar(
+    stamps: &[Stamp],
+    site: &SiteConfig,
+    output_dir: &Path,
+    today: NaiveDate,
+) -> Result<usize> {
+    let mut upcoming: Vec<(NaiveDate, &Stamp)> = stamps
+        .iter()
+        .filter(|s| !s.canceled)
+        .filter_map(|s| {
+            let date = NaiveDate::parse_from_str(s.issue_date.as_deref()?, "%Y-%m-%d").ok()?;
+            (date > today).then_some((date, s))
+        })
+        .collect();
+    upcoming.sort_by(|(a_date, a), (b_date, b)| a_date.cmp(b_date).then(a.name.cmp(&b.name)));
+
+    let base_url = site
+        .base_url
+        .as_deref()
+        .map(|url| url.trim_end_matches('/'));
+    let uid_host = base_url
+        .and_then(|url| url.split("://").nth(1))
+        .unwrap_or("usps-rates");
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//usps-rates//Upcoming stamp issues//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!(
+            "X-WR-CALNAME:{}",
+            ical_escape(&format!("{} - Upcoming", site.title))
+        ),
+    ];
+    for (date, stamp) in &upcoming {
+        let page = format!("/stamps/{}/", stamp.slug);
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@{}", stamp.slug, uid_host));
+        lines.push(format!("DTSTAMP:{}T000000Z", today.format("%Y%m%d")));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+        if let Some(end) = date.succ_opt() {
+            lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+        }
+        lines.push(format!("SUMMARY:{}", ical_escape(&stamp.name)));
+        if !stamp.issue_locations.is_empty() {
+            lines.push(format!(
+                "LOCATION:{}",
+                ical_escape(&stamp.issue_locations.join("; "))
+            ));
+        }
+        match base_url {
+            Some(base_url) => {
+                lines.push(format!("URL:{}{}", base_url, page));
+                lines.push(format!("DESCRIPTION:{}{}", base_url, page));
+            }
+            None => lines.push(format!("DESCRIPTION:{}", page)),
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let ics: String = lines.iter().map(|l| ical_fold(l) + "\r\n").collect();
+    fs::write(output_dir.join("upcoming.ics"), ics)?;
+    Ok(upcoming.len())
+}
+
+/// Generate credits index and individual pages
+fn generate_people_pages(stamps: &[Stamp], site: &SiteConfig, output_dir: &Path) -> Result<()> {
+    // Collect all people and their stamps (with roles tracking)
+    let people = collect_people(stamps);
+
+    // Sort by name
+    let mut sorted_people: Vec<_> = people.into_iter().collect();
+    sorted_people.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Generate index page
+    let credits_dir = output_dir.join("credits");
+    fs::create_dir_all(&credits_dir)?;
+
+    let mut html = page_header(site, "Credits", "/credits/");
+
+    html.push_str(
+        r#"<nav class="breadcrumb">
+    <a href="/">Home</a> <span>/</span>
+    <span>Credits</span>
+</nav>
+"#,
+    );
+
+    html.push_str("<h2>Artists, Designers & Photographers</h2>");
+    html.push_str(&format!(
+        "<p style=\"margin-bottom: 24px; color: var(--text-muted);\">{} people</p>",
+        sorted_people.len()
     ));
 
     html.push_str(r#"<div class="people-grid">"#);
@@ -2297,7 +3559,7 @@ fn generate_people_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
     }
     html.push_str("</div>");
 
-    html.push_str(page_footer());
+    html.push_str(&page_footer(site));
     fs::write(credits_dir.join("index.html"), html)?;
 
     // Generate individual person pages
@@ -2306,7 +3568,7 @@ fn generate_people_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
         let person_dir = credits_dir.join(&slug);
         fs::create_dir_all(&person_dir)?;
 
-        let mut html = page_header(name, "");
+        let mut html = page_header(site, name, &format!("/credits/{}/", slug));
 
         html.push_str(&format!(
             r#"<nav class="breadcrumb">
@@ -2336,15 +3598,53 @@ fn generate_people_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
         }
         html.push_str("</div>");
 
-        html.push_str(page_footer());
+        html.push_str(&page_footer(site));
         fs::write(person_dir.join("index.html"), html)?;
     }
 
     Ok(())
 }
 
+/// Pick a representative image for a series: the most recently issued stamp with one
+///
+/// Returns (year, slug, image filename), preferring the stamp image over the sheet.
+fn series_cover<'a>(series_stamps: &[&'a Stamp]) -> Option<(u32, &'a str, &'a str)> {
+    series_stamps
+        .iter()
+        .filter_map(|s| {
+            let img = s.stamp_images.first().or(s.sheet_image.as_ref())?;
+            Some((*s, img.as_str()))
+        })
+        .max_by(|(a, _), (b, _)| {
+            a.year
+                .cmp(&b.year)
+                .then_with(|| a.issue_date.cmp(&b.issue_date))
+                .then_with(|| b.name.cmp(&a.name))
+        })
+        .map(|(s, img)| (s.year, s.slug.as_str(), img))
+}
+
+/// Load series descriptions from `path` (series name -> description); a missing file has none
+fn load_series_descriptions(path: &Path) -> HashMap<String, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    match serde_conl::from_str(&content) {
+        Ok(descriptions) => descriptions,
+        Err(e) => {
+            eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+            HashMap::new()
+        }
+    }
+}
+
 /// Generate series index and individual series pages
-fn generate_series_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
+fn generate_series_pages(
+    stamps: &[Stamp],
+    descriptions: &HashMap<String, String>,
+    site: &SiteConfig,
+    output_dir: &Path,
+) -> Result<()> {
     // Collect all series and their stamps
     let mut series_map: HashMap<String, Vec<&Stamp>> = HashMap::new();
 
@@ -2366,7 +3666,7 @@ fn generate_series_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
     let series_dir = output_dir.join("series");
     fs::create_dir_all(&series_dir)?;
 
-    let mut html = page_header("Series", "/series/");
+    let mut html = page_header(site, "Series", "/series/");
 
     html.push_str(
         r#"<nav class="breadcrumb">
@@ -2385,19 +3685,29 @@ fn generate_series_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
     html.push_str(r#"<div class="people-grid">"#);
     for (series_name, series_stamps) in &sorted_series {
         let slug = slugify(series_name);
+        let cover_html = series_cover(series_stamps)
+            .map(|(year, stamp_slug, img)| {
+                format!(
+                    r#"<img class="series-cover" src="/images/{}/{}/{}" alt="" loading="lazy">"#,
+                    year, stamp_slug, img
+                )
+            })
+            .unwrap_or_default();
         html.push_str(&format!(
             r#"<a href="/series/{}/" class="person-link">
+    {}
     <div class="person-name">{}</div>
     <div class="person-count">{} stamps</div>
 </a>"#,
             slug,
+            cover_html,
             html_escape(series_name),
             series_stamps.len()
         ));
     }
     html.push_str("</div>");
 
-    html.push_str(page_footer());
+    html.push_str(&page_footer(site));
     fs::write(series_dir.join("index.html"), html)?;
 
     // Generate individual series pages
@@ -2414,7 +3724,7 @@ fn generate_series_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
                 .then_with(|| a.name.cmp(&b.name))
         });
 
-        let mut html = page_header(&series_name, "");
+        let mut html = page_header(site, &series_name, &format!("/series/{}/", slug));
 
         html.push_str(&format!(
             r#"<nav class="breadcrumb">
@@ -2426,7 +3736,22 @@ fn generate_series_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
             html_escape(&series_name)
         ));
 
+        if let Some((year, stamp_slug, img)) = series_cover(&series_stamps) {
+            html.push_str(&format!(
+                r#"<img class="series-banner" src="/images/{}/{}/{}" alt="{}">"#,
+                year,
+                stamp_slug,
+                img,
+                html_escape(&series_name)
+            ));
+        }
         html.push_str(&format!("<h2>{}</h2>", html_escape(&series_name)));
+        if let Some(description) = descriptions.get(&series_name) {
+            html.push_str(&format!(
+                r#"<p class="series-description">{}</p>"#,
+                html_escape(description)
+            ));
+        }
         html.push_str(&format!(
             "<p style=\"margin-bottom: 24px; color: var(--text-muted);\">{} stamps</p>",
             series_stamps.len()
@@ -2438,7 +3763,120 @@ fn generate_series_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
         }
         html.push_str("</div>");
 
-        html.push_str(page_footer());
+        html.push_str(&page_footer(site));
+        fs::write(page_dir.join("index.html"), html)?;
+    }
+
+    Ok(())
+}
+
+/// Generate topic index and individual topic pages under /topics/
+///
+/// Tags are grouped by slug, so spellings that differ only in case or
+/// punctuation share a page (named after the first spelling seen).
+fn generate_tag_pages(stamps: &[Stamp], site: &SiteConfig, output_dir: &Path) -> Result<()> {
+    // Collect all tags and their stamps, keyed by slug
+    let mut tag_map: HashMap<String, (&str, Vec<&Stamp>)> = HashMap::new();
+
+    for stamp in stamps {
+        for tag in &stamp.tags {
+            tag_map
+                .entry(slugify(tag))
+                .or_insert_with(|| (tag.as_str(), Vec::new()))
+                .1
+                .push(stamp);
+        }
+    }
+
+    // Sort tags by stamp count (descending), then alphabetically
+    let mut sorted_tags: Vec<_> = tag_map.into_iter().collect();
+    sorted_tags.sort_by(|a, b| b.1 .1.len().cmp(&a.1 .1.len()).then_with(|| a.0.cmp(&b.0)));
+
+    // Generate index page
+    let topics_dir = output_dir.join("topics");
+    fs::create_dir_all(&topics_dir)?;
+
+    let mut html = page_header(site, "Topics", "/topics/");
+
+    html.push_str(
+        r#"<nav class="breadcrumb">
+    <a href="/">Home</a> <span>/</span>
+    <span>Topics</span>
+</nav>
+"#,
+    );
+
+    html.push_str("<h2>Topics</h2>");
+    html.push_str(&format!(
+        "<p style=\"margin-bottom: 24px; color: var(--text-muted);\">{} topics</p>",
+        sorted_tags.len()
+    ));
+
+    html.push_str(r#"<div class="people-grid">"#);
+    for (slug, (tag_name, tag_stamps)) in &sorted_tags {
+        let cover_html = series_cover(tag_stamps)
+            .map(|(year, stamp_slug, img)| {
+                format!(
+                    r#"<img class="series-cover" src="/images/{}/{}/{}" alt="" loading="lazy">"#,
+                    year, stamp_slug, img
+                )
+            })
+            .unwrap_or_default();
+        html.push_str(&format!(
+            r#"<a href="/topics/{}/" class="person-link">
+    {}
+    <div class="person-name">{}</div>
+    <div class="person-count">{} stamps</div>
+</a>"#,
+            slug,
+            cover_html,
+            html_escape(tag_name),
+            tag_stamps.len()
+        ));
+    }
+    html.push_str("</div>");
+
+    html.push_str(&page_footer(site));
+    fs::write(topics_dir.join("index.html"), html)?;
+
+    // Generate individual topic pages
+    for (slug, (tag_name, mut tag_stamps)) in sorted_tags {
+        let page_dir = topics_dir.join(&slug);
+        fs::create_dir_all(&page_dir)?;
+
+        // Sort stamps by year desc, then issue_date desc, then name
+        tag_stamps.sort_by(|a, b| {
+            b.year
+                .cmp(&a.year)
+                .then_with(|| b.issue_date.cmp(&a.issue_date))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let mut html = page_header(site, tag_name, &format!("/topics/{}/", slug));
+
+        html.push_str(&format!(
+            r#"<nav class="breadcrumb">
+    <a href="/">Home</a> <span>/</span>
+    <a href="/topics/">Topics</a> <span>/</span>
+    <span>{}</span>
+</nav>
+"#,
+            html_escape(tag_name)
+        ));
+
+        html.push_str(&format!("<h2>{}</h2>", html_escape(tag_name)));
+        html.push_str(&format!(
+            "<p style=\"margin-bottom: 24px; color: var(--text-muted);\">{} stamps</p>",
+            tag_stamps.len()
+        ));
+
+        html.push_str(r#"<div class="stamp-grid">"#);
+        for stamp in &tag_stamps {
+            html.push_str(&stamp_card_html(stamp, "/images"));
+        }
+        html.push_str("</div>");
+
+        html.push_str(&page_footer(site));
         fs::write(page_dir.join("index.html"), html)?;
     }
 
@@ -2446,7 +3884,7 @@ fn generate_series_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
 }
 
 /// Generate rate type index and individual rate type pages
-fn generate_rate_type_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
+fn generate_rate_type_pages(stamps: &[Stamp], site: &SiteConfig, output_dir: &Path) -> Result<()> {
     // Collect all rate types and their stamps
     let mut rate_type_map: HashMap<String, Vec<&Stamp>> = HashMap::new();
 
@@ -2468,7 +3906,7 @@ fn generate_rate_type_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
     let rate_type_dir = output_dir.join("rates");
     fs::create_dir_all(&rate_type_dir)?;
 
-    let mut html = page_header("Rate Types", "/rates/");
+    let mut html = page_header(site, "Rate Types", "/rates/");
 
     html.push_str(
         r#"<nav class="breadcrumb">
@@ -2499,7 +3937,7 @@ fn generate_rate_type_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
     }
     html.push_str("</div>");
 
-    html.push_str(page_footer());
+    html.push_str(&page_footer(site));
     fs::write(rate_type_dir.join("index.html"), html)?;
 
     // Generate individual rate type pages
@@ -2516,7 +3954,7 @@ fn generate_rate_type_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
                 .then_with(|| a.name.cmp(&b.name))
         });
 
-        let mut html = page_header(&rate_type_name, "");
+        let mut html = page_header(site, &rate_type_name, &format!("/rates/{}/", slug));
 
         html.push_str(&format!(
             r#"<nav class="breadcrumb">
@@ -2540,7 +3978,7 @@ fn generate_rate_type_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
         }
         html.push_str("</div>");
 
-        html.push_str(page_footer());
+        html.push_str(&page_footer(site));
         fs::write(page_dir.join("index.html"), html)?;
     }
 
@@ -2548,10 +3986,16 @@ fn generate_rate_type_pages(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
 }
 
 /// Generate homepage
-fn generate_homepage(stamps: &[Stamp], years: &[u32], output_dir: &Path) -> Result<()> {
-    let mut html = page_header("US Postage Stamps", "/");
+fn generate_homepage(
+    stamps: &[Stamp],
+    years: &[u32],
+    today: NaiveDate,
+    site: &SiteConfig,
+    output_dir: &Path,
+) -> Result<()> {
+    let mut html = page_header(site, &site.title, "/");
 
-    html.push_str("<h2>US Postage Stamps</h2>");
+    html.push_str(&format!("<h2>{}</h2>", html_escape(&site.title)));
     html.push_str(&format!(
         "<p style=\"margin-bottom: 24px; color: var(--text-muted);\">{} stamps from {} to {}</p>",
         stamps.len(),
@@ -2566,10 +4010,22 @@ fn generate_homepage(stamps: &[Stamp], years: &[u32], output_dir: &Path) -> Resu
     }
     html.push_str("</div>");
 
+    // Upcoming stamps get their own section rather than passing as recent issues
+    let (upcoming, issued): (Vec<&Stamp>, Vec<&Stamp>) =
+        stamps.iter().partition(|s| s.is_upcoming(today));
+    if !upcoming.is_empty() {
+        html.push_str(r#"<h3 id="upcoming">Upcoming</h3>"#);
+        html.push_str(r#"<div class="stamp-grid">"#);
+        for stamp in &upcoming {
+            html.push_str(&stamp_card_html(stamp, "/images"));
+        }
+        html.push_str("</div>");
+    }
+
     // Show recent stamps (last 2 years)
     let current_year = years.first().copied().unwrap_or(2026);
-    let recent: Vec<_> = stamps
-        .iter()
+    let recent: Vec<_> = issued
+        .into_iter()
         .filter(|s| s.year >= current_year - 1)
         .collect();
 
@@ -2580,7 +4036,7 @@ fn generate_homepage(stamps: &[Stamp], years: &[u32], output_dir: &Path) -> Resu
     }
     html.push_str("</div>");
 
-    html.push_str(page_footer());
+    html.push_str(&page_footer(site));
 
     fs::write(output_dir.join("index.html"), html)?;
 
@@ -2588,12 +4044,10 @@ fn generate_homepage(stamps: &[Stamp], years: &[u32], output_dir: &Path) -> Resu
 }
 
 /// Create symlinks for images
-fn symlink_images(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
+fn symlink_images(stamps: &[Stamp], data_dir: &Path, output_dir: &Path) -> Result<()> {
     let images_dir = output_dir.join("images");
     fs::create_dir_all(&images_dir)?;
 
-    let data_dir = Path::new(DATA_DIR);
-
     for stamp in stamps {
         let stamp_images_dir = images_dir.join(stamp.year.to_string()).join(&stamp.slug);
         // Use api_slug for source since that's the folder name on disk
@@ -2612,7 +4066,7 @@ fn symlink_images(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
 
             if path.is_file() {
                 let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                if ["png", "jpg", "jpeg", "gif", "webp"].contains(&ext.to_lowercase().as_str()) {
+                if IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()) {
                     let filename = path.file_name().unwrap();
                     let link_path = stamp_images_dir.join(filename);
 
@@ -2632,24 +4086,259 @@ fn symlink_images(stamps: &[Stamp], output_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// An image file in images/manifest.json; dimensions are omitted if the header can't be read
+#[derive(Debug, Serialize)]
+struct ManifestImage {
+    file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<u32>,
+}
+
+impl ManifestImage {
+    fn new(dir: &Path, file: &str) -> Self {
+        let dimensions = image_dimensions(&dir.join(file));
+        Self {
+            file: file.to_string(),
+            width: dimensions.map(|(w, _)| w),
+            height: dimensions.map(|(_, h)| h),
+        }
+    }
+}
+
+/// One stamp image and its derivatives, keyed by kind ("thumbnail", "webp", "avif")
+#[derive(Debug, Serialize)]
+struct ManifestEntry {
+    original: ManifestImage,
+    variants: BTreeMap<&'static str, ManifestImage>,
+}
+
+/// Derivatives of `file` in `files`: `{stem}-thumb.*`, `{stem}.webp` and `{stem}.avif`
+fn image_variants<'a>(file: &str, files: &'a [String]) -> Vec<(&'static str, &'a str)> {
+    let stem = Path::new(file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file);
+    let thumb_prefix = format!("{}-thumb.", stem);
+    let mut variants = Vec::new();
+    for candidate in files.iter().filter(|f| *f != file) {
+        if candidate.starts_with(&thumb_prefix) {
+            variants.push(("thumbnail", candidate.as_str()));
+        } else if *candidate == format!("{}.webp", stem) {
+            variants.push(("webp", candidate.as_str()));
+        } else if *candidate == format!("{}.avif", stem) {
+            variants.push(("avif", candidate.as_str()));
+        }
+    }
+    variants
+}
+
+/// Write images/manifest.json: for each stamp and sheet image (keyed by its path under
+/// /images/), its dimensions and any derivative files sitting next to it
+fn write_image_manifest(stamps: &[Stamp], data_dir: &Path, output_dir: &Path) -> Result<()> {
+    let mut manifest: BTreeMap<String, ManifestEntry> = BTreeMap::new();
+
+    for stamp in stamps {
+        let source_dir = data_dir.join(stamp.year.to_string()).join(&stamp.api_slug);
+        let Ok(entries) = fs::read_dir(&source_dir) else {
+            continue;
+        };
+        let files: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.file_name().to_str().map(String::from))
+            .collect();
+
+        for file in stamp.stamp_images.iter().chain(&stamp.sheet_image) {
+            if !files.contains(file) {
+                continue;
+            }
+            let variants = image_variants(file, &files)
+                .into_iter()
+                .map(|(kind, variant)| (kind, ManifestImage::new(&source_dir, variant)))
+                .collect();
+            manifest.insert(
+                format!("{}/{}/{}", stamp.year, stamp.slug, file),
+                ManifestEntry {
+                    original: ManifestImage::new(&source_dir, file),
+                    variants,
+                },
+            );
+        }
+    }
+
+    let images_dir = output_dir.join("images");
+    fs::create_dir_all(&images_dir)?;
+    fs::write(
+        images_dir.join("manifest.json"),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(())
+}
+
+/// Rate history for forever values and "Value Today", or None (with a warning) to use scraped rates
+fn load_postal_rates() -> Option<PostalRates> {
+    match PostalRates::load() {
+        Ok(rates) => Some(rates),
+        Err(e) => {
+            eprintln!(
+                "Warning: Using scraped rates, failed to load rate history: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Find and load the stamp with this site slug (or API slug) under `data_dir`
+fn find_stamp(data_dir: &Path, slug: &str) -> Result<Stamp> {
+    let slug_lines = [format!("slug = {}", slug), format!("api_slug = {}", slug)];
+    let mut year_dirs: Vec<PathBuf> = fs::read_dir(data_dir)
+        .with_context(|| format!("Failed to read {}", data_dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    year_dirs.sort();
+
+    for year_dir in year_dirs {
+        for entry in fs::read_dir(&year_dir)? {
+            let conl_path = entry?.path().join("metadata.conl");
+            let Ok(content) = fs::read_to_string(&conl_path) else {
+                continue;
+            };
+            // Cheap line match first; only parse the candidates
+            if content
+                .lines()
+                .any(|line| slug_lines.iter().any(|l| l == line.trim()))
+            {
+                let stamp = load_stamp(&conl_path)?;
+                if stamp.slug == slug || stamp.api_slug == slug {
+                    return Ok(stamp);
+                }
+            }
+        }
+    }
+    bail!("Stamp '{}' not found in {}", slug, data_dir.display());
+}
+
+/// `--only`: regenerate one stamp page and its image symlinks without touching index pages
+///
+/// Related-stamp and previous/next links are left out, since no other stamps are loaded.
+fn generate_only(options: &GenerateOptions, slug: &str) -> Result<()> {
+    let mut stamps = vec![find_stamp(&options.data_dir, slug)?];
+    let postal_rates = load_postal_rates();
+    if let Some(rates) = &postal_rates {
+        apply_forever_rates(&mut stamps, rates, options.as_of);
+    }
+    let letter_rate = postal_rates
+        .as_ref()
+        .and_then(|rates| rates.letter.rate_on_date(options.as_of).ok());
+
+    let output_dir = options.output_dir.as_path();
+    let site = &options.site;
+    let stamp = &stamps[0];
+    generate_stamp_page(
+        stamp,
+        &[stamp],
+        &stamps,
+        letter_rate,
+        options.as_of,
+        site,
+        output_dir,
+    )?;
+    symlink_images(&stamps, &options.data_dir, output_dir)?;
+
+    if options.minify {
+        minify_output(&output_dir.join("stamps").join(&stamp.slug))?;
+    }
+
+    println!(
+        "Done! Regenerated {}/stamps/{}/",
+        output_dir.display(),
+        stamp.slug
+    );
+    Ok(())
+}
+
+/// `output` -> `output.{suffix}`, next to it so renames stay on one filesystem
+fn sibling_dir(dir: &Path, suffix: &str) -> PathBuf {
+    let mut name = dir.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Move the finished build at `build_dir` into place at `target`
+///
+/// The old site is renamed aside first since a directory can't be renamed over a
+/// non-empty one, so the gap a server might see is two renames, not a whole build.
+fn replace_dir(build_dir: &Path, target: &Path) -> Result<()> {
+    let old = sibling_dir(target, "old");
+    if old.exists() {
+        fs::remove_dir_all(&old)?;
+    }
+    if target.exists() {
+        fs::rename(target, &old)
+            .with_context(|| format!("Failed to move {} aside", target.display()))?;
+    }
+    fs::rename(build_dir, target)
+        .with_context(|| format!("Failed to move {} into place", build_dir.display()))?;
+    if old.exists() {
+        fs::remove_dir_all(&old)?;
+    }
+    Ok(())
+}
+
 /// Main generation function
-pub fn run_generate() -> Result<()> {
+pub fn run_generate(options: &GenerateOptions) -> Result<()> {
+    if let Some(max_year) = options.max_year {
+        if max_year < options.min_year {
+            bail!(
+                "--max-year {} is before --min-year {}",
+                max_year,
+                options.min_year
+            );
+        }
+    }
+
+    if let Some(slug) = &options.only {
+        return generate_only(options, slug);
+    }
+
     println!("Loading stamps...");
-    let stamps = load_all_stamps()?;
+    let mut stamps = load_all_stamps(options)?;
     println!("Loaded {} stamps", stamps.len());
 
+    let postal_rates = load_postal_rates();
+    if let Some(rates) = &postal_rates {
+        apply_forever_rates(&mut stamps, rates, options.as_of);
+    }
+
     if stamps.is_empty() {
+        if options.fail_on_empty {
+            bail!(
+                "No stamps found in {}; leaving {} untouched",
+                options.data_dir.display(),
+                options.output_dir.display()
+            );
+        }
         println!("No stamps found. Run 'usps-rates stamps scrape' first.");
         return Ok(());
     }
 
-    let output_dir = PathBuf::from(OUTPUT_DIR);
+    let build_dir = if options.atomic {
+        sibling_dir(&options.output_dir, "tmp")
+    } else {
+        options.output_dir.clone()
+    };
+    let output_dir = build_dir.as_path();
+    let site = &options.site;
 
-    // Clean and create output directory
+    // Clean and create the build directory (left behind for inspection if the build fails)
     if output_dir.exists() {
-        fs::remove_dir_all(&output_dir)?;
+        fs::remove_dir_all(output_dir)?;
     }
-    fs::create_dir_all(&output_dir)?;
+    fs::create_dir_all(output_dir)?;
 
     // Collect years
     let mut years: Vec<u32> = stamps
@@ -2660,15 +4349,32 @@ pub fn run_generate() -> Result<()> {
         .collect();
     years.sort_by(|a, b| b.cmp(a)); // Descending
 
+    // The letter rate as of the generate date, for the "Value Today" row on denominated stamps
+    let letter_rate = postal_rates
+        .as_ref()
+        .and_then(|rates| rates.letter.rate_on_date(options.as_of).ok());
+
     println!("Generating stamp pages...");
-    for stamp in &stamps {
-        generate_stamp_page(stamp, &output_dir)?;
+    for year in &years {
+        let mut year_stamps: Vec<&Stamp> = stamps.iter().filter(|s| s.year == *year).collect();
+        sort_by_issue_order(&mut year_stamps);
+        for stamp in &year_stamps {
+            generate_stamp_page(
+                stamp,
+                &year_stamps,
+                &stamps,
+                letter_rate,
+                options.as_of,
+                site,
+                output_dir,
+            )?;
+        }
     }
 
     println!("Generating year pages...");
     for year in &years {
         let year_stamps: Vec<_> = stamps.iter().filter(|s| s.year == *year).collect();
-        generate_year_page(*year, &year_stamps, &years, &output_dir)?;
+        generate_year_page(*year, &year_stamps, &years, options.as_of, site, output_dir)?;
     }
 
     println!("Generating category pages...");
@@ -2678,65 +4384,57 @@ pub fn run_generate() -> Result<()> {
         "forever-stamps",
         "Forever Stamps",
         |s| {
-            matches!(s.rate_type.as_deref(), Some("Forever") | Some("Semipostal"))
+            s.rate_type.as_deref().map(rate_category) == Some(RateCategory::Forever)
                 && s.stamp_type == "stamp"
         },
         CategorySort::Default,
         &stamps,
-        &output_dir,
+        options,
+        output_dir,
     )?;
 
     // Additional postage forever stamps (group by type, then year desc)
     generate_category_page(
         "additional-postage-forever-stamps",
         "Additional Postage Forever Stamps",
-        |s| {
-            matches!(
-                s.rate_type.as_deref(),
-                Some("Additional Ounce")
-                    | Some("Two Ounce")
-                    | Some("Three Ounce")
-                    | Some("Additional Postage")
-            )
-        },
+        |s| s.rate_type.as_deref().map(rate_category) == Some(RateCategory::AdditionalPostage),
         CategorySort::GroupByRateType,
         &stamps,
-        &output_dir,
+        options,
+        output_dir,
     )?;
 
     // Non-machinable forever stamps (default sort: year desc)
     generate_category_page(
         "non-machinable-forever-stamps",
         "Non-Machinable Forever Stamps",
-        |s| s.rate_type.as_deref() == Some("Nonmachineable Surcharge"),
+        |s| s.rate_type.as_deref().map(rate_category) == Some(RateCategory::Nonmachinable),
         CategorySort::Default,
         &stamps,
-        &output_dir,
+        options,
+        output_dir,
     )?;
 
     // Global forever stamps (default sort: year desc)
     generate_category_page(
         "global-forever-stamps",
         "Global Forever Stamps",
-        |s| {
-            matches!(
-                s.rate_type.as_deref(),
-                Some("International") | Some("Global Forever")
-            )
-        },
+        |s| s.rate_type.as_deref().map(rate_category) == Some(RateCategory::Global),
         CategorySort::Default,
         &stamps,
-        &output_dir,
+        options,
+        output_dir,
     )?;
 
     // Postcard forever stamps (forever first, then year desc)
     generate_category_page(
         "postcard-forever-stamps",
         "Postcard Forever Stamps",
-        |s| s.rate_type.as_deref() == Some("Postcard"),
+        |s| s.rate_type.as_deref().map(rate_category) == Some(RateCategory::Postcard),
         CategorySort::ForeverThenYear,
         &stamps,
-        &output_dir,
+        options,
+        output_dir,
     )?;
 
     // Denominated postage stamps (sort by rate desc)
@@ -2744,17 +4442,13 @@ pub fn run_generate() -> Result<()> {
         "denominated-postage-stamps",
         "Denominated Postage Stamps",
         |s| {
-            matches!(
-                s.rate_type.as_deref(),
-                Some("Definitive")
-                    | Some("Other Denomination")
-                    | Some("First Class")
-                    | Some("Special")
-            ) || extract_denomination(&s.name).is_some()
+            s.rate_type.as_deref().map(rate_category) == Some(RateCategory::Denominated)
+                || extract_denomination(&s.name).is_some()
         },
         CategorySort::RateDescending,
         &stamps,
-        &output_dir,
+        options,
+        output_dir,
     )?;
 
     // Cards (default sort: year desc)
@@ -2764,7 +4458,8 @@ pub fn run_generate() -> Result<()> {
         |s| s.stamp_type == "card",
         CategorySort::Default,
         &stamps,
-        &output_dir,
+        options,
+        output_dir,
     )?;
 
     // Envelopes (default sort: year desc)
@@ -2774,25 +4469,926 @@ pub fn run_generate() -> Result<()> {
         |s| s.stamp_type == "envelope",
         CategorySort::Default,
         &stamps,
-        &output_dir,
+        options,
+        output_dir,
     )?;
 
+    // Canceled stamps only appear on their year page and their own page
+    let listed: Vec<Stamp> = stamps.iter().filter(|s| !s.canceled).cloned().collect();
+
     println!("Generating people pages...");
-    generate_people_pages(&stamps, &output_dir)?;
+    generate_people_pages(&listed, site, output_dir)?;
+
+    println!("Exporting credits.json...");
+    generate_credits_json(&listed, output_dir)?;
+
+    println!("Exporting people.json...");
+    generate_people_json(&listed, output_dir)?;
+
+    println!("Exporting upcoming.ics...");
+    generate_calendar(&stamps, site, output_dir, options.as_of)?;
 
     println!("Generating series pages...");
-    generate_series_pages(&stamps, &output_dir)?;
+    generate_series_pages(
+        &listed,
+        &load_series_descriptions(&options.series_file),
+        site,
+        output_dir,
+    )?;
+
+    println!("Generating topic pages...");
+    generate_tag_pages(&listed, site, output_dir)?;
 
     println!("Generating rate type pages...");
-    generate_rate_type_pages(&stamps, &output_dir)?;
+    generate_rate_type_pages(&listed, site, output_dir)?;
 
     println!("Generating homepage...");
-    generate_homepage(&stamps, &years, &output_dir)?;
+    generate_homepage(&listed, &years, options.as_of, site, output_dir)?;
 
     println!("Creating image symlinks...");
-    symlink_images(&stamps, &output_dir)?;
+    symlink_images(&stamps, &options.data_dir, output_dir)?;
+
+    println!("Writing image manifest...");
+    write_image_manifest(&stamps, &options.data_dir, output_dir)?;
+
+    if options.minify {
+        println!("Minifying HTML...");
+        minify_output(output_dir)?;
+    }
+
+    if options.atomic {
+        replace_dir(output_dir, &options.output_dir)?;
+    }
 
-    println!("Done! Generated site in {}/", OUTPUT_DIR);
+    println!("Done! Generated site in {}/", options.output_dir.display());
+
+    report_unknown_category(&stamps);
 
     Ok(())
 }
+
+/// Print stamps shown under "Unknown" on year pages, grouped by rate_type,
+/// so missing rate_type/rate overrides are easy to spot
+fn report_unknown_category(stamps: &[Stamp]) {
+    let mut by_rate_type: BTreeMap<&str, Vec<&Stamp>> = BTreeMap::new();
+    for stamp in stamps {
+        if YearPageCategory::from_stamp(stamp) == YearPageCategory::Unknown {
+            by_rate_type
+                .entry(stamp.rate_type.as_deref().unwrap_or("(none)"))
+                .or_default()
+                .push(stamp);
+        }
+    }
+    if by_rate_type.is_empty() {
+        return;
+    }
+
+    let total: usize = by_rate_type.values().map(Vec::len).sum();
+    eprintln!(
+        "\nWarning: {} stamp(s) in the Unknown category (add overrides in enrichment/stamps/):",
+        total
+    );
+    for (rate_type, mut unknown) in by_rate_type {
+        unknown.sort_by(|a, b| a.slug.cmp(&b.slug));
+        eprintln!("  {} ({})", rate_type, unknown.len());
+        for stamp in unknown {
+            eprintln!("    {}  {}", stamp.slug, stamp.name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use usps_rates::slug::generate_slug;
+    use usps_rates::types::StampMetadata;
+
+    #[test]
+    fn test_parse_conl_multiline_round_trip() {
+        let about = "Issued to mark the anniversary.\n\n\
+                     The pane features:\n\
+                     - a portrait\n  - with a nested note\n\
+                     - a selvage illustration\n\n\
+                     Ends with a final paragraph.";
+
+        let compact = "name = Test\nslug = test-2025\napi_slug = test\nurl = https://www.stampsforever.com/stamps/test\nyear = 2025\n";
+        let metadata = StampMetadata {
+            about: Some(about.to_string()),
+            ..StampMetadata::from_conl(compact).unwrap()
+        };
+        let conl = metadata.to_conl().unwrap();
+
+        let data = parse_conl(&conl).unwrap();
+        match data.get("about") {
+            Some(ConlValue::String(s)) => assert_eq!(s, about),
+            _ => panic!("about should parse as a string"),
+        }
+        match data.get("year") {
+            Some(ConlValue::String(s)) => assert_eq!(s, "2025"),
+            _ => panic!("year should parse alongside the multiline block"),
+        }
+
+        let dir = std::env::temp_dir().join(format!("usps-conl-round-trip-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("metadata.conl"), &conl).unwrap();
+        let stamp = load_stamp(&dir.join("metadata.conl")).unwrap();
+        assert_eq!(stamp.about.as_deref(), Some(about));
+        assert_eq!(stamp.year, 2025);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_issue_locations() {
+        assert_eq!(
+            parse_issue_locations("Kansas City, MO"),
+            vec!["Kansas City, MO"]
+        );
+        assert_eq!(
+            parse_issue_locations("Kansas City, MO; Chicago, IL"),
+            vec!["Kansas City, MO", "Chicago, IL"]
+        );
+        assert_eq!(
+            parse_issue_locations("Kansas City, MO, Chicago, IL"),
+            vec!["Kansas City, MO", "Chicago, IL"]
+        );
+        assert_eq!(
+            parse_issue_locations("Washington, DC"),
+            vec!["Washington, DC"]
+        );
+        assert_eq!(parse_issue_locations("nationwide"), vec!["Nationwide"]);
+        assert_eq!(
+            parse_issue_locations("Nationwide; New York, NY"),
+            vec!["Nationwide", "New York, NY"]
+        );
+        // Commas inside a single place name don't split it
+        assert_eq!(
+            parse_issue_locations("Sarasota, Florida"),
+            vec!["Sarasota, Florida"]
+        );
+        assert_eq!(
+            parse_issue_locations("Berlin, Germany; Paris, France"),
+            vec!["Berlin, Germany", "Paris, France"]
+        );
+        assert_eq!(
+            parse_issue_locations("Kansas City, MO, Springfield, Illinois"),
+            vec!["Kansas City, MO, Springfield, Illinois"]
+        );
+        assert!(parse_issue_locations("TBA").is_empty());
+        assert!(parse_issue_locations("  ").is_empty());
+    }
+
+    #[test]
+    fn test_denomination_to_cents() {
+        assert_eq!(denomination_to_cents("1¢"), 1.0);
+        assert_eq!(denomination_to_cents("10¢"), 10.0);
+        assert_eq!(denomination_to_cents("½¢"), 0.5);
+        assert_eq!(denomination_to_cents("1½¢"), 1.5);
+        assert_eq!(denomination_to_cents("2¼¢"), 2.25);
+        assert_eq!(denomination_to_cents("1-1/2¢"), 1.5);
+        assert_eq!(denomination_to_cents("$1"), 100.0);
+        assert_eq!(denomination_to_cents("$1.00"), 100.0);
+        assert_eq!(denomination_to_cents("$26.95"), 2695.0);
+        assert_eq!(denomination_to_cents("$1,000"), 100000.0);
+        assert_eq!(denomination_to_cents("Forever"), f64::MAX);
+    }
+
+    #[test]
+    fn test_extract_denomination() {
+        assert_eq!(extract_denomination("1¢ Apples").as_deref(), Some("1¢"));
+        assert_eq!(extract_denomination("10c Poppies").as_deref(), Some("10¢"));
+        assert_eq!(extract_denomination("½¢ Benjamin Franklin").as_deref(), Some("½¢"));
+        assert_eq!(extract_denomination("1½¢ Albert Gallatin").as_deref(), Some("1½¢"));
+        assert_eq!(extract_denomination("1-1/2¢ Martha Washington").as_deref(), Some("1-1/2¢"));
+        assert_eq!(extract_denomination("$1,000 Commemorative").as_deref(), Some("$1,000"));
+        assert_eq!(extract_denomination("$2 Floral Geometry").as_deref(), Some("$2"));
+        assert_eq!(extract_denomination("Arc de Triomphe"), None);
+        assert_eq!(extract_denomination("Frogs"), None);
+    }
+
+    #[test]
+    fn test_historical_denominations_sort_by_value() {
+        let mut denoms = vec!["$1", "2¢", "1½¢", "½¢", "1¢", "1-1/4¢", "$1,000", "25¢"];
+        denoms.sort_by(|a, b| denomination_to_cents(a).total_cmp(&denomination_to_cents(b)));
+        assert_eq!(
+            denoms,
+            vec!["½¢", "1¢", "1-1/4¢", "1½¢", "2¢", "25¢", "$1", "$1,000"]
+        );
+    }
+
+    #[test]
+    fn test_value_today_display() {
+        assert_eq!(
+            value_today_display(0.34, 0.78),
+            "face value: 34¢ (still usable as 34¢ toward the $0.78 rate)"
+        );
+        assert_eq!(
+            value_today_display(5.0, 0.78),
+            "face value: $5.00 (covers the $0.78 letter rate)"
+        );
+    }
+
+    #[test]
+    fn test_stamp_badge_class() {
+        let dir = std::env::temp_dir().join(format!("usps-badge-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let conl_path = dir.join("metadata.conl");
+        let badge = |fields: &str| {
+            let conl = format!("name = Flag\nslug = flag\nyear = 2010\n{}", fields);
+            fs::write(&conl_path, conl).unwrap();
+            rate_category_badge_class(stamp_rate_category(&load_stamp(&conl_path).unwrap()))
+        };
+        // First Class follows the stamp, as on the year pages
+        assert_eq!(
+            badge("rate_type = First Class\nrate = 0.44\nforever = false\n"),
+            "definitive"
+        );
+        assert_eq!(
+            badge("rate_type = First Class\nforever = true\n"),
+            "forever"
+        );
+        assert_eq!(badge("rate_type = Semipostal\n"), "forever");
+        assert_eq!(badge("rate_type = Definitive\n"), "definitive");
+        assert_eq!(badge("rate_type = Presorted Standard\n"), "presorted");
+        assert_eq!(badge("rate_type = Special\n"), "denominated");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_additional_postage_alias_categorized() {
+        let rate_type = RateType::canonical_str("Additional Postage");
+        assert_eq!(rate_type, "Additional Ounce");
+        for rate_type in [rate_type, "Additional Postage"] {
+            assert_eq!(
+                rate_type_to_category(Some(rate_type)),
+                Some(("additional-postage-forever-stamps", "Additional Postage"))
+            );
+        }
+        assert_eq!(rate_type_to_category(Some("Priority Mail")), None);
+        assert_eq!(
+            rate_category_badge_class(rate_category(rate_type)),
+            "additional"
+        );
+    }
+
+    #[test]
+    fn test_canceled_stamp_not_issued() {
+        let dir = std::env::temp_dir().join(format!("usps-canceled-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let conl_path = dir.join("metadata.conl");
+        fs::write(
+            &conl_path,
+            "name = Canceled Flag
+slug = canceled-flag-forever-2024
+year = 2024
+rate_type = Forever
+forever = true
+type = stamp
+canceled = true
+",
+        )
+        .unwrap();
+        let stamp = load_stamp(&conl_path).unwrap();
+        assert!(stamp.canceled);
+        let category = YearPageCategory::from_stamp(&stamp);
+        assert_eq!(category, YearPageCategory::NotIssued);
+        assert_eq!(category.display_name(), "Announced but not issued");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_undated_stamp_upcoming_by_year() {
+        let dir = std::env::temp_dir().join(format!("usps-undated-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let conl_path = dir.join("metadata.conl");
+        let today = NaiveDate::from_ymd_opt(2025, 7, 14).unwrap();
+        for (year, upcoming) in [(2024, false), (2025, true), (2026, true)] {
+            fs::write(
+                &conl_path,
+                format!("name = Undated\nslug = undated-forever-{year}\nyear = {year}\n"),
+            )
+            .unwrap();
+            let stamp = load_stamp(&conl_path).unwrap();
+            assert_eq!(stamp.is_upcoming(today), upcoming, "{}", year);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_generate_calendar() {
+        let dir = std::env::temp_dir().join(format!("usps-calendar-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let stamp = |slug: &str, extra: &str| {
+            let conl_path = dir.join(format!("{}.conl", slug));
+            fs::write(
+                &conl_path,
+                format!("name = {}\nslug = {}\nyear = 2025\n{}", slug, slug, extra),
+            )
+            .unwrap();
+            load_stamp(&conl_path).unwrap()
+        };
+        let stamps = vec![
+            stamp(
+                "lunar-new-year",
+                "issue_date = 2025-01-25\nissue_location = Honolulu, HI\n",
+            ),
+            stamp("love", "issue_date = 2025-01-10\n"),
+            stamp("last-year", "issue_date = 2024-06-01\n"),
+            stamp("tba", ""),
+            stamp("canceled", "issue_date = 2025-03-01\ncanceled = true\n"),
+        ];
+        let site = SiteConfig {
+            base_url: Some("https://stamps.example.com/".to_string()),
+            ..SiteConfig::default()
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        assert_eq!(generate_calendar(&stamps, &site, &dir, today).unwrap(), 2);
+        let ics = fs::read_to_string(dir.join("upcoming.ics")).unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        // Sorted by date
+        let love = ics.find("SUMMARY:love").unwrap();
+        assert!(love < ics.find("SUMMARY:lunar-new-year").unwrap());
+        assert!(ics.contains(
+            "UID:lunar-new-year@stamps.example.com\r\nDTSTAMP:20250101T000000Z\r\n\
+             DTSTART;VALUE=DATE:20250125\r\nDTEND;VALUE=DATE:20250126\r\n"
+        ));
+        assert!(ics.contains("LOCATION:Honolulu\\, HI\r\n"));
+        assert!(ics.contains("URL:https://stamps.example.com/stamps/lunar-new-year/\r\n"));
+        assert!(!ics.contains("last-year") && !ics.contains("tba") && !ics.contains("canceled"));
+
+        assert_eq!(
+            ical_fold(&"x".repeat(80)),
+            format!("{}\r\n {}", "x".repeat(75), "x".repeat(5))
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_forever_override_before_2007() {
+        // Pre-2011 First Class stamps are denominated unless overridden
+        let slug = |forever| {
+            generate_slug(
+                "liberty-bell-2005",
+                2005,
+                Some("First Class"),
+                Some("0.37"),
+                forever,
+            )
+        };
+        assert_eq!(slug(None), ("liberty-bell-37c-2005".to_string(), false));
+        assert_eq!(
+            slug(Some(true)),
+            ("liberty-bell-first-class-2005".to_string(), true)
+        );
+
+        let dir =
+            std::env::temp_dir().join(format!("usps-forever-override-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let conl_path = dir.join("metadata.conl");
+        fs::write(
+            &conl_path,
+            "name = Liberty Bell
+slug = liberty-bell-first-class-2005
+year = 2005
+rate_type = First Class
+forever = true
+type = stamp
+",
+        )
+        .unwrap();
+        let stamp = load_stamp(&conl_path).unwrap();
+        assert!(stamp.forever);
+        assert_eq!(
+            YearPageCategory::from_stamp(&stamp),
+            YearPageCategory::Forever
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_text_color_for_background() {
+        let text = |hex: &str| text_color_for(parse_hex_color(hex).unwrap());
+        // Black/white contrast crosses over at luminance ~0.179
+        assert_eq!(text("757575"), "#ffffff");
+        assert_eq!(text("767676"), "#000000");
+        assert_eq!(text("#575757"), "#ffffff");
+        assert_eq!(text("ab1224"), "#ffffff");
+        assert_eq!(text("ffcc00"), "#000000");
+        assert_eq!(text("009090"), "#000000");
+
+        assert_eq!(parse_hex_color("fff"), None);
+        assert_eq!(parse_hex_color("zz0000"), None);
+        assert_eq!(background_style(Some("nope")), "");
+        assert_eq!(
+            background_style(Some("4B5D2C")),
+            r#" style="background-color: #4b5d2c; color: #ffffff""#
+        );
+    }
+
+    #[test]
+    fn test_minify_css() {
+        let css = "/* Header */\nheader nav a:hover, .b {\n    color: red;\n    margin: 0 auto;\n}\n\n.a :first-child { padding: 4px }\n";
+        assert_eq!(
+            minify_css(css),
+            "header nav a:hover,.b{color:red;margin:0 auto}.a :first-child{padding:4px}"
+        );
+    }
+
+    #[test]
+    fn test_minify_html() {
+        let html = "<div>\n    <a href=\"/\">Home</a> <span>/</span>\n</div>\n<style>\n  .a { color: red; }\n</style>\n<script>\n  // keep\n  x();\n</script>\n";
+        assert_eq!(
+            minify_html(html),
+            "<div> <a href=\"/\">Home</a> <span>/</span> </div> <style>.a{color:red}</style> <script>\n  // keep\n  x();\n</script>"
+        );
+    }
+
+    const FIXTURE_FOREVER: &str = "name = Golden Poppies
+slug = golden-poppies-forever-2025
+api_slug = golden-poppies
+url = https://www.stampsforever.com/stamps/golden-poppies
+year = 2025
+issue_date = 2025-03-01
+issue_location = Sacramento, CA
+rate_type = Forever
+pair_with = floral-geometry-5d-2025
+series = Flora
+tags
+  = Flowers
+  = California
+credits
+  designer
+    = Ana Rivera
+    = Jane Doe
+";
+
+    const FIXTURE_DENOMINATED: &str = "name = Floral Geometry
+slug = floral-geometry-5d-2025
+api_slug = floral-geometry
+url = https://www.stampsforever.com/stamps/floral-geometry
+year = 2025
+issue_date = 2025-01-15
+rate = 5.00
+rate_type = Denominated
+forever = false
+type = stamp
+pair_with = golden-poppies-forever-2025
+series = Flora
+tags
+  = flowers
+stamp_images
+  = floral.png
+credits
+  artist = Jane Doe
+";
+
+    /// Write a fixture `data/stamps/` tree into a fresh temp dir
+    fn write_fixture(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("usps-{}-{}", name, std::process::id()));
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        for (api_slug, conl) in [
+            ("golden-poppies", FIXTURE_FOREVER),
+            ("floral-geometry", FIXTURE_DENOMINATED),
+        ] {
+            let dir = root.join("data/stamps/2025").join(api_slug);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("metadata.conl"), conl).unwrap();
+        }
+        root
+    }
+
+    fn read_output(output_dir: &Path, path: &str) -> String {
+        fs::read_to_string(output_dir.join(path))
+            .unwrap_or_else(|e| panic!("missing {}: {}", path, e))
+    }
+
+    /// Header bytes of a `width`x`height` PNG, padded past what `image_dimensions` reads
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend(width.to_be_bytes());
+        png.extend(height.to_be_bytes());
+        png.resize(32, 0);
+        png
+    }
+
+    /// Options generating `root`'s fixture into `root/output`, as of 2025-07-14
+    fn fixture_options(root: &Path) -> GenerateOptions {
+        GenerateOptions {
+            include_hidden: false,
+            min_year: MIN_YEAR,
+            max_year: None,
+            data_dir: root.join("data/stamps"),
+            series_file: root.join("enrichment/series.conl"),
+            output_dir: root.join("output"),
+            minify: false,
+            only_with_products: false,
+            exclude_rate_types: Vec::new(),
+            as_of: NaiveDate::from_ymd_opt(2025, 7, 14).unwrap(),
+            only: None,
+            fail_on_empty: true,
+            atomic: false,
+            hide_upcoming: false,
+            site: SiteConfig::default(),
+        }
+    }
+
+    /// Write the fixture as `name` and generate the site with default options
+    fn generate_fixture(name: &str) -> (PathBuf, GenerateOptions) {
+        let root = write_fixture(name);
+        let options = fixture_options(&root);
+        run_generate(&options).unwrap();
+        (root, options)
+    }
+
+    #[test]
+    fn test_generate_upcoming() {
+        let root = write_fixture("generate-upcoming");
+        // Golden Poppies issues 2025-03-01, Floral Geometry 2025-01-15
+        let options = GenerateOptions {
+            as_of: NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+            site: SiteConfig {
+                base_url: Some("https://stamps.example.com".to_string()),
+                ..SiteConfig::default()
+            },
+            ..fixture_options(&root)
+        };
+        run_generate(&options).unwrap();
+        let out = &options.output_dir;
+        let poppies = r#"href="/stamps/golden-poppies-forever-2025/""#;
+
+        // The calendar is as of --as-of too, and links events with a base_url
+        let ics = read_output(out, "upcoming.ics");
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("DTSTAMP:20250201T000000Z"));
+        assert!(ics.contains("URL:https://stamps.example.com/stamps/golden-poppies-forever-2025/"));
+
+        let year = read_output(out, "2025/index.html");
+        let upcoming = year.find(r#"<h3 id="upcoming">Upcoming</h3>"#).unwrap();
+        let denominated = year.find(r#"<h3 id="denominated">"#).unwrap();
+        assert!((upcoming..denominated).contains(&year.find(poppies).unwrap()));
+        assert!(!year.contains(r#"<h3 id="forever">"#));
+
+        let home = read_output(out, "index.html");
+        assert!(home.find(poppies).unwrap() < home.find("<h3>Recent Stamps</h3>").unwrap());
+
+        // Neither available nor discontinued on category pages
+        let category = read_output(out, "forever-stamps/index.html");
+        assert!(category.contains("1 stamps (0 available, 1 upcoming)"));
+        assert!(!category.contains(r#"<h3 id="discontinued">"#));
+        assert!(
+            read_output(out, "stamps/golden-poppies-forever-2025/index.html")
+                .contains(r#"<span class="discontinued-label upcoming">Upcoming</span>"#)
+        );
+
+        run_generate(&GenerateOptions {
+            hide_upcoming: true,
+            ..options.clone()
+        })
+        .unwrap();
+        assert!(!out.join("stamps/golden-poppies-forever-2025").exists());
+        assert!(out.join("stamps/floral-geometry-5d-2025").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_stamp_pages() {
+        let (root, options) = generate_fixture("generate-stamp-pages");
+        let out = &options.output_dir;
+
+        let forever = read_output(out, "stamps/golden-poppies-forever-2025/index.html");
+        assert!(forever.contains("<h1>Golden Poppies</h1>"));
+        assert!(forever.contains("Sacramento, CA"));
+        assert!(forever.contains(
+            r#"<span class="stamp-meta-label">Designers</span><span><a href="/credits/ana-rivera/">Ana Rivera</a>, <a href="/credits/jane-doe/">Jane Doe</a></span>"#
+        ));
+        assert!(forever.contains(r#"<a href="/rates/forever/" class="category-badge forever">"#));
+        assert!(forever.contains(r#"<span class="discontinued-label">Discontinued</span>"#));
+        assert!(forever.contains(
+            r#"See also the <a href="/stamps/floral-geometry-5d-2025/">$5.00 version</a>"#
+        ));
+
+        let denominated = read_output(out, "stamps/floral-geometry-5d-2025/index.html");
+        assert!(denominated.contains("<h1>Floral Geometry</h1>"));
+        // Letter rate as of 2025-07-14, from enrichment/rates/letter.conl
+        assert!(denominated.contains("face value: $5.00 (covers the $0.78 letter rate)"));
+        assert!(denominated.contains(
+            r#"See also the <a href="/stamps/golden-poppies-forever-2025/">Forever version</a>"#
+        ));
+        // Related by shared creator (Jane Doe)
+        assert!(denominated.contains("<h2>Related Stamps</h2>"));
+        assert!(denominated.contains(r#"<a href="/stamps/golden-poppies-forever-2025/">"#));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_load_all_stamps_keeps_filtered_pair() {
+        let root = write_fixture("load-filtered-pair");
+        let options = GenerateOptions {
+            exclude_rate_types: vec!["Denominated".to_string()],
+            ..fixture_options(&root)
+        };
+
+        // Floral Geometry is filtered out, but the pairing was mutual
+        let stamps = load_all_stamps(&options).unwrap();
+        assert_eq!(stamps.len(), 1);
+        assert_eq!(
+            stamps[0].pair_with.as_deref(),
+            Some("floral-geometry-5d-2025")
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_badge_matches_year_section() {
+        let root = write_fixture("generate-badges");
+        for (api_slug, forever) in [("old-flag", false), ("liberty-flag", true)] {
+            let dir = root.join("data/stamps/2025").join(api_slug);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("metadata.conl"),
+                format!(
+                    "name = {api_slug}\nslug = {api_slug}\napi_slug = {api_slug}\nyear = 2025\n\
+                     issue_date = 2025-01-10\nrate_type = First Class\nrate = 0.44\nforever = {forever}\n"
+                ),
+            )
+            .unwrap();
+        }
+        let options = fixture_options(&root);
+        run_generate(&options).unwrap();
+        let out = &options.output_dir;
+
+        let year = read_output(out, "2025/index.html");
+        for (slug, section, badge) in [
+            ("old-flag", "denominated", "definitive"),
+            ("liberty-flag", "forever", "forever"),
+        ] {
+            let link = year.find(&format!(r#"href="/stamps/{}/""#, slug)).unwrap();
+            let heading = year[..link].rfind(r#"<h3 id=""#).unwrap();
+            assert_eq!(year[heading..].split('"').nth(1), Some(section));
+            let page = read_output(out, &format!("stamps/{}/index.html", slug));
+            let pill = format!(r#"class="category-badge {}""#, badge);
+            assert!(page.contains(&pill), "{}", slug);
+        }
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_year_and_category_pages() {
+        let (root, options) = generate_fixture("generate-year-pages");
+        let out = &options.output_dir;
+
+        assert!(read_output(out, "index.html").contains("2025"));
+        let year = read_output(out, "2025/index.html");
+        assert!(year.contains("Golden Poppies"));
+        assert!(year.contains(r#"<span class="stamp-card-rate">$5.00</span>"#));
+        assert!(year.contains(r#"<h3 id="forever">"#));
+        assert!(year.contains(r#"<h3 id="denominated">"#));
+
+        let category = read_output(out, "forever-stamps/index.html");
+        assert!(category.contains("Forever Stamps"));
+        assert!(category.contains("Golden Poppies"));
+        assert!(!category.contains("Floral Geometry"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_site_config() {
+        let root = write_fixture("generate-site-config");
+        let options = GenerateOptions {
+            site: SiteConfig {
+                title: "Stamp Catalog".to_string(),
+                lang: "en-US".to_string(),
+                base_url: Some("https://stamps.example.com/".to_string()),
+                footer_html: "<p>Unofficial.</p>".to_string(),
+            },
+            ..fixture_options(&root)
+        };
+        run_generate(&options).unwrap();
+
+        let year = read_output(&options.output_dir, "2025/index.html");
+        assert!(year.contains(r#"<html lang="en-US">"#));
+        assert!(year.contains("Stamp Catalog"));
+        assert!(year.contains(r#"<link rel="canonical" href="https://stamps.example.com/2025/">"#));
+        assert!(year.contains("<p>Unofficial.</p>"));
+        assert!(!year.contains("Not affiliated with United States Postal Service"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_series_and_topics() {
+        let root = write_fixture("generate-series-topics");
+        let options = fixture_options(&root);
+        fs::create_dir_all(root.join("enrichment")).unwrap();
+        fs::write(
+            &options.series_file,
+            "Flora = Wildflowers and garden blooms\n",
+        )
+        .unwrap();
+        run_generate(&options).unwrap();
+        let out = &options.output_dir;
+
+        // Only the denominated stamp has an image, so it covers the series
+        let cover = r#"src="/images/2025/floral-geometry-5d-2025/floral.png""#;
+        assert!(read_output(out, "series/index.html").contains(cover));
+        let flora = read_output(out, "series/flora/index.html");
+        assert!(flora.contains(cover));
+        assert!(
+            flora.contains(r#"<p class="series-description">Wildflowers and garden blooms</p>"#)
+        );
+
+        // "Flowers" and "flowers" share one topic page
+        let forever = read_output(out, "stamps/golden-poppies-forever-2025/index.html");
+        assert!(forever.contains(r#"<a href="/topics/flowers/">Flowers</a>, <a href="/topics/california/">California</a>"#));
+        let topics = read_output(out, "topics/index.html");
+        assert!(topics.contains("<div class=\"person-count\">2 stamps</div>"));
+        assert!(topics.contains(r#"<a href="/topics/california/" class="person-link">"#));
+        let flowers = read_output(out, "topics/flowers/index.html");
+        assert!(flowers.contains("Golden Poppies") && flowers.contains("Floral Geometry"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_credits_and_people() {
+        let root = write_fixture("generate-credits");
+        let meadow_dir = root.join("data/stamps/2025/meadow");
+        fs::create_dir_all(&meadow_dir).unwrap();
+        fs::write(
+            meadow_dir.join("metadata.conl"),
+            "name = Meadow
+slug = meadow-forever-2025
+api_slug = meadow
+url = https://www.stampsforever.com/stamps/meadow
+year = 2025
+rate_type = Forever
+credits
+  photographer = Jane-Doe
+",
+        )
+        .unwrap();
+        let options = fixture_options(&root);
+        run_generate(&options).unwrap();
+        let out = &options.output_dir;
+
+        assert!(read_output(out, "credits.json").contains("Jane Doe"));
+        assert!(read_output(out, "credits/ana-rivera/index.html").contains("Golden Poppies"));
+
+        let people: serde_json::Value =
+            serde_json::from_str(&read_output(out, "people.json")).unwrap();
+        let jane = &people["jane-doe"]["stamps"];
+        assert_eq!(jane[0]["slug"], "floral-geometry-5d-2025");
+        assert_eq!(jane[0]["roles"], serde_json::json!(["Artist"]));
+        assert_eq!(jane[1]["slug"], "golden-poppies-forever-2025");
+        assert_eq!(jane[1]["roles"], serde_json::json!(["Designer"]));
+        // "Jane-Doe" slugifies the same and is merged in
+        assert_eq!(people["jane-doe"]["name"], "Jane Doe");
+        assert_eq!(jane[2]["slug"], "meadow-forever-2025");
+        assert_eq!(jane[2]["roles"], serde_json::json!(["Photographer"]));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_leaves_out_canceled() {
+        let root = write_fixture("generate-canceled");
+        let withdrawn_dir = root.join("data/stamps/2025/withdrawn-bloom");
+        fs::create_dir_all(&withdrawn_dir).unwrap();
+        fs::write(
+            withdrawn_dir.join("metadata.conl"),
+            "name = Withdrawn Bloom
+slug = withdrawn-bloom-forever-2025
+api_slug = withdrawn-bloom
+url = https://www.stampsforever.com/stamps/withdrawn-bloom
+year = 2025
+issue_date = 2025-05-01
+rate_type = Forever
+canceled = true
+series = Flora
+tags
+  = Flowers
+credits
+  designer = Lee Park
+",
+        )
+        .unwrap();
+        let options = fixture_options(&root);
+        run_generate(&options).unwrap();
+        let out = &options.output_dir;
+
+        // Still on its year page and its own page
+        assert!(read_output(out, "2025/index.html").contains("Withdrawn Bloom"));
+        assert!(out.join("stamps/withdrawn-bloom-forever-2025").exists());
+
+        for page in [
+            "index.html",
+            "rates/forever/index.html",
+            "series/flora/index.html",
+            "topics/flowers/index.html",
+            "credits/index.html",
+            "credits.json",
+            "people.json",
+        ] {
+            assert!(!read_output(out, page).contains("Withdrawn"), "{}", page);
+        }
+        assert!(!out.join("credits/lee-park").exists());
+        assert!(read_output(out, "index.html").contains("2 stamps from 2025 to 2025"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_image_manifest() {
+        let root = write_fixture("generate-manifest");
+        let floral_dir = root.join("data/stamps/2025/floral-geometry");
+        fs::write(floral_dir.join("floral.png"), png_header(600, 400)).unwrap();
+        fs::write(floral_dir.join("floral-thumb.png"), png_header(150, 100)).unwrap();
+        let options = fixture_options(&root);
+        run_generate(&options).unwrap();
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&read_output(&options.output_dir, "images/manifest.json"))
+                .unwrap();
+        assert_eq!(
+            manifest["2025/floral-geometry-5d-2025/floral.png"],
+            serde_json::json!({
+                "original": {"file": "floral.png", "width": 600, "height": 400},
+                "variants": {"thumbnail": {"file": "floral-thumb.png", "width": 150, "height": 100}}
+            })
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_only() {
+        let (root, options) = generate_fixture("generate-only");
+        let out = &options.output_dir;
+
+        // --only rebuilds one page (found by API slug too) and leaves the rest alone
+        let page = out.join("stamps/golden-poppies-forever-2025/index.html");
+        fs::remove_file(&page).unwrap();
+        let only = |slug: &str| {
+            run_generate(&GenerateOptions {
+                only: Some(slug.to_string()),
+                ..options.clone()
+            })
+        };
+        only("golden-poppies").unwrap();
+        assert!(
+            read_output(out, "stamps/golden-poppies-forever-2025/index.html")
+                .contains("<h1>Golden Poppies</h1>")
+        );
+        assert!(out.join("series/index.html").exists());
+        assert!(only("no-such-stamp").is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_atomic() {
+        let root = write_fixture("generate-atomic");
+        let options = GenerateOptions {
+            atomic: true,
+            ..fixture_options(&root)
+        };
+
+        // A stale site is replaced wholesale
+        fs::create_dir_all(root.join("output/stale")).unwrap();
+        run_generate(&options).unwrap();
+        assert!(!root.join("output/stale").exists());
+        assert!(root.join("output/series/index.html").exists());
+        assert!(!root.join("output.tmp").exists() && !root.join("output.old").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_generate_fail_on_empty() {
+        let (root, options) = generate_fixture("generate-empty");
+        let out = &options.output_dir;
+
+        // A run that finds no stamps fails without wiping the previous output
+        let empty = GenerateOptions {
+            data_dir: root.join("missing"),
+            ..options.clone()
+        };
+        assert!(run_generate(&empty).is_err());
+        assert!(out.join("series/index.html").exists());
+        run_generate(&GenerateOptions {
+            fail_on_empty: false,
+            ..empty
+        })
+        .unwrap();
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
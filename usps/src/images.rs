@@ -0,0 +1,346 @@
+//! Downloaded stamp images: header dimensions and lossless metadata stripping
+//!
+//! EXIF, XMP, IPTC and text chunks are dropped without re-encoding, so pixels, color
+//! profiles and the file format are untouched and the result is never larger.
+
+use anyhow::Result;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::scrape::STAMPS_DIR;
+
+/// Width and height from a PNG, GIF, WebP or JPEG header, without decoding the image
+pub fn image_dimensions(path: &Path) -> Option<(u32, u32)> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut h = [0u8; 30];
+    file.read_exact(&mut h).ok()?;
+
+    let be16 = |b: &[u8]| u16::from_be_bytes([b[0], b[1]]) as u32;
+    let le16 = |b: &[u8]| u16::from_le_bytes([b[0], b[1]]) as u32;
+    let le24 = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], 0]);
+    let be32 = |b: &[u8]| u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+
+    if h.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some((be32(&h[16..20]), be32(&h[20..24])))
+    } else if h.starts_with(b"GIF8") {
+        Some((le16(&h[6..8]), le16(&h[8..10])))
+    } else if h.starts_with(b"RIFF") && &h[8..12] == b"WEBP" {
+        match &h[12..16] {
+            b"VP8 " => Some((le16(&h[26..28]) & 0x3fff, le16(&h[28..30]) & 0x3fff)),
+            b"VP8L" => {
+                let bits = u32::from_le_bytes([h[21], h[22], h[23], h[24]]);
+                Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+            }
+            b"VP8X" => Some((le24(&h[24..27]) + 1, le24(&h[27..30]) + 1)),
+            _ => None,
+        }
+    } else if h.starts_with(&[0xff, 0xd8]) {
+        // Walk JPEG segments to the start-of-frame, which holds height then width
+        file.seek(SeekFrom::Start(2)).ok()?;
+        let mut marker = [0u8; 4];
+        loop {
+            file.read_exact(&mut marker).ok()?;
+            if marker[0] != 0xff {
+                return None;
+            }
+            let length = be16(&marker[2..4]) as i64;
+            match marker[1] {
+                0xc0..=0xcf if ![0xc4, 0xc8, 0xcc].contains(&marker[1]) => {
+                    let mut frame = [0u8; 5];
+                    file.read_exact(&mut frame).ok()?;
+                    return Some((be16(&frame[3..5]), be16(&frame[1..3])));
+                }
+                _ => {
+                    file.seek(SeekFrom::Current(length - 2)).ok()?;
+                }
+            }
+        }
+    } else {
+        None
+    }
+}
+
+/// JPEG segments dropped: APP1 (EXIF/XMP), APP13 (Photoshop/IPTC), COM (comments)
+const JPEG_METADATA_MARKERS: &[u8] = &[0xE1, 0xED, 0xFE];
+
+/// PNG chunks dropped: text, EXIF and modification time
+const PNG_METADATA_CHUNKS: &[&[u8]] = &[b"tEXt", b"zTXt", b"iTXt", b"eXIf", b"tIME"];
+
+/// WebP chunks dropped, with their VP8X feature flag bits
+const WEBP_METADATA_CHUNKS: &[(&[u8], u8)] = &[(b"EXIF", 0x08), (b"XMP ", 0x04)];
+
+/// Remove metadata from JPEG, PNG or WebP bytes
+///
+/// Returns None for other formats, malformed files, or when there's nothing to strip.
+pub fn strip_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg(bytes)
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        strip_png(bytes)
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        strip_webp(bytes)
+    } else {
+        None
+    }
+}
+
+fn strip_jpeg(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = bytes[..2].to_vec();
+    let mut pos = 2;
+    let mut stripped = false;
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+        match marker {
+            // Start of scan: entropy-coded data runs to the end of the file
+            0xDA => {
+                out.extend_from_slice(&bytes[pos..]);
+                break;
+            }
+            // Fill byte before the real marker
+            0xFF => {
+                pos += 1;
+                continue;
+            }
+            // Markers without a length
+            0x01 | 0xD0..=0xD7 => {
+                out.extend_from_slice(&bytes[pos..pos + 2]);
+                pos += 2;
+                continue;
+            }
+            0xD9 => {
+                out.extend_from_slice(&bytes[pos..pos + 2]);
+                break;
+            }
+            _ => {}
+        }
+        let len = u16::from_be_bytes([*bytes.get(pos + 2)?, *bytes.get(pos + 3)?]) as usize;
+        let segment = bytes.get(pos..pos + 2 + len)?;
+        if JPEG_METADATA_MARKERS.contains(&marker) {
+            stripped = true;
+        } else {
+            out.extend_from_slice(segment);
+        }
+        pos += 2 + len;
+    }
+    stripped.then_some(out)
+}
+
+fn strip_png(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = bytes[..8].to_vec();
+    let mut pos = 8;
+    let mut stripped = false;
+    while pos < bytes.len() {
+        let len = u32::from_be_bytes(bytes.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        let chunk_type = bytes.get(pos + 4..pos + 8)?;
+        // Length, type, data, CRC
+        let chunk = bytes.get(pos..pos + 12 + len)?;
+        if PNG_METADATA_CHUNKS.contains(&chunk_type) {
+            stripped = true;
+        } else {
+            out.extend_from_slice(chunk);
+        }
+        pos += 12 + len;
+        if chunk_type == b"IEND" {
+            break;
+        }
+    }
+    stripped.then_some(out)
+}
+
+fn strip_webp(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = bytes[..12].to_vec();
+    let mut pos = 12;
+    let mut cleared_flags = 0u8;
+    while pos < bytes.len() {
+        let fourcc = bytes.get(pos..pos + 4)?;
+        let len = u32::from_le_bytes(bytes.get(pos + 4..pos + 8)?.try_into().ok()?) as usize;
+        // Chunks are padded to an even length
+        let end = (pos + 8 + len + (len & 1)).min(bytes.len());
+        match WEBP_METADATA_CHUNKS
+            .iter()
+            .find(|(name, _)| *name == fourcc)
+        {
+            Some((_, flag)) => cleared_flags |= flag,
+            None => out.extend_from_slice(&bytes[pos..end]),
+        }
+        pos = end;
+    }
+    if cleared_flags == 0 {
+        return None;
+    }
+
+    // The extended header advertises which chunks are present
+    if out.get(12..16) == Some(b"VP8X") {
+        *out.get_mut(20)? &= !cleared_flags;
+    }
+    let riff_size = u32::try_from(out.len() - 8).ok()?;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(out)
+}
+
+/// Strip metadata from every image already downloaded into data/stamps/
+pub fn run_normalize_images() -> Result<()> {
+    let mut files = 0;
+    let mut stripped = 0;
+    let mut saved_bytes = 0;
+    for year in fs::read_dir(STAMPS_DIR)?.flatten() {
+        for stamp in fs::read_dir(year.path())?.flatten() {
+            if !stamp.path().is_dir() {
+                continue;
+            }
+            for file in fs::read_dir(stamp.path())?.flatten() {
+                let path = file.path();
+                if !path.is_file() {
+                    continue;
+                }
+                files += 1;
+                if let Some(saved) = strip_file(&path)? {
+                    stripped += 1;
+                    saved_bytes += saved;
+                }
+            }
+        }
+    }
+    println!(
+        "Stripped metadata from {} of {} files, saving {:.1}KB",
+        stripped,
+        files,
+        saved_bytes as f64 / 1024.0
+    );
+    Ok(())
+}
+
+/// Rewrite `path` without metadata, returning the bytes saved if anything changed
+fn strip_file(path: &Path) -> Result<Option<usize>> {
+    let bytes = fs::read(path)?;
+    let Some(clean) = strip_metadata(&bytes) else {
+        return Ok(None);
+    };
+    fs::write(path, &clean)?;
+    Ok(Some(bytes.len() - clean.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_dimensions() {
+        let dir = std::env::temp_dir().join(format!("usps-dimensions-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend(1200u32.to_be_bytes());
+        png.extend(900u32.to_be_bytes());
+        png.resize(32, 0);
+        let mut gif = b"GIF89a".to_vec();
+        gif.extend([0x40, 0x01, 0xf0, 0x00]);
+        gif.resize(32, 0);
+        // SOI, an APP0 segment to skip, then SOF0 with height 480 and width 640
+        let mut jpeg = vec![0xff, 0xd8, 0xff, 0xe0, 0x00, 0x04, 0x00, 0x00];
+        jpeg.extend([0xff, 0xc0, 0x00, 0x11, 0x08, 0x01, 0xe0, 0x02, 0x80]);
+        jpeg.resize(40, 0);
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8X".to_vec();
+        webp.resize(24, 0);
+        webp.extend([0x7f, 0x02, 0x00, 0xdf, 0x01, 0x00]);
+
+        for (name, bytes, expected) in [
+            ("a.png", png, Some((1200, 900))),
+            ("a.gif", gif, Some((320, 240))),
+            ("a.jpg", jpeg, Some((640, 480))),
+            ("a.webp", webp, Some((640, 480))),
+            ("a.txt", vec![b'x'; 40], None),
+        ] {
+            fs::write(dir.join(name), bytes).unwrap();
+            assert_eq!(image_dimensions(&dir.join(name)), expected, "{}", name);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn jpeg_segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+        let mut segment = vec![0xFF, marker];
+        segment.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+        segment.extend_from_slice(payload);
+        segment
+    }
+
+    fn png_chunk(chunk_type: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(chunk_type);
+        chunk.extend_from_slice(data);
+        chunk.extend_from_slice(&[0; 4]); // CRC isn't checked
+        chunk
+    }
+
+    fn webp_chunk(fourcc: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut chunk = fourcc.to_vec();
+        chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    }
+
+    fn webp(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let body = chunks.concat();
+        let mut file = b"RIFF".to_vec();
+        file.extend_from_slice(&(body.len() as u32 + 4).to_le_bytes());
+        file.extend_from_slice(b"WEBP");
+        file.extend_from_slice(&body);
+        file
+    }
+
+    #[test]
+    fn test_strip_metadata() {
+        // JPEG: drop EXIF and comments, keep JFIF and the ICC profile
+        let jfif = jpeg_segment(0xE0, b"JFIF\0");
+        let icc = jpeg_segment(0xE2, b"ICC_PROFILE\0");
+        let scan = [0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9];
+        let jpeg = [
+            &[0xFF, 0xD8][..],
+            &jfif,
+            &jpeg_segment(0xE1, b"Exif\0\0camera"),
+            &icc,
+            &jpeg_segment(0xFE, b"made with a tool"),
+            &scan,
+        ]
+        .concat();
+        let clean = [&[0xFF, 0xD8][..], &jfif, &icc, &scan].concat();
+        assert_eq!(strip_metadata(&jpeg), Some(clean.clone()));
+        assert_eq!(strip_metadata(&clean), None);
+
+        // PNG: drop text chunks
+        let signature = b"\x89PNG\r\n\x1a\n";
+        let ihdr = png_chunk(b"IHDR", &[0; 13]);
+        let idat = png_chunk(b"IDAT", &[1, 2, 3]);
+        let iend = png_chunk(b"IEND", &[]);
+        let png = [
+            &signature[..],
+            &ihdr,
+            &png_chunk(b"tEXt", b"Software\0tool"),
+            &idat,
+            &iend,
+        ]
+        .concat();
+        let clean = [&signature[..], &ihdr, &idat, &iend].concat();
+        assert_eq!(strip_metadata(&png), Some(clean));
+
+        // WebP: drop EXIF and clear its VP8X flag
+        let image = webp_chunk(b"VP8L", &[1, 2, 3]);
+        let with_exif = webp(&[
+            webp_chunk(b"VP8X", &[0x08, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            image.clone(),
+            webp_chunk(b"EXIF", b"camera"),
+        ]);
+        let clean = webp(&[webp_chunk(b"VP8X", &[0; 10]), image]);
+        assert_eq!(strip_metadata(&with_exif), Some(clean));
+
+        assert_eq!(strip_metadata(b"GIF89a"), None);
+        // Truncated segments are left alone
+        assert_eq!(strip_metadata(&jpeg[..10]), None);
+    }
+}
@@ -0,0 +1,218 @@
+//! Import curated stamps that aren't in the stampsforever.com API
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::init_database;
+use crate::scrape::{DATABASE_FILE, STAMPS_DIR, VALID_RATE_TYPES};
+use usps_rates::detect_stamp_type;
+use usps_rates::types::{Credits, RateType, StampMetadata, StampType};
+
+/// One row of the import CSV (header: name,year,issue_date,rate,rate_type,slug)
+#[derive(Debug, Deserialize)]
+struct CsvStamp {
+    name: String,
+    year: u32,
+    issue_date: Option<String>,
+    rate: Option<f64>,
+    rate_type: String,
+    slug: String,
+}
+
+/// Validate a CSV row, returning its ISO issue date (if any)
+fn validate_row(row: &CsvStamp) -> Result<Option<String>> {
+    if row.slug.trim().is_empty() || row.name.trim().is_empty() {
+        bail!("name and slug are required");
+    }
+    if !VALID_RATE_TYPES.contains(&row.rate_type.as_str()) {
+        bail!(
+            "Invalid rate_type '{}'. Valid values: {:?}",
+            row.rate_type,
+            VALID_RATE_TYPES
+        );
+    }
+    let issue_date = match row.issue_date.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(d) => {
+            NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                .with_context(|| format!("Invalid issue_date '{}' (expected YYYY-MM-DD)", d))?;
+            Some(d.to_string())
+        }
+    };
+    Ok(issue_date)
+}
+
+/// Import stamps from a curated CSV into stamps.db and data/stamps/
+///
+/// Rows are inserted into `stamps`, and a `metadata.conl` is written so
+/// `generate` picks them up. Slugs already in the database (or repeated in
+/// the CSV) are skipped. Imported stamps have no stampsforever.com page, so
+/// their `url` is left empty and they stay out of `stampsforever_stamps`,
+/// which `scrape` works from.
+pub fn run_import_csv(path: &Path) -> Result<()> {
+    let mut conn = Connection::open(DATABASE_FILE)?;
+    init_database(&conn)?;
+    let (imported, skipped) = import_csv(&mut conn, path, Path::new(STAMPS_DIR))?;
+
+    println!(
+        "Imported {} stamps from {} ({} already present)",
+        imported,
+        path.display(),
+        skipped
+    );
+    Ok(())
+}
+
+/// Import `path` into `conn` and `stamps_dir`, returning (imported, skipped) counts
+fn import_csv(conn: &mut Connection, path: &Path, stamps_dir: &Path) -> Result<(usize, usize)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+
+    // Validate every row before touching the database
+    let mut rows: Vec<(CsvStamp, Option<String>)> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for (i, result) in reader.deserialize::<CsvStamp>().enumerate() {
+        // Line 1 is the header
+        let line = i + 2;
+        let row = result.with_context(|| format!("{}:{}", path.display(), line))?;
+        let issue_date = validate_row(&row)
+            .with_context(|| format!("{}:{} ({})", path.display(), line, row.slug))?;
+        if !seen.insert(row.slug.clone()) {
+            eprintln!("  Skipping duplicate slug '{}' on line {}", row.slug, line);
+            continue;
+        }
+        rows.push((row, issue_date));
+    }
+
+    let tx = conn.transaction()?;
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    for (row, issue_date) in &rows {
+        let existing: Option<String> = tx
+            .query_row(
+                "SELECT slug FROM stampsforever_stamps WHERE slug = ?1
+                 UNION SELECT slug FROM stamps WHERE slug = ?1",
+                [&row.slug],
+                |r| r.get(0),
+            )
+            .optional()?;
+        if existing.is_some() {
+            println!("  Skipping '{}': already in {}", row.slug, DATABASE_FILE);
+            skipped += 1;
+            continue;
+        }
+
+        let rate_type_name = RateType::canonical_str(&row.rate_type);
+        let rate_type = RateType::from_str(rate_type_name);
+        let forever = rate_type.is_forever(row.year);
+        let rate = row.rate.map(|r| format!("{:.2}", r));
+        let stamp_type = detect_stamp_type(&row.name);
+
+        tx.execute(
+            "INSERT INTO stamps (slug, api_slug, name, url, year, issue_date, rate, rate_type, type, forever)
+             VALUES (?1, ?1, ?2, '', ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                row.slug,
+                row.name,
+                row.year,
+                issue_date,
+                rate,
+                rate_type_name,
+                stamp_type,
+                forever as i32
+            ],
+        )?;
+
+        let metadata = StampMetadata {
+            name: row.name.clone(),
+            slug: row.slug.clone(),
+            api_slug: row.slug.clone(),
+            url: String::new(),
+            year: row.year,
+            issue_date: issue_date.clone(),
+            issue_location: None,
+            rate: row.rate,
+            rate_type: Some(rate_type),
+            extra_cost: None,
+            forever,
+            stamp_type: StampType::from_str(stamp_type),
+            series: None,
+            tags: Vec::new(),
+            stamp_images: Vec::new(),
+            sheet_image: None,
+            sheet_image_size: None,
+            background_color: None,
+            credits: Credits::default(),
+            about: None,
+            products: Vec::new(),
+            hidden: None,
+            canceled: None,
+            pair_with: None,
+        };
+        let stamp_dir = stamps_dir.join(row.year.to_string()).join(&row.slug);
+        fs::create_dir_all(&stamp_dir)?;
+        fs::write(stamp_dir.join("metadata.conl"), metadata.to_conl()?)?;
+
+        imported += 1;
+    }
+
+    tx.commit().context("Failed to commit imported stamps")?;
+    Ok((imported, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_csv() {
+        let dir = std::env::temp_dir().join(format!("usps-import-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let csv_path = dir.join("stamps.csv");
+        fs::write(
+            &csv_path,
+            "name,year,issue_date,rate,rate_type,slug\n\
+             Harbor Lights,1995,1995-06-01,0.32,First Class,harbor-lights-1995\n\
+             Harbor Lights,1995,,,First Class,harbor-lights-1995\n",
+        )
+        .unwrap();
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let stamps_dir = dir.join("stamps");
+
+        assert_eq!(
+            import_csv(&mut conn, &csv_path, &stamps_dir).unwrap(),
+            (1, 0)
+        );
+        let conl =
+            fs::read_to_string(stamps_dir.join("1995/harbor-lights-1995/metadata.conl")).unwrap();
+        assert!(conl.contains("name = Harbor Lights\n"));
+        assert!(conl.contains("issue_date = 1995-06-01\n"));
+        assert!(conl.contains("rate_type = First Class\n"));
+
+        // Imported stamps have no API page, so scrape must never pick them up
+        let listed: usize = conn
+            .query_row("SELECT COUNT(*) FROM stampsforever_stamps", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(listed, 0);
+        assert!(crate::scrape::incomplete_stamps(&conn).unwrap().is_empty());
+
+        // A second import skips what's already there
+        assert_eq!(
+            import_csv(&mut conn, &csv_path, &stamps_dir).unwrap(),
+            (0, 1)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
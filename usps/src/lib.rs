@@ -0,0 +1,80 @@
+//! USPS postage rates and stamp metadata
+//!
+//! The reusable pieces of `usps-rates`: rate history lookups, stamp metadata
+//! types with CONL read/write, and slug transformation. The `usps-rates`
+//! binary is a thin CLI over this library.
+
+pub mod rates;
+pub mod slug;
+pub mod types;
+pub mod utils;
+
+pub use rates::{PostalRates, RateError, RateHistory};
+pub use slug::generate_slug;
+pub use types::*;
+
+pub const STAMPS_API_URL: &str = "https://admin.stampsforever.com/api/stamp-issuances";
+pub const MIN_SCRAPE_YEAR: u32 = 1996;
+
+/// Parse date string like "June 17, 2025" to ISO 8601 "2025-06-17"
+/// Returns None for TBA dates, panics on invalid date format
+pub fn parse_date_to_iso(date_str: &str) -> Option<String> {
+    let date_str = date_str.trim();
+
+    // Skip TBA dates
+    if date_str.starts_with("TBA") || date_str.is_empty() {
+        return None;
+    }
+
+    let months = [
+        ("January", "01"),
+        ("February", "02"),
+        ("March", "03"),
+        ("April", "04"),
+        ("May", "05"),
+        ("June", "06"),
+        ("July", "07"),
+        ("August", "08"),
+        ("September", "09"),
+        ("October", "10"),
+        ("November", "11"),
+        ("December", "12"),
+    ];
+
+    // Parse "Month Day, Year" format
+    for (month_name, month_num) in &months {
+        if date_str.starts_with(month_name) {
+            let rest = date_str[month_name.len()..].trim();
+            // Parse "Day, Year"
+            if let Some((day_str, year_str)) = rest.split_once(',') {
+                let day: u32 = day_str
+                    .trim()
+                    .parse()
+                    .unwrap_or_else(|_| panic!("Failed to parse day from date: '{}'", date_str));
+                let year: u32 = year_str
+                    .trim()
+                    .parse()
+                    .unwrap_or_else(|_| panic!("Failed to parse year from date: '{}'", date_str));
+                return Some(format!("{:04}-{}-{:02}", year, month_num, day));
+            }
+        }
+    }
+
+    panic!(
+        "Failed to parse date: '{}'. Expected format 'Month Day, Year'",
+        date_str
+    );
+}
+
+/// Detect stamp type based on name
+/// Returns "card" for stamped cards, "envelope" for stamped envelopes, "stamp" otherwise
+pub fn detect_stamp_type(name: &str) -> &'static str {
+    let lower = name.to_lowercase();
+    if lower.contains("stamped card") || lower.contains("postal card") {
+        "card"
+    } else if lower.contains("stamped envelope") || lower.contains("postal envelope") {
+        "envelope"
+    } else {
+        "stamp"
+    }
+}
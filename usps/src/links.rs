@@ -0,0 +1,134 @@
+//! Broken link checking for the generated site
+
+use anyhow::{bail, Result};
+use scraper::{Html, Selector};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An internal href/src that doesn't resolve to a file in the output directory
+#[derive(Debug)]
+struct BrokenLink {
+    /// Page containing the link, relative to the output directory
+    page: PathBuf,
+    /// The href/src value as written
+    target: String,
+}
+
+/// Recursively collect generated HTML files
+fn html_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            html_files(&path, files)?;
+        } else if path.extension().is_some_and(|e| e == "html") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Map an internal link to the file it should resolve to, or None for external links
+fn resolve_target(output_dir: &Path, page_dir: &Path, target: &str) -> Option<PathBuf> {
+    // Drop fragment and query; a bare "#anchor" points at the page itself
+    let path = target.split(['#', '?']).next().unwrap_or("");
+    if path.is_empty() || path.starts_with("//") || path.contains(':') {
+        return None;
+    }
+
+    let resolved = match path.strip_prefix('/') {
+        Some(rest) => output_dir.join(rest),
+        None => page_dir.join(path),
+    };
+    // Directory URLs are served from their index.html
+    if path.ends_with('/') || resolved.is_dir() {
+        Some(resolved.join("index.html"))
+    } else {
+        Some(resolved)
+    }
+}
+
+/// Find internal hrefs and img srcs in `output_dir` that don't exist on disk
+///
+/// Symlinked images are followed, so a link to a missing source image is broken too.
+fn find_broken_links(output_dir: &Path) -> Result<Vec<BrokenLink>> {
+    let selector = Selector::parse("[href], [src]").expect("valid selector");
+    let mut pages = Vec::new();
+    html_files(output_dir, &mut pages)?;
+    pages.sort();
+
+    let mut broken = Vec::new();
+    for page in &pages {
+        let document = Html::parse_document(&fs::read_to_string(page)?);
+        let page_dir = page.parent().unwrap_or(output_dir);
+        for element in document.select(&selector) {
+            let attrs = element.value();
+            for target in [attrs.attr("href"), attrs.attr("src")]
+                .into_iter()
+                .flatten()
+            {
+                let Some(file) = resolve_target(output_dir, page_dir, target) else {
+                    continue;
+                };
+                if !file.exists() {
+                    broken.push(BrokenLink {
+                        page: page.strip_prefix(output_dir).unwrap_or(page).to_path_buf(),
+                        target: target.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(broken)
+}
+
+/// Report broken internal links in the generated site, failing if there are any
+pub fn run_check_links(output_dir: &Path) -> Result<()> {
+    println!("Checking links in {}/...", output_dir.display());
+    let broken = find_broken_links(output_dir)?;
+
+    if broken.is_empty() {
+        println!("No broken links.");
+        return Ok(());
+    }
+
+    for link in &broken {
+        eprintln!("  {}: {}", link.page.display(), link.target);
+    }
+    bail!("{} broken internal link(s)", broken.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_broken_links() {
+        let root = std::env::temp_dir().join(format!("usps-links-{}", std::process::id()));
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        fs::create_dir_all(root.join("stamps/ok")).unwrap();
+        fs::write(root.join("stamps/ok/index.html"), "<p>ok</p>").unwrap();
+        fs::write(root.join("logo.png"), "").unwrap();
+        fs::write(
+            root.join("index.html"),
+            r##"<a href="/stamps/ok/">ok</a>
+<a href="/stamps/ok/#top">anchor</a>
+<a href="stamps/ok">relative</a>
+<a href="/stamps/missing/">missing</a>
+<a href="#top">self</a>
+<a href="https://www.stampsforever.com/">external</a>
+<img src="/logo.png"><img src="/images/2025/gone.png">"##,
+        )
+        .unwrap();
+
+        let broken: Vec<String> = find_broken_links(&root)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.target)
+            .collect();
+        assert_eq!(broken, vec!["/stamps/missing/", "/images/2025/gone.png"]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,77 +1,35 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand};
 use rusqlite::Connection;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 mod enrichment;
+mod export;
+mod fetch;
 mod generate;
-mod rates;
+mod images;
+mod import;
+mod links;
+mod progress;
 mod scrape;
+mod serve;
 mod simple;
 mod sync;
-mod types;
-mod utils;
-
-pub use types::*;
-
-pub const STAMPS_API_URL: &str = "https://admin.stampsforever.com/api/stamp-issuances";
-pub const MIN_SCRAPE_YEAR: u32 = 1996;
-
-/// Parse date string like "June 17, 2025" to ISO 8601 "2025-06-17"
-/// Returns None for TBA dates, panics on invalid date format
-pub fn parse_date_to_iso(date_str: &str) -> Option<String> {
-    let date_str = date_str.trim();
-
-    // Skip TBA dates
-    if date_str.starts_with("TBA") || date_str.is_empty() {
-        return None;
-    }
-
-    let months = [
-        ("January", "01"),
-        ("February", "02"),
-        ("March", "03"),
-        ("April", "04"),
-        ("May", "05"),
-        ("June", "06"),
-        ("July", "07"),
-        ("August", "08"),
-        ("September", "09"),
-        ("October", "10"),
-        ("November", "11"),
-        ("December", "12"),
-    ];
-
-    // Parse "Month Day, Year" format
-    for (month_name, month_num) in &months {
-        if date_str.starts_with(month_name) {
-            let rest = date_str[month_name.len()..].trim();
-            // Parse "Day, Year"
-            if let Some((day_str, year_str)) = rest.split_once(',') {
-                let day: u32 = day_str
-                    .trim()
-                    .parse()
-                    .unwrap_or_else(|_| panic!("Failed to parse day from date: '{}'", date_str));
-                let year: u32 = year_str
-                    .trim()
-                    .parse()
-                    .unwrap_or_else(|_| panic!("Failed to parse year from date: '{}'", date_str));
-                return Some(format!("{:04}-{}-{:02}", year, month_num, day));
-            }
-        }
-    }
-
-    panic!(
-        "Failed to parse date: '{}'. Expected format 'Month Day, Year'",
-        date_str
-    );
-}
 
 #[derive(Parser)]
 #[command(name = "usps-rates")]
 #[command(about = "USPS postage rates and stamp scraper")]
 struct Cli {
+    /// Directory for cached HTTP responses and images
+    #[arg(long, global = true, default_value = "cache")]
+    cache_dir: PathBuf,
+
+    /// Ignore cached responses for this run (fresh responses are still cached)
+    #[arg(long, global = true)]
+    no_cache: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -79,7 +37,26 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Fetch simple USPS postage rates (domestic and international)
-    Simple,
+    Simple {
+        /// Output format printed to stdout (rates.json is always written)
+        #[arg(long, value_enum, default_value = "json")]
+        format: simple::OutputFormat,
+    },
+    /// Check live USPS rates against the recorded rate history
+    Rates {
+        #[command(subcommand)]
+        action: RatesAction,
+    },
+    /// Manage the HTTP response cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Check the per-stamp overrides in enrichment/stamps/
+    Overrides {
+        #[command(subcommand)]
+        action: OverridesAction,
+    },
     /// Scrape all stamps from stampsforever.com into SQLite
     Stamps {
         #[command(subcommand)]
@@ -87,6 +64,37 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum RatesAction {
+    /// Compare live domestic rates to enrichment/rates/*.conl at today's date
+    Diff,
+    /// Append rates from a rates.json to enrichment/rates/*.conl when they've changed
+    Record {
+        /// rates.json written by `simple`
+        #[arg(default_value = "rates.json")]
+        input: PathBuf,
+        /// Date the rates took effect (defaults to today)
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        date: Option<chrono::NaiveDate>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Remove cached API responses (and their images) for slugs no longer in stamps.db
+    Prune {
+        /// List what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum OverridesAction {
+    /// Report override keys that match no stamp in stamps.db for their year
+    Check,
+}
+
 #[derive(Subcommand)]
 enum StampsAction {
     /// Build/update the stamps SQLite database from API
@@ -94,18 +102,104 @@ enum StampsAction {
         /// Output SQLite database file
         #[arg(short, long, default_value = "stamps.db")]
         output: String,
+        /// Stamps fetched per API request (use e.g. 5000 for a single request)
+        #[arg(long, default_value_t = 500, value_parser = clap::value_parser!(u32).range(1..))]
+        per_page: u32,
+        /// Sync from a saved API dump (path or file:// URL) or another API URL
+        #[arg(long, value_name = "PATH_OR_URL")]
+        source: Option<String>,
+    },
+    /// Import curated stamps from a CSV (name,year,issue_date,rate,rate_type,slug)
+    ImportCsv {
+        /// CSV file with a header row
+        path: PathBuf,
+    },
+    /// Export the products table to CSV (stamp_slug,year,title,price,postal_store_url)
+    ExportProducts {
+        /// CSV file to write (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
     /// Scrape detailed stamp info, images, and metadata
     Scrape {
         /// Specific stamp slug or year (e.g., "love-2026" or "2025")
         #[arg(value_name = "SLUG_OR_YEAR")]
         filter: Option<String>,
-        /// Quiet mode - suppress progress output
-        #[arg(short, long)]
-        quiet: bool,
+        /// Skip stamps up to and including this API slug in the work list
+        #[arg(long)]
+        after_slug: Option<String>,
+        /// Maximum number of stamps to scrape
+        #[arg(long)]
+        limit: Option<usize>,
+        #[command(flatten)]
+        args: ScrapeArgs,
+    },
+    /// Re-scrape stamps missing images or a rate type
+    RescrapeMissing {
+        /// Skip stamps up to and including this API slug in the work list
+        #[arg(long)]
+        after_slug: Option<String>,
+        /// Maximum number of stamps to scrape
+        #[arg(long)]
+        limit: Option<usize>,
+        #[command(flatten)]
+        args: ScrapeArgs,
     },
     /// Generate static HTML site in output/ directory
-    Generate,
+    Generate {
+        /// Include hidden rate types (Federal Duck, Presorted, Nonprofit)
+        #[arg(long)]
+        include_hidden: bool,
+        /// Only include stamps issued in or after this year
+        #[arg(long, default_value_t = generate::MIN_YEAR)]
+        min_year: u32,
+        /// Only include stamps issued in or before this year
+        #[arg(long)]
+        max_year: Option<u32>,
+        /// Minify generated HTML and CSS (default output stays readable for diffs)
+        #[arg(long)]
+        minify: bool,
+        /// Only include stamps that can still be bought (have product listings)
+        #[arg(long)]
+        only_with_products: bool,
+        /// Leave out stamps of this rate type (repeatable)
+        #[arg(
+            long,
+            value_name = "RATE_TYPE",
+            value_parser = clap::builder::PossibleValuesParser::new(scrape::VALID_RATE_TYPES)
+        )]
+        exclude_rate_type: Vec<String>,
+        /// Price forever stamps at this date's rates instead of today's (YYYY-MM-DD)
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        as_of: Option<chrono::NaiveDate>,
+        /// Fail if any generated page links to a missing page or image
+        #[arg(long)]
+        check_links: bool,
+        /// Serve output/ on localhost after generating
+        #[arg(long)]
+        serve: bool,
+        /// Port for --serve
+        #[arg(long, default_value_t = 8000, requires = "serve")]
+        port: u16,
+        /// Open the site in the default browser when done (the served URL with --serve)
+        #[arg(long)]
+        open: bool,
+        /// Rebuild only this stamp's page and image links (slug or API slug), skipping index pages
+        #[arg(long, value_name = "SLUG")]
+        only: Option<String>,
+        /// Exit with an error if no stamps load instead of succeeding with nothing generated
+        /// (always on when the CI environment variable is set)
+        #[arg(long)]
+        fail_on_empty: bool,
+        /// Wipe and rebuild output/ in place instead of building beside it and swapping it in
+        #[arg(long)]
+        no_atomic: bool,
+        /// Leave out stamps that haven't been issued yet (future or TBA issue date)
+        #[arg(long)]
+        hide_upcoming: bool,
+        #[command(flatten)]
+        site: SiteArgs,
+    },
     /// Enrich stamps with AI image analysis (uses Gemini API)
     Enrich {
         /// Specific stamp slug or year (e.g., "love-2026" or "2025")
@@ -117,24 +211,60 @@ enum StampsAction {
         /// Force regeneration of existing enrichment data
         #[arg(short, long)]
         force: bool,
+        /// Emit JSONL records (one per image, plus a summary) instead of progress output
+        #[arg(long)]
+        json_logs: bool,
+        /// Gemini model to use (defaults to $GEMINI_MODEL, then the built-in model)
+        #[arg(long)]
+        model: Option<String>,
+        /// Also run this model on each image and report agreement (writes to logs/compare/)
+        #[arg(long, value_name = "MODEL2")]
+        compare: Option<String>,
+        /// Only reprocess images that failed in earlier runs (logs/enrichment-errors.json)
+        #[arg(long, conflicts_with = "force")]
+        retry_errors: bool,
+        /// Analyze every image of each stamp, not just the first (one request per image)
+        #[arg(long)]
+        all_images: bool,
+        /// Print the prompt and request JSON for the first image, then exit without sending
+        #[arg(long, conflicts_with = "compare")]
+        dump_prompt: bool,
+    },
+    /// Print local paths and links for a stamp (no network)
+    Open {
+        /// Stamp slug or API slug (e.g., "love-2026")
+        slug: String,
+    },
+    /// Show a stamp's API data and prompt for overrides (rate_type, rate, issue date/location)
+    Fix {
+        /// Stamp slug or API slug (e.g., "healing-ptsd")
+        slug: String,
+    },
+    /// Search scraped stamps by name (case-insensitive)
+    Search {
+        /// Text to find in stamp names (e.g., "flag")
+        query: String,
+        /// Only show stamps issued in this year
+        #[arg(long)]
+        year: Option<u32>,
+        /// Also match series names
+        #[arg(long)]
+        series: bool,
+    },
+    /// List years with no scraped stamps, or suspiciously few (read-only)
+    Years {
+        /// Flag years with fewer scraped stamps than this
+        #[arg(long, default_value_t = 10)]
+        min_count: u32,
     },
+    /// Check scraped rates against the rate history, e.g. Two Ounce = 1oz + additional (read-only)
+    ValidateRates,
+    /// Strip EXIF and other metadata from images already in data/stamps/ (lossless)
+    NormalizeImages,
     /// Clean generated files (stamps.db and data/ folder)
     Clean,
 }
 
-/// Detect stamp type based on name
-/// Returns "card" for stamped cards, "envelope" for stamped envelopes, "stamp" otherwise
-pub fn detect_stamp_type(name: &str) -> &'static str {
-    let lower = name.to_lowercase();
-    if lower.contains("stamped card") || lower.contains("postal card") {
-        "card"
-    } else if lower.contains("stamped envelope") || lower.contains("postal envelope") {
-        "envelope"
-    } else {
-        "stamp"
-    }
-}
-
 pub fn init_database(conn: &Connection) -> Result<()> {
     // Read and execute schema from SQL file
     let schema = include_str!("../schema.sql");
@@ -163,18 +293,256 @@ fn run_clean() -> Result<()> {
     Ok(())
 }
 
+/// Product kinds to keep when scraping (see `scrape::ProductFilter`)
+#[derive(Args)]
+struct ProductFilterArgs {
+    /// Keep first day cover listings
+    #[arg(long)]
+    include_first_day_covers: bool,
+    /// Keep coil strip listings
+    #[arg(long)]
+    include_strips: bool,
+    /// Drop press sheet listings
+    #[arg(long)]
+    no_press_sheets: bool,
+}
+
+impl From<ProductFilterArgs> for scrape::ProductFilter {
+    fn from(args: ProductFilterArgs) -> Self {
+        Self {
+            include_first_day_covers: args.include_first_day_covers,
+            include_strips: args.include_strips,
+            include_press_sheets: !args.no_press_sheets,
+        }
+    }
+}
+
+/// Site-wide strings for `generate` (see `generate::SiteConfig`); unset ones keep the defaults
+#[derive(Args)]
+struct SiteArgs {
+    /// Public URL the site is served from, for canonical links and calendar event links
+    #[arg(long, value_name = "URL")]
+    base_url: Option<String>,
+    /// Site name shown in the header and page titles
+    #[arg(long)]
+    site_title: Option<String>,
+    /// Language code for <html lang>
+    #[arg(long)]
+    lang: Option<String>,
+    /// File of raw HTML to use as the page footer
+    #[arg(long, value_name = "FILE")]
+    footer_html: Option<PathBuf>,
+}
+
+impl TryFrom<SiteArgs> for generate::SiteConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(args: SiteArgs) -> Result<Self> {
+        let defaults = Self::default();
+        let footer_html = match args.footer_html {
+            Some(path) => fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?,
+            None => defaults.footer_html,
+        };
+        Ok(Self {
+            title: args.site_title.unwrap_or(defaults.title),
+            lang: args.lang.unwrap_or(defaults.lang),
+            base_url: args.base_url,
+            footer_html,
+        })
+    }
+}
+
+/// Flags shared by `scrape` and `rescrape-missing` (see `scrape::ScrapeOptions`)
+#[derive(Args)]
+struct ScrapeArgs {
+    /// Quiet mode - suppress progress output
+    #[arg(short, long)]
+    quiet: bool,
+    /// Exit non-zero if any stamp warns (missing rate_type, issue_date, or images)
+    #[arg(long)]
+    strict: bool,
+    /// Refresh the database and metadata.conl without downloading images
+    #[arg(long, conflicts_with = "images_only")]
+    metadata_only: bool,
+    /// Download images without rewriting the database or metadata.conl
+    #[arg(long)]
+    images_only: bool,
+    /// Download all stamp details and images into the cache concurrently first
+    #[arg(long, conflicts_with = "metadata_only")]
+    prefetch: bool,
+    #[command(flatten)]
+    products: ProductFilterArgs,
+    /// Slug scheme: "new" adds rate/denomination and year, "api" keeps the API slug
+    #[arg(long, value_enum, default_value_t)]
+    slug_format: scrape::SlugFormat,
+    /// Which wins when an override and the API both set a field
+    #[arg(long, value_enum, default_value_t)]
+    prefer: scrape::Precedence,
+    /// Minimum milliseconds between API and image requests (cache hits aren't limited)
+    #[arg(long, value_name = "MS", default_value_t = scrape::DEFAULT_REQUEST_INTERVAL.as_millis() as u64)]
+    request_interval_ms: u64,
+    /// Skip images larger than this many bytes instead of downloading and caching them
+    #[arg(long, value_name = "BYTES", default_value_t = scrape::DEFAULT_MAX_IMAGE_BYTES)]
+    max_image_bytes: u64,
+}
+
+impl From<ScrapeArgs> for scrape::ScrapeOptions {
+    fn from(args: ScrapeArgs) -> Self {
+        let mode = if args.metadata_only {
+            scrape::ScrapeMode::MetadataOnly
+        } else if args.images_only {
+            scrape::ScrapeMode::ImagesOnly
+        } else {
+            scrape::ScrapeMode::Full
+        };
+        Self {
+            quiet: args.quiet,
+            strict: args.strict,
+            mode,
+            prefetch: args.prefetch,
+            products: args.products.into(),
+            slug_format: args.slug_format,
+            prefer: args.prefer,
+            request_interval: Duration::from_millis(args.request_interval_ms),
+            max_image_bytes: args.max_image_bytes,
+            data_dir: PathBuf::from(scrape::STAMPS_DIR),
+        }
+    }
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Simple => simple::run_simple(),
+        Commands::Simple { format } => simple::run_simple(format),
+        Commands::Rates { action } => match action {
+            RatesAction::Diff => simple::run_rates_diff(),
+            RatesAction::Record { input, date } => simple::run_rates_record(&input, date),
+        },
+        Commands::Cache { action } => match action {
+            CacheAction::Prune { dry_run } => scrape::run_cache_prune(&cli.cache_dir, dry_run),
+        },
+        Commands::Overrides { action } => match action {
+            OverridesAction::Check => scrape::run_overrides_check(),
+        },
         Commands::Stamps { action } => match action {
-            StampsAction::Sync { output } => sync::run_sync(&output),
-            StampsAction::Scrape { filter, quiet } => scrape::run_scrape(filter, quiet),
-            StampsAction::Generate => generate::run_generate(),
-            StampsAction::Enrich { filter, quiet, force } => {
-                enrichment::run_enrich(filter, quiet, force)
+            StampsAction::Sync {
+                output,
+                per_page,
+                source,
+            } => sync::run_sync(&output, per_page, source.as_deref()),
+            StampsAction::ImportCsv { path } => import::run_import_csv(&path),
+            StampsAction::ExportProducts { output } => {
+                export::run_export_products(output.as_deref())
+            }
+            StampsAction::Scrape {
+                filter,
+                after_slug,
+                limit,
+                args,
+            } => scrape::run_scrape(
+                filter,
+                &cli.cache_dir,
+                cli.no_cache,
+                after_slug.as_deref(),
+                limit,
+                &args.into(),
+            ),
+            StampsAction::RescrapeMissing {
+                after_slug,
+                limit,
+                args,
+            } => scrape::run_rescrape_missing(
+                &cli.cache_dir,
+                cli.no_cache,
+                after_slug.as_deref(),
+                limit,
+                &args.into(),
+            ),
+            StampsAction::Generate {
+                include_hidden,
+                min_year,
+                max_year,
+                minify,
+                only_with_products,
+                exclude_rate_type,
+                as_of,
+                check_links,
+                serve,
+                port,
+                open,
+                only,
+                fail_on_empty,
+                no_atomic,
+                hide_upcoming,
+                site,
+            } => {
+                let options = generate::GenerateOptions {
+                    include_hidden,
+                    min_year,
+                    max_year,
+                    data_dir: PathBuf::from(generate::DATA_DIR),
+                    series_file: PathBuf::from(generate::SERIES_FILE),
+                    output_dir: PathBuf::from(generate::OUTPUT_DIR),
+                    minify,
+                    only_with_products,
+                    exclude_rate_types: exclude_rate_type,
+                    as_of: as_of.unwrap_or_else(|| chrono::Local::now().date_naive()),
+                    only,
+                    fail_on_empty: fail_on_empty || std::env::var_os("CI").is_some(),
+                    atomic: !no_atomic,
+                    hide_upcoming,
+                    site: site.try_into()?,
+                };
+                generate::run_generate(&options)?;
+                if check_links {
+                    links::run_check_links(&options.output_dir)?;
+                }
+                if serve {
+                    serve::run_serve(&options.output_dir, port, open)?;
+                } else if open {
+                    let index = options.output_dir.join("index.html");
+                    let index = fs::canonicalize(&index).unwrap_or(index);
+                    serve::open_in_browser(&format!("file://{}", index.display()));
+                }
+                Ok(())
             }
+            StampsAction::Enrich {
+                filter,
+                quiet,
+                force,
+                json_logs,
+                model,
+                compare,
+                retry_errors,
+                all_images,
+                dump_prompt,
+            } => enrichment::run_enrich(
+                enrichment::EnrichOptions {
+                    filter,
+                    quiet,
+                    force,
+                    json_logs,
+                    model,
+                    compare_model: compare,
+                    retry_errors,
+                    all_images,
+                    dump_prompt,
+                },
+                &cli.cache_dir,
+                cli.no_cache,
+            ),
+            StampsAction::Open { slug } => scrape::run_open(&slug),
+            StampsAction::Fix { slug } => scrape::run_fix(&slug, &cli.cache_dir, cli.no_cache),
+            StampsAction::Search {
+                query,
+                year,
+                series,
+            } => scrape::run_search(&query, year, series),
+            StampsAction::Years { min_count } => scrape::run_years(min_count),
+            StampsAction::ValidateRates => scrape::run_validate_rates(),
+            StampsAction::NormalizeImages => images::run_normalize_images(),
             StampsAction::Clean => run_clean(),
         },
     }
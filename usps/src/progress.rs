@@ -0,0 +1,130 @@
+//! Progress bar with an ETA for long scrape/enrich runs
+//!
+//! On a terminal the bar is redrawn in place below the per-item output; when stdout
+//! is redirected it falls back to a plain status line every `LINE_INTERVAL`.
+
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+/// Width of the `[=====>    ]` bar in characters
+const BAR_WIDTH: usize = 30;
+
+/// How often to print a status line when stdout isn't a terminal
+const LINE_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct ProgressBar {
+    total: usize,
+    done: usize,
+    start: Instant,
+    enabled: bool,
+    tty: bool,
+    last_line: Instant,
+}
+
+impl ProgressBar {
+    /// Start timing `total` items; nothing is printed when `quiet`
+    pub fn new(total: usize, quiet: bool) -> Self {
+        let now = Instant::now();
+        ProgressBar {
+            total,
+            done: 0,
+            start: now,
+            enabled: !quiet,
+            tty: io::stdout().is_terminal(),
+            last_line: now,
+        }
+    }
+
+    /// Mark `n` more items finished and redraw
+    pub fn advance(&mut self, n: usize) {
+        self.done = (self.done + n).min(self.total);
+        if !self.enabled {
+            return;
+        }
+        let status = status_line(self.done, self.total, self.start.elapsed());
+        if self.tty {
+            print!("\r\x1b[K{}", status);
+            let _ = io::stdout().flush();
+        } else if self.done == self.total || self.last_line.elapsed() >= LINE_INTERVAL {
+            println!("{}", status);
+            self.last_line = Instant::now();
+        }
+    }
+
+    /// Erase the bar so the next item's output starts on a clean line
+    pub fn clear(&self) {
+        if self.enabled && self.tty && self.done > 0 {
+            print!("\r\x1b[K");
+            let _ = io::stdout().flush();
+        }
+    }
+}
+
+/// `[=====>    ] 120/3000 4% 1m02s elapsed, ETA 25m10s`
+///
+/// The ETA assumes the remaining items take the average time of the finished ones.
+fn status_line(done: usize, total: usize, elapsed: Duration) -> String {
+    let fraction = if total == 0 {
+        1.0
+    } else {
+        done as f64 / total as f64
+    };
+    let filled = (fraction * BAR_WIDTH as f64) as usize;
+    let bar = if filled >= BAR_WIDTH {
+        "=".repeat(BAR_WIDTH)
+    } else {
+        format!(
+            "{}>{}",
+            "=".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled - 1)
+        )
+    };
+    let eta = if done == 0 {
+        "--".to_string()
+    } else {
+        format_duration(elapsed.mul_f64((total - done) as f64 / done as f64))
+    };
+    format!(
+        "[{}] {}/{} {}% {} elapsed, ETA {}",
+        bar,
+        done,
+        total,
+        (fraction * 100.0) as u32,
+        format_duration(elapsed),
+        eta
+    )
+}
+
+/// Compact duration: `45s`, `3m05s`, `1h02m`
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_line() {
+        assert_eq!(
+            status_line(0, 4, Duration::ZERO),
+            "[>                             ] 0/4 0% 0s elapsed, ETA --"
+        );
+        // 1 of 4 took 62s, so the other 3 should take 186s
+        assert_eq!(
+            status_line(1, 4, Duration::from_secs(62)),
+            "[=======>                      ] 1/4 25% 1m02s elapsed, ETA 3m06s"
+        );
+        assert_eq!(
+            status_line(4, 4, Duration::from_secs(3725)),
+            "[==============================] 4/4 100% 1h02m elapsed, ETA 0s"
+        );
+    }
+}
@@ -1,12 +1,63 @@
 //! Historical postal rate data and lookup functions
 
-use anyhow::{Context, Result};
 use chrono::NaiveDate;
 use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 
-const RATES_DIR: &str = "enrichment/rates";
+use crate::types::RateType;
+
+pub const RATES_DIR: &str = "enrichment/rates";
+
+/// Errors from loading or looking up rate history
+#[derive(Debug)]
+pub enum RateError {
+    /// The rate file couldn't be read
+    FileNotFound { path: PathBuf, source: io::Error },
+    /// A rate file or date string was malformed
+    ParseError(String),
+    /// The date is earlier than the first recorded rate
+    DateBeforeRange(NaiveDate),
+    /// No rate history exists for this rate type
+    UnknownRateType(String),
+}
+
+impl fmt::Display for RateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateError::FileNotFound { path, source } => {
+                write!(f, "Failed to read rate file {}: {}", path.display(), source)
+            }
+            RateError::ParseError(message) => write!(f, "{}", message),
+            RateError::DateBeforeRange(date) => {
+                write!(f, "No rate recorded on or before {}", date)
+            }
+            RateError::UnknownRateType(rate_type) => {
+                write!(f, "No rate history for rate type '{}'", rate_type)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RateError::FileNotFound { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, RateError>;
+
+/// Parse an ISO (YYYY-MM-DD) date string
+fn parse_iso_date(date_str: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|_| {
+        RateError::ParseError(format!("Invalid date '{}' (expected YYYY-MM-DD)", date_str))
+    })
+}
 
 /// Historical rate data for a specific rate type
 #[derive(Debug, Clone)]
@@ -27,11 +78,18 @@ impl RateHistory {
 
     /// Load rate history from a specific path
     pub fn load_from_path(name: &str, path: &Path) -> Result<Self> {
-        let content = fs::read_to_string(path)
-            .with_context(|| format!("Failed to read rate file: {}", path.display()))?;
+        let content = fs::read_to_string(path).map_err(|source| RateError::FileNotFound {
+            path: path.to_path_buf(),
+            source,
+        })?;
 
-        let entries: BTreeMap<String, f64> = serde_conl::from_str(&content)
-            .with_context(|| format!("Failed to parse rate file: {}", path.display()))?;
+        let entries: BTreeMap<String, f64> = serde_conl::from_str(&content).map_err(|e| {
+            RateError::ParseError(format!(
+                "Failed to parse rate file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
 
         let mut rates: Vec<(NaiveDate, f64)> = entries
             .into_iter()
@@ -53,8 +111,8 @@ impl RateHistory {
     /// Get the effective rate for a given date
     ///
     /// Returns the rate that was in effect on the given date,
-    /// or None if the date is before the first rate entry.
-    pub fn rate_on_date(&self, date: NaiveDate) -> Option<f64> {
+    /// or `DateBeforeRange` if the date is before the first rate entry.
+    pub fn rate_on_date(&self, date: NaiveDate) -> Result<f64> {
         // Find the last rate entry that starts on or before the given date
         let mut effective_rate = None;
         for (effective_date, rate) in &self.rates {
@@ -64,13 +122,17 @@ impl RateHistory {
                 break;
             }
         }
-        effective_rate
+        effective_rate.ok_or(RateError::DateBeforeRange(date))
+    }
+
+    /// The most recent (effective_date, rate) entry, if any
+    pub fn latest(&self) -> Option<(NaiveDate, f64)> {
+        self.rates.last().copied()
     }
 
     /// Get the effective rate for a date string in ISO format (YYYY-MM-DD)
-    pub fn rate_on_date_str(&self, date_str: &str) -> Option<f64> {
-        let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()?;
-        self.rate_on_date(date)
+    pub fn rate_on_date_str(&self, date_str: &str) -> Result<f64> {
+        self.rate_on_date(parse_iso_date(date_str)?)
     }
 }
 
@@ -93,40 +155,73 @@ impl PostalRates {
     }
 
     /// Get the 2oz letter rate for a given date (1oz + additional ounce)
-    pub fn letter_2oz(&self, date: NaiveDate) -> Option<f64> {
+    pub fn letter_2oz(&self, date: NaiveDate) -> Result<f64> {
         let base = self.letter.rate_on_date(date)?;
         let additional = self.ounce.rate_on_date(date)?;
-        Some(base + additional)
+        Ok(base + additional)
     }
 
     /// Get the 3oz letter rate for a given date (1oz + 2 additional ounces)
-    pub fn letter_3oz(&self, date: NaiveDate) -> Option<f64> {
+    pub fn letter_3oz(&self, date: NaiveDate) -> Result<f64> {
         let base = self.letter.rate_on_date(date)?;
         let additional = self.ounce.rate_on_date(date)?;
-        Some(base + additional * 2.0)
+        Ok(base + additional * 2.0)
     }
 
     /// Get the postcard rate for a given date
-    pub fn postcard(&self, date: NaiveDate) -> Option<f64> {
+    pub fn postcard(&self, date: NaiveDate) -> Result<f64> {
         self.postcard.rate_on_date(date)
     }
 
+    /// Whether `rate_type` has a recorded rate history
+    pub fn has_history(rate_type: &RateType) -> bool {
+        matches!(
+            rate_type,
+            RateType::FirstClass
+                | RateType::AdditionalOunce
+                | RateType::TwoOunce
+                | RateType::ThreeOunce
+                | RateType::Postcard
+        )
+    }
+
+    /// Get the rate for a stamp `rate_type` (e.g. "Two Ounce") on a given date
+    ///
+    /// Returns `UnknownRateType` for rate types without a recorded history.
+    pub fn rate_for_type(&self, rate_type: &str, date: NaiveDate) -> Result<f64> {
+        match RateType::from_str(rate_type) {
+            RateType::FirstClass => self.letter.rate_on_date(date),
+            RateType::AdditionalOunce => self.ounce.rate_on_date(date),
+            RateType::TwoOunce => self.letter_2oz(date),
+            RateType::ThreeOunce => self.letter_3oz(date),
+            RateType::Postcard => self.postcard(date),
+            _ => Err(RateError::UnknownRateType(rate_type.to_string())),
+        }
+    }
+
     /// Get the 2oz letter rate for a date string in ISO format (YYYY-MM-DD)
-    pub fn letter_2oz_str(&self, date_str: &str) -> Option<f64> {
-        let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()?;
-        self.letter_2oz(date)
+    pub fn letter_2oz_str(&self, date_str: &str) -> Result<f64> {
+        self.letter_2oz(parse_iso_date(date_str)?)
     }
 
     /// Get the 3oz letter rate for a date string in ISO format (YYYY-MM-DD)
-    pub fn letter_3oz_str(&self, date_str: &str) -> Option<f64> {
-        let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()?;
-        self.letter_3oz(date)
+    pub fn letter_3oz_str(&self, date_str: &str) -> Result<f64> {
+        self.letter_3oz(parse_iso_date(date_str)?)
     }
 
     /// Get the postcard rate for a date string in ISO format (YYYY-MM-DD)
-    pub fn postcard_str(&self, date_str: &str) -> Option<f64> {
-        let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()?;
-        self.postcard(date)
+    pub fn postcard_str(&self, date_str: &str) -> Result<f64> {
+        self.postcard(parse_iso_date(date_str)?)
+    }
+
+    /// Get the rate for a stamp `rate_type` on a date string in ISO format (YYYY-MM-DD)
+    ///
+    /// The rate type is checked before the date, so `UnknownRateType` takes precedence.
+    pub fn rate_for_type_str(&self, rate_type: &str, date_str: &str) -> Result<f64> {
+        if !Self::has_history(&RateType::from_str(rate_type)) {
+            return Err(RateError::UnknownRateType(rate_type.to_string()));
+        }
+        self.rate_for_type(rate_type, parse_iso_date(date_str)?)
     }
 }
 
@@ -143,18 +238,21 @@ mod tests {
 
             // Test a known rate: July 13, 2025 should be $0.78
             let date = NaiveDate::from_ymd_opt(2025, 7, 14).unwrap();
-            assert_eq!(history.rate_on_date(date), Some(0.78));
+            assert_eq!(history.rate_on_date(date).ok(), Some(0.78));
 
             // Test a date before all rates
             let early_date = NaiveDate::from_ymd_opt(1800, 1, 1).unwrap();
-            assert_eq!(history.rate_on_date(early_date), None);
+            assert!(matches!(
+                history.rate_on_date(early_date),
+                Err(RateError::DateBeforeRange(_))
+            ));
         }
     }
 
-    fn approx_eq(a: Option<f64>, b: f64) -> bool {
+    fn approx_eq(a: Result<f64>, b: f64) -> bool {
         match a {
-            Some(v) => (v - b).abs() < 0.001,
-            None => false,
+            Ok(v) => (v - b).abs() < 0.001,
+            Err(_) => false,
         }
     }
 
@@ -174,4 +272,46 @@ mod tests {
             assert!(approx_eq(rates.postcard(date), 0.61));
         }
     }
+
+    #[test]
+    fn test_rate_errors() {
+        let history = |name: &str| RateHistory {
+            _name: name.to_string(),
+            rates: vec![(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), 0.5)],
+        };
+        let rates = PostalRates {
+            letter: history("letter"),
+            ounce: history("ounce"),
+            postcard: history("postcard"),
+        };
+
+        assert!(approx_eq(
+            rates.rate_for_type_str("Two Ounce", "2021-06-01"),
+            1.0
+        ));
+        assert!(matches!(
+            rates.rate_for_type_str("Definitive", "2021-06-01"),
+            Err(RateError::UnknownRateType(_))
+        ));
+        assert!(matches!(
+            rates.rate_for_type_str("Definitive", ""),
+            Err(RateError::UnknownRateType(_))
+        ));
+        assert!(approx_eq(
+            rates.rate_for_type_str("Additional Postage", "2021-06-01"),
+            0.5
+        ));
+        assert!(matches!(
+            rates.rate_for_type_str("Postcard", "June 2021"),
+            Err(RateError::ParseError(_))
+        ));
+        assert!(matches!(
+            rates.rate_for_type_str("First Class", "2019-12-31"),
+            Err(RateError::DateBeforeRange(_))
+        ));
+        assert!(matches!(
+            RateHistory::load_from_path("letter", Path::new("does/not/exist.conl")),
+            Err(RateError::FileNotFound { .. })
+        ));
+    }
 }
@@ -1,20 +1,38 @@
 use anyhow::{bail, Context, Result};
-use rusqlite::Connection;
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension};
 use scraper::Html;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::{self, Write};
-use std::path::PathBuf;
-
-use crate::rates::PostalRates;
-use crate::types::{Credits, Product, RateType, StampMetadata, StampType};
-use crate::utils::{osc8_file_link, osc8_link};
-use crate::{detect_stamp_type, init_database, parse_date_to_iso, MIN_SCRAPE_YEAR, STAMPS_API_URL};
-
-const CACHE_DIR: &str = "cache";
-const STAMPS_DIR: &str = "data/stamps";
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::enrichment::{load_enrichment, sort_json_value};
+use crate::fetch::{fetch_binary_limited, http_client, Fetcher, Progress, RateLimiter, TooLarge};
+use crate::images::{image_dimensions, strip_metadata};
+use crate::init_database;
+use crate::progress::ProgressBar;
+use usps_rates::rates::{PostalRates, RateError};
+use usps_rates::slug::{dedup_tags, generate_slug};
+use usps_rates::types::{Credits, Product, RateType, StampMetadata, StampType};
+use usps_rates::utils::{
+    extract_image_filename, normalize_price, original_image_urls, osc8_file_link, osc8_link,
+    parse_price, stamp_link, strip_url_query,
+};
+use usps_rates::{detect_stamp_type, parse_date_to_iso, MIN_SCRAPE_YEAR, STAMPS_API_URL};
+
+pub(crate) const DATABASE_FILE: &str = "stamps.db";
+pub(crate) const STAMPS_DIR: &str = "data/stamps";
 const OVERRIDES_DIR: &str = "enrichment/stamps";
+const BOILERPLATE_FILE: &str = "enrichment/boilerplate.conl";
+/// `issued` override values for a stamp that was announced but never issued;
+/// any other value is a fallback issue date
+const CANCELED_ISSUED_VALUES: &[&str] = &["canceled", "cancelled", "false"];
 
 /// Override data for a stamp (loaded from enrichment/stamps/{year}.conl)
 #[derive(Debug, Default, Clone, Deserialize)]
@@ -32,16 +50,21 @@ struct StampOverrides {
     #[serde(rename = "type")]
     stamp_type: Option<String>,
     stamp_images: Option<Vec<String>>,
+    hidden: Option<bool>,
+    pair_with: Option<String>,
+    series: Option<String>,
+    tags: Option<Vec<String>>,
+    description_source: Option<String>,
 }
 
 /// Valid rate_type values (must match RateType enum variants)
-const VALID_RATE_TYPES: &[&str] = &[
+pub(crate) const VALID_RATE_TYPES: &[&str] = &[
     "Forever",
     "Postcard",
     "International",
     "Global Forever",
     "Additional Ounce",
-    "Additional Postage",
+    "Additional Postage", // Alias, stored as "Additional Ounce"
     "Two Ounce",
     "Three Ounce",
     "Nonmachineable Surcharge",
@@ -55,6 +78,9 @@ const VALID_RATE_TYPES: &[&str] = &[
     "First Class", // Historical 1oz letter rate - inferred from issue_date
 ];
 
+/// Valid description_source values: which API field is preferred for the description
+const VALID_DESCRIPTION_SOURCES: &[&str] = &["about", "caption"];
+
 /// Load all overrides from year-based CONL files in enrichment/stamps/
 fn load_overrides() -> HashMap<u32, HashMap<String, StampOverrides>> {
     let mut all_overrides: HashMap<u32, HashMap<String, StampOverrides>> = HashMap::new();
@@ -82,7 +108,7 @@ fn load_overrides() -> HashMap<u32, HashMap<String, StampOverrides>> {
                             }
                         };
 
-                    // Validate rate_type values
+                    // Validate rate_type and description_source values
                     for (slug, stamp_override) in &overrides {
                         if let Some(ref rate_type) = stamp_override.rate_type {
                             if !VALID_RATE_TYPES.contains(&rate_type.as_str()) {
@@ -95,6 +121,17 @@ fn load_overrides() -> HashMap<u32, HashMap<String, StampOverrides>> {
                                 );
                             }
                         }
+                        if let Some(ref source) = stamp_override.description_source {
+                            if !VALID_DESCRIPTION_SOURCES.contains(&source.as_str()) {
+                                panic!(
+                                    "Invalid description_source '{}' for '{}' in {}. Valid values: {:?}",
+                                    source,
+                                    slug,
+                                    path.display(),
+                                    VALID_DESCRIPTION_SOURCES
+                                );
+                            }
+                        }
                     }
 
                     all_overrides.insert(year, overrides);
@@ -163,51 +200,75 @@ struct ProductMedia {
 }
 
 // Cache system
+/// Fetcher that serves responses from `cache_dir`, downloading and caching misses
 struct CachedClient {
     client: reqwest::blocking::Client,
     cache_dir: PathBuf,
+    /// Skip cache reads (responses are still written to the cache)
+    no_cache: bool,
+    /// Spaces out network requests; cache hits don't wait
+    limiter: RateLimiter,
+    /// Downloads over this size are abandoned and not cached
+    max_bytes: u64,
 }
 
 impl CachedClient {
-    fn new() -> Result<Self> {
-        let client = reqwest::blocking::Client::builder()
-            .user_agent("Mozilla/5.0 (compatible; USPSStampScraper/1.0)")
-            .build()?;
-        let cache_dir = PathBuf::from(CACHE_DIR);
-        Ok(Self { client, cache_dir })
+    fn new(
+        cache_dir: &Path,
+        no_cache: bool,
+        request_interval: Duration,
+        max_bytes: u64,
+    ) -> Result<Self> {
+        let client = http_client()?;
+        fs::create_dir_all(cache_dir)
+            .with_context(|| format!("Failed to create cache dir: {:?}", cache_dir))?;
+        Ok(Self {
+            client,
+            cache_dir: cache_dir.to_path_buf(),
+            no_cache,
+            limiter: RateLimiter::new(request_interval),
+            max_bytes,
+        })
     }
 
     fn url_to_cache_path(&self, url: &str) -> PathBuf {
-        // Strip query params and protocol, use as path
-        let url = url.split('?').next().unwrap_or(url);
-        if let Some(stripped) = url.strip_prefix("https://") {
-            self.cache_dir.join(stripped)
-        } else if let Some(stripped) = url.strip_prefix("http://") {
-            self.cache_dir.join(stripped)
-        } else {
-            self.cache_dir.join(url)
-        }
+        url_cache_path(&self.cache_dir, url)
     }
 
-    fn fetch_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T> {
+    /// Download `url` into the cache unless it's already there, without reading it back
+    ///
+    /// Returns whether a download happened.
+    fn prefetch_binary(&self, url: &str) -> Result<bool> {
         let cache_path = self.url_to_cache_path(url);
+        if !self.no_cache && cache_path.exists() {
+            return Ok(false);
+        }
+        self.download_binary(url, &cache_path, &mut |_, _| {})?;
+        Ok(true)
+    }
 
-        if cache_path.exists() {
-            let content = fs::read_to_string(&cache_path)
-                .with_context(|| format!("Failed to read cache: {:?}", cache_path))?;
-            return serde_json::from_str(&content)
-                .with_context(|| format!("Failed to parse cached JSON: {:?}", cache_path));
+    fn download_binary(&self, url: &str, cache_path: &Path, progress: Progress) -> Result<Vec<u8>> {
+        self.limiter.wait();
+        let bytes = fetch_binary_limited(&self.client, url, Some(self.max_bytes), progress)?;
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent)?;
         }
+        fs::write(cache_path, &bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl Fetcher for CachedClient {
+    fn fetch_text(&self, url: &str) -> Result<String> {
+        let cache_path = self.url_to_cache_path(url);
 
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .with_context(|| format!("Failed to fetch: {}", url))?;
+        if !self.no_cache && cache_path.exists() {
+            return fs::read_to_string(&cache_path)
+                .with_context(|| format!("Failed to read cache: {:?}", cache_path));
+        }
 
-        let text = response
-            .text()
-            .with_context(|| format!("Failed to read response: {}", url))?;
+        self.limiter.wait();
+        let text = self.client.fetch_text(url)?;
 
         // Cache the response
         if let Some(parent) = cache_path.parent() {
@@ -215,41 +276,114 @@ impl CachedClient {
         }
         fs::write(&cache_path, &text)?;
 
-        serde_json::from_str(&text).with_context(|| format!("Failed to parse JSON: {}", url))
+        Ok(text)
     }
 
     fn fetch_binary(&self, url: &str) -> Result<Vec<u8>> {
+        self.fetch_binary_with_progress(url, &mut |_, _| {})
+    }
+
+    fn fetch_binary_with_progress(&self, url: &str, progress: Progress) -> Result<Vec<u8>> {
         let cache_path = self.url_to_cache_path(url);
 
-        if cache_path.exists() {
+        if !self.no_cache && cache_path.exists() {
             return fs::read(&cache_path)
                 .with_context(|| format!("Failed to read cache: {:?}", cache_path));
         }
 
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .with_context(|| format!("Failed to fetch: {}", url))?;
+        self.download_binary(url, &cache_path, progress)
+    }
+}
+
+/// Named entities seen in USPS/stampsforever descriptions
+const NAMED_ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("ndash", '–'),
+    ("mdash", '—'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("hellip", '…'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("deg", '°'),
+    ("cent", '¢'),
+    ("frac12", '½'),
+    ("eacute", 'é'),
+];
 
-        let bytes = response
-            .bytes()
-            .with_context(|| format!("Failed to read response: {}", url))?;
+/// Decode named (`&mdash;`) and numeric (`&#8217;`, `&#x2019;`) HTML entities
+///
+/// Unknown or malformed entities are left as-is.
+fn decode_html_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end > 0 && end <= 10)
+            .and_then(|end| {
+                let name = &rest[1..end + 1];
+                let c = if let Some(num) = name.strip_prefix('#') {
+                    let code = match num.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => num.parse().ok(),
+                    };
+                    code.and_then(char::from_u32)
+                } else {
+                    NAMED_ENTITIES
+                        .iter()
+                        .find(|(n, _)| *n == name)
+                        .map(|(_, c)| *c)
+                };
+                c.map(|c| (c, end + 2))
+            });
 
-        if let Some(parent) = cache_path.parent() {
-            fs::create_dir_all(parent)?;
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
         }
-        fs::write(&cache_path, &bytes)?;
+    }
+    out.push_str(rest);
+    out
+}
 
-        Ok(bytes.to_vec())
+/// Map a URL onto its file under `cache_dir`
+fn url_cache_path(cache_dir: &Path, url: &str) -> PathBuf {
+    // Strip query params and protocol, use as path
+    let url = strip_url_query(url);
+    if let Some(stripped) = url.strip_prefix("https://") {
+        cache_dir.join(stripped)
+    } else if let Some(stripped) = url.strip_prefix("http://") {
+        cache_dir.join(stripped)
+    } else {
+        cache_dir.join(url)
     }
 }
 
 fn html_to_text(html: &str) -> String {
     let document = Html::parse_fragment(html);
 
-    // Extract text from all text nodes, joining with spaces
+    // Extract text from all text nodes, joining with spaces. The parser decodes
+    // entities once; decode again for double-encoded API text (`&amp;#8217;`).
     let text: String = document.root_element().text().collect::<Vec<_>>().join(" ");
+    let text = decode_html_entities(&text);
 
     // Clean up: normalize whitespace and newlines
     let mut cleaned = String::new();
@@ -268,24 +402,138 @@ fn html_to_text(html: &str) -> String {
     cleaned.trim().to_string()
 }
 
-fn is_included_product(title: &str) -> bool {
-    let lower = title.to_lowercase();
-    // Exclude first day covers and strips (not purchasable separately)
-    if lower.contains("first day cover") || lower.contains("strip of") {
-        return false;
-    }
-    // Include: Pane, Press Sheet, Keepsake, Notecard, Ceremony Program, Pack of 5 (envelopes), Booklet, Coil, Stamped Card
-    lower.contains("pane")
-        || lower.contains("press sheet")
-        || lower.contains("keepsake")
-        || lower.contains("notecard")
-        || lower.contains("ceremony program")
-        || lower.contains("stamp folio")
-        || lower.contains("pack of 5")
-        || lower.contains("booklet")
-        || lower.contains("coil of")
-        || lower.contains("stamped card")
-        || lower.contains("double reply")
+/// Boilerplate phrases trimmed from the end of `about` text
+#[derive(Debug, Default, Deserialize)]
+struct BoilerplateConfig {
+    #[serde(default)]
+    phrases: Vec<String>,
+}
+
+/// Load boilerplate phrases from enrichment/boilerplate.conl (empty if missing)
+fn load_boilerplate() -> Result<Vec<String>> {
+    let content = match fs::read_to_string(BOILERPLATE_FILE) {
+        Ok(c) => c,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let config: BoilerplateConfig = serde_conl::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", BOILERPLATE_FILE))?;
+    Ok(config.phrases)
+}
+
+/// Strip trailing boilerplate sentences (repeatedly, so stacked phrases all go)
+///
+/// Runs of whitespace (including line breaks and non-breaking spaces) match a single
+/// space, so a phrase still matches when the scraped text wraps it differently.
+fn strip_boilerplate(text: &str, phrases: &[String]) -> String {
+    let mut text = text.trim_end();
+    while let Some(rest) = phrases
+        .iter()
+        .find_map(|p| strip_suffix_normalized(text, p))
+    {
+        text = rest.trim_end();
+    }
+    text.to_string()
+}
+
+/// `text` without a trailing `phrase`, comparing word by word so any whitespace matches
+fn strip_suffix_normalized<'a>(text: &'a str, phrase: &str) -> Option<&'a str> {
+    let mut rest = text;
+    let mut matched = false;
+    for word in phrase.split_whitespace().rev() {
+        if matched {
+            let trimmed = rest.trim_end();
+            // Words must be separated by whitespace in the text too
+            if trimmed.len() == rest.len() {
+                return None;
+            }
+            rest = trimmed;
+        }
+        rest = rest.strip_suffix(word)?;
+        matched = true;
+    }
+    matched.then_some(rest)
+}
+
+/// Stamp description from `about` (falling back to `caption`), without boilerplate
+///
+/// `source = Some("caption")` (a `description_source` override) prefers `caption` instead.
+fn about_text(
+    detail: &StampDetail,
+    boilerplate: &[String],
+    source: Option<&str>,
+) -> Option<String> {
+    let (about, caption) = (detail.about.as_deref(), detail.caption.as_deref());
+    let fields = match source {
+        Some("caption") => [caption, about],
+        _ => [about, caption],
+    };
+    fields
+        .into_iter()
+        .flatten()
+        .map(|html| strip_boilerplate(&html_to_text(html), boilerplate))
+        .find(|t| !t.is_empty())
+}
+
+/// Which kinds of product listing a scrape keeps
+#[derive(Debug, Clone, Copy)]
+pub struct ProductFilter {
+    /// First day covers (not sold as stamps)
+    pub include_first_day_covers: bool,
+    /// Coil strips (not purchasable separately)
+    pub include_strips: bool,
+    pub include_press_sheets: bool,
+}
+
+impl Default for ProductFilter {
+    fn default() -> Self {
+        Self {
+            include_first_day_covers: false,
+            include_strips: false,
+            include_press_sheets: true,
+        }
+    }
+}
+
+impl ProductFilter {
+    /// Whether a product listing with this title is kept
+    fn includes(&self, title: &str) -> bool {
+        let lower = title.to_lowercase();
+        if lower.contains("first day cover") {
+            return self.include_first_day_covers;
+        }
+        if lower.contains("strip of") {
+            return self.include_strips;
+        }
+        if lower.contains("press sheet") {
+            return self.include_press_sheets;
+        }
+        // Include: Pane, Keepsake, Notecard, Ceremony Program, Pack of 5 (envelopes), Booklet, Coil, Stamped Card
+        lower.contains("pane")
+            || lower.contains("keepsake")
+            || lower.contains("notecard")
+            || lower.contains("ceremony program")
+            || lower.contains("stamp folio")
+            || lower.contains("pack of 5")
+            || lower.contains("booklet")
+            || lower.contains("coil of")
+            || lower.contains("stamped card")
+            || lower.contains("double reply")
+    }
+}
+
+/// Purchasable products worth listing, deduplicated by cleaned title
+///
+/// Removes duplicates like "Coil of 100 (BCA)" and "Coil of 100 (APU)".
+fn included_products<'a>(
+    products: &'a [ProductListing],
+    filter: &ProductFilter,
+) -> Vec<&'a ProductListing> {
+    let mut seen_titles = HashSet::new();
+    products
+        .iter()
+        .filter(|p| filter.includes(&p.product_title))
+        .filter(|p| seen_titles.insert(clean_product_title(&p.product_title)))
+        .collect()
 }
 
 /// Clean up product title for display (expand abbreviations, remove printer codes)
@@ -321,6 +569,57 @@ fn extract_quantity(title: &str) -> Option<u32> {
     None
 }
 
+/// Extract panes-per-sheet from a press sheet title (e.g., "Press Sheet of 6 Panes" -> 6)
+fn extract_panes(title: &str) -> Option<u32> {
+    let lower = title.to_lowercase();
+    let idx = lower.find(" panes")?;
+    let num_str: String = lower[..idx]
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    num_str.parse().ok()
+}
+
+/// Maximum relative difference between a press sheet's per-pane price and the pane price
+const PRESS_SHEET_PRICE_TOLERANCE: f64 = 0.1;
+
+/// Sanity-check a press sheet price against the pane price and return its pane count
+///
+/// Uses the panes parsed from the title when present, otherwise infers them from
+/// `sheet_price / pane_price`. Warns (without failing) when the per-pane price is off.
+fn check_press_sheet_panes(
+    api_slug: &str,
+    title: &str,
+    sheet_price: f64,
+    pane_price: f64,
+    panes: Option<u32>,
+) -> Option<u32> {
+    if pane_price <= 0.0 {
+        return panes;
+    }
+    let panes_count = panes.unwrap_or_else(|| (sheet_price / pane_price).round() as u32);
+    let per_pane = if panes_count > 0 {
+        sheet_price / panes_count as f64
+    } else {
+        f64::INFINITY
+    };
+    if (per_pane - pane_price).abs() / pane_price > PRESS_SHEET_PRICE_TOLERANCE {
+        eprintln!(
+            "\n  WARNING: '{}' press sheet '{}' price ${:.2} is not a whole number of ${:.2} panes",
+            stamp_link(api_slug, api_slug, &io::stderr()),
+            title,
+            sheet_price,
+            pane_price
+        );
+        return panes;
+    }
+    Some(panes_count)
+}
+
 /// Parse product metadata from title
 /// Returns a JSON object with extracted attributes, or None if not parseable
 fn parse_product_metadata(title: &str) -> Option<serde_json::Value> {
@@ -352,6 +651,13 @@ fn parse_product_metadata(title: &str) -> Option<serde_json::Value> {
         metadata.insert("quantity".to_string(), serde_json::Value::Number(qty.into()));
     }
 
+    // Press sheet panes (e.g., "Press Sheet of 6 Panes")
+    if format == "press-sheet" {
+        if let Some(panes) = extract_panes(title) {
+            metadata.insert("panes".to_string(), serde_json::Value::Number(panes.into()));
+        }
+    }
+
     // Envelope-specific metadata
     if format == "envelope" {
         // Extract size (e.g., "#6-3/4", "#9", "#10")
@@ -401,16 +707,6 @@ fn parse_product_metadata(title: &str) -> Option<serde_json::Value> {
     Some(serde_json::Value::Object(metadata))
 }
 
-fn extract_image_filename(url: &str) -> String {
-    url.split('/')
-        .last()
-        .unwrap_or("image.png")
-        .split('?')
-        .next()
-        .unwrap_or("image.png")
-        .to_string()
-}
-
 /// Suffixes that should NOT cause a comma split (e.g., "Edith Widder, Ph.D." is one name)
 const NAME_SUFFIXES: &[&str] = &["Ph.D.", "M.D.", "Jr.", "Sr.", "II", "III", "IV"];
 
@@ -452,9 +748,7 @@ fn get_corrected_rate(
         Some("Forever") | Some("Semipostal") => Some(format!("{:.2}", CURRENT_FOREVER_RATE)),
         Some("Two Ounce") => Some(format!("{:.2}", CURRENT_TWO_OUNCE_RATE)),
         Some("Three Ounce") => Some(format!("{:.2}", CURRENT_THREE_OUNCE_RATE)),
-        Some("Additional Ounce") | Some("Additional Postage") => {
-            Some(format!("{:.2}", CURRENT_ADDITIONAL_OUNCE_RATE))
-        }
+        Some("Additional Ounce") => Some(format!("{:.2}", CURRENT_ADDITIONAL_OUNCE_RATE)),
         Some("Postcard") => Some(format!("{:.2}", CURRENT_POSTCARD_RATE)),
         Some("International") | Some("Global Forever") => {
             Some(format!("{:.2}", CURRENT_GLOBAL_FOREVER_RATE))
@@ -548,122 +842,217 @@ fn parse_credits_heading(heading: &str) -> CreditsHeadingType {
     }
 }
 
-/// Generate the new slug format based on rate_type and rate
-/// Format: "{base}-{denomination}-{year}" for denominated, "{base}-{value_type}-{year}" for forever
-fn generate_slug(api_slug: &str, year: u32, rate_type: Option<&str>, rate: Option<&str>) -> (String, bool) {
-    // Use RateType::is_forever(year) to determine if this is a forever stamp
-    // This respects the year-based rules for when forever stamps became available
-    let is_forever = rate_type
-        .map(|rt| RateType::from_str(rt).is_forever(year))
-        .unwrap_or(false);
+/// Which parts of a stamp a scrape refreshes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrapeMode {
+    /// Download images and rewrite the database and metadata.conl
+    Full,
+    /// Rewrite the database and metadata.conl from images already on disk
+    MetadataOnly,
+    /// Download images without touching the database or metadata.conl
+    ImagesOnly,
+}
 
-    // Clean the API slug to get base name (remove year suffix if present)
-    let year_suffix = format!("-{}", year);
-    let base_slug = if api_slug.ends_with(&year_suffix) {
-        &api_slug[..api_slug.len() - year_suffix.len()]
-    } else {
-        api_slug
-    };
+/// Whether an `issued` override value marks the stamp as canceled (never issued)
+fn is_canceled(issued: &str) -> bool {
+    CANCELED_ISSUED_VALUES.contains(&issued.trim().to_lowercase().as_str())
+}
 
-    // Strip disambiguation suffix (-2, -3, etc.)
-    let base_slug = if let Some(last_dash) = base_slug.rfind('-') {
-        let suffix = &base_slug[last_dash + 1..];
-        if suffix.len() == 1
-            && suffix
-                .chars()
-                .next()
-                .map(|c| c.is_ascii_digit())
-                .unwrap_or(false)
-        {
-            &base_slug[..last_dash]
-        } else {
-            base_slug
-        }
-    } else {
-        base_slug
-    };
+/// Which slug scheme scraped stamps are written with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum SlugFormat {
+    /// Denomination/rate type and year suffixes, e.g. `us-flags-forever-2023`
+    #[default]
+    New,
+    /// The stampsforever.com API slug unchanged, e.g. `us-flags-2023`
+    Api,
+}
 
-    // Strip denomination prefix (e.g., "10c-poppies" -> "poppies", "2-floral" -> "floral")
-    let base_slug = if let Some(idx) = base_slug.find('-') {
-        let prefix = &base_slug[..idx];
-        if prefix.ends_with('c')
-            && prefix[..prefix.len() - 1]
-                .chars()
-                .all(|c| c.is_ascii_digit())
-        {
-            // Remove cent prefix like "10c-"
-            &base_slug[idx + 1..]
-        } else if prefix.chars().all(|c| c.is_ascii_digit()) {
-            // Remove dollar prefix like "2-"
-            &base_slug[idx + 1..]
-        } else {
-            base_slug
+/// Which side wins when an override and the API both set a field
+///
+/// Override-only fields (slug, forever, type, extra_cost, hidden, pair_with, tags)
+/// have no API value to compete with and always apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Precedence {
+    /// Overrides replace API values
+    #[default]
+    Override,
+    /// Overrides only fill fields the API leaves empty
+    Api,
+}
+
+impl Precedence {
+    /// Whether an override should replace a field the API did (or didn't) set
+    fn applies(self, api_has_value: bool) -> bool {
+        self == Precedence::Override || !api_has_value
+    }
+}
+
+/// Flags shared by the scrape commands
+pub struct ScrapeOptions {
+    /// Suppress progress output
+    pub quiet: bool,
+    /// Fail at the end if any stamp warned
+    pub strict: bool,
+    pub mode: ScrapeMode,
+    /// Download all details and images into the cache concurrently before scraping
+    pub prefetch: bool,
+    pub products: ProductFilter,
+    pub slug_format: SlugFormat,
+    pub prefer: Precedence,
+    /// Minimum time between stampsforever.com requests (cache hits don't count)
+    pub request_interval: Duration,
+    /// Skip (and don't cache) images larger than this
+    pub max_image_bytes: u64,
+    /// Directory stamp folders are written under (normally `STAMPS_DIR`)
+    pub data_dir: PathBuf,
+}
+
+/// Default spacing between network requests, to stay a polite API client
+pub const DEFAULT_REQUEST_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Default image size limit; full-sheet scans run a few MB, so this only catches
+/// broken responses
+pub const DEFAULT_MAX_IMAGE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Save an image into `stamp_dir`, returning its filename
+///
+/// In `MetadataOnly` mode nothing is fetched: the filename is returned only if
+/// the file is already on disk.
+fn save_image(
+    client: &dyn Fetcher,
+    stamp_dir: &Path,
+    url: &str,
+    mode: ScrapeMode,
+    show_progress: bool,
+) -> Result<Option<String>> {
+    let img_filename = extract_image_filename(url);
+    let img_path = stamp_dir.join(&img_filename);
+    if mode == ScrapeMode::MetadataOnly {
+        return Ok(img_path.exists().then_some(img_filename));
+    }
+
+    // Large downloads show "1.2/4.5MB" in place, erased once done
+    let show_progress = show_progress && io::stdout().is_terminal();
+    let mut shown = 0;
+    let fetched = client.fetch_binary_with_progress(url, &mut |read, total| {
+        if !show_progress {
+            return;
         }
-    } else {
-        base_slug
+        let text = format!(
+            "{:.1}/{:.1}MB",
+            read as f64 / 1_000_000.0,
+            total as f64 / 1_000_000.0
+        );
+        print!(
+            "{:<width$}{}",
+            text,
+            "\x08".repeat(text.len().max(shown)),
+            width = shown
+        );
+        let _ = io::stdout().flush();
+        shown = text.len();
+    });
+    if shown > 0 {
+        print!("{}{}", " ".repeat(shown), "\x08".repeat(shown));
+    }
+    let img_data = match fetched {
+        Err(e) if e.is::<TooLarge>() => {
+            eprintln!("\n  WARNING: skipping image: {}", e);
+            return Ok(None);
+        }
+        result => result?,
     };
+    // The cache keeps the original; the site copy drops EXIF and other metadata
+    let img_data = strip_metadata(&img_data).unwrap_or(img_data);
+    fs::write(&img_path, &img_data)?;
+    Ok(Some(img_filename))
+}
 
-    // Build the new slug
-    let slug = if is_forever {
-        // Use rate_type for slug, fall back to "forever"
-        let value_type = rate_type.unwrap_or("Forever");
-        let vt_slug = value_type.to_lowercase().replace(' ', "-");
+/// A sheet image saved by `save_pane_image`
+struct SavedPane {
+    filename: String,
+    /// The URL it came from, after any fallbacks
+    url: String,
+    /// Pixel width and height, if the file is a format we can read
+    size: Option<(u32, u32)>,
+}
 
-        // Handle special case: don't duplicate "semipostal" in "alzheimers-semipostal"
-        if base_slug.ends_with("-semipostal") && vt_slug == "semipostal" {
-            format!("{}-{}", base_slug, year)
-        } else {
-            format!("{}-{}-{}", base_slug, vt_slug, year)
+/// Save the sheet image, trying the original before the derivative the API links
+///
+/// Candidates come from `original_image_urls`. One that fails to download, is too
+/// large, or isn't an image (a missing original can come back as an HTML error page)
+/// falls through to the next; the URL as given is always tried last.
+fn save_pane_image(
+    client: &dyn Fetcher,
+    stamp_dir: &Path,
+    url: &str,
+    mode: ScrapeMode,
+    show_progress: bool,
+) -> Result<Option<SavedPane>> {
+    let candidates = original_image_urls(url);
+    for (i, candidate) in candidates.iter().enumerate() {
+        let last = i + 1 == candidates.len();
+        let filename = match save_image(client, stamp_dir, candidate, mode, show_progress) {
+            Ok(Some(filename)) => filename,
+            Ok(None) => continue,
+            Err(e) if !last => {
+                eprintln!("\n  Falling back from {}: {:#}", candidate, e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let path = stamp_dir.join(&filename);
+        let size = image_dimensions(&path);
+        if size.is_none() && !last && mode != ScrapeMode::MetadataOnly {
+            fs::remove_file(&path)?;
+            continue;
         }
-    } else {
-        // Denominated stamp: include denomination in slug
-        // Parse rate like "5.00" or "6.70" into slug format like "5d" or "6d70c"
-        let denom_slug = rate
-            .and_then(|r| {
-                let r = r.trim_start_matches('$');
-                let parts: Vec<&str> = r.split('.').collect();
-                if parts.len() == 2 {
-                    let dollars: u32 = parts[0].parse().ok()?;
-                    let cents: u32 = parts[1].parse().ok()?;
-                    if dollars == 0 {
-                        // Sub-dollar: just cents (e.g., "46c" not "0d46c")
-                        Some(format!("{}c", cents))
-                    } else if cents == 0 {
-                        Some(format!("{}d", dollars))
-                    } else {
-                        Some(format!("{}d{:02}c", dollars, cents))
-                    }
-                } else if parts.len() == 1 {
-                    // Just dollars, no decimal
-                    let dollars: u32 = parts[0].parse().ok()?;
-                    Some(format!("{}d", dollars))
-                } else {
-                    None
-                }
-            });
+        return Ok(Some(SavedPane {
+            filename,
+            url: candidate.clone(),
+            size,
+        }));
+    }
+    Ok(None)
+}
 
-        match denom_slug {
-            Some(d) => format!("{}-{}-{}", base_slug, d, year),
-            None => format!("{}-{}", base_slug, year),
-        }
+/// The `credits` column: each role plus the raw `sources` names, None if there are none
+///
+/// Keys are sorted so dumps of the database diff cleanly between runs.
+fn credits_json(credits: &Credits, sources: &[String]) -> Result<Option<String>> {
+    let mut credits_map = match serde_json::to_value(credits)? {
+        serde_json::Value::Object(map) => map,
+        _ => serde_json::Map::new(),
     };
-
-    (slug, is_forever)
+    if !sources.is_empty() {
+        credits_map.insert("sources".to_string(), serde_json::json!(sources));
+    }
+    if credits_map.is_empty() {
+        return Ok(None);
+    }
+    let sorted = sort_json_value(serde_json::Value::Object(credits_map));
+    Ok(Some(serde_json::to_string(&sorted)?))
 }
 
 fn scrape_stamp(
-    client: &CachedClient,
+    client: &dyn Fetcher,
     conn: &Connection,
     api_slug: &str,
     year: u32,
     index: usize,
     total: usize,
-    quiet: bool,
+    options: &ScrapeOptions,
     overrides: &HashMap<u32, HashMap<String, StampOverrides>>,
     postal_rates: &PostalRates,
+    boilerplate: &[String],
+    warnings: &mut Vec<String>,
 ) -> Result<()> {
+    let quiet = options.quiet;
     let mut stdout = io::stdout();
     let forever_url = format!("https://www.stampsforever.com/stamps/{}", api_slug);
+    // Slug as printed in warnings (and collected for the --strict summary)
+    let slug_link = stamp_link(api_slug, api_slug, &io::stderr());
 
     // Print progress prefix and slug link
     if !quiet {
@@ -687,27 +1076,49 @@ fn scrape_stamp(
     let mut extra_cost: Option<f64> = None;
     let mut rate_override: Option<String> = None;
     let mut stamp_images_override: Option<Vec<String>> = None;
+    let mut hidden: Option<bool> = None;
+    let mut pair_with: Option<String> = None;
+    let mut tags_override: Option<Vec<String>> = None;
+    let mut canceled: Option<bool> = None;
+    let mut description_source: Option<String> = None;
 
     if let Some(year_overrides) = overrides.get(&year) {
         if let Some(stamp_overrides) = year_overrides.get(api_slug) {
+            let prefer = options.prefer;
             if let Some(ref rt) = stamp_overrides.rate_type {
-                detail.rate_type = Some(rt.clone());
+                if prefer.applies(detail.rate_type.is_some()) {
+                    detail.rate_type = Some(rt.clone());
+                }
             }
             if let Some(ref r) = stamp_overrides.rate {
-                detail.rate = Some(r.clone());
-                rate_override = Some(r.clone());
+                if prefer.applies(detail.rate.is_some()) {
+                    detail.rate = Some(r.clone());
+                    rate_override = Some(r.clone());
+                }
             }
             if let Some(ref id) = stamp_overrides.issue_date {
-                detail.issue_date = Some(id.clone());
+                if prefer.applies(detail.issue_date.is_some()) {
+                    detail.issue_date = Some(id.clone());
+                }
             }
-            // Use 'issued' as fallback for issue_date
-            if detail.issue_date.is_none() {
-                if let Some(ref issued) = stamp_overrides.issued {
-                    detail.issue_date = Some(issued.clone());
+            // 'issued' marks a canceled stamp, or is a fallback for issue_date
+            match stamp_overrides.issued.as_deref() {
+                Some(issued) if is_canceled(issued) => canceled = Some(true),
+                Some(issued) if detail.issue_date.is_none() => {
+                    detail.issue_date = Some(issued.to_string());
                 }
+                _ => {}
             }
             if let Some(ref il) = stamp_overrides.issue_location {
-                detail.issue_location = Some(il.clone());
+                if prefer.applies(detail.issue_location.is_some()) {
+                    detail.issue_location = Some(il.clone());
+                }
+            }
+            // Rename (or assign) the series so it groups with its canonical name
+            if let Some(ref name) = stamp_overrides.series {
+                if prefer.applies(detail.series.is_some()) {
+                    detail.series = Some(SeriesInfo { name: name.clone() });
+                }
             }
             // Extract overrides that are applied later
             slug_override = stamp_overrides.slug.clone();
@@ -715,6 +1126,10 @@ fn scrape_stamp(
             stamp_type_override = stamp_overrides.stamp_type.clone();
             extra_cost = stamp_overrides.extra_cost;
             stamp_images_override = stamp_overrides.stamp_images.clone();
+            hidden = stamp_overrides.hidden;
+            pair_with = stamp_overrides.pair_with.clone();
+            tags_override = stamp_overrides.tags.clone();
+            description_source = stamp_overrides.description_source.clone();
         }
     }
 
@@ -723,17 +1138,15 @@ fn scrape_stamp(
     let mut sheet_images: Vec<String> = Vec::new();
 
     // Use api_slug directory structure: data/stamps/{year}/{api_slug}/
-    let stamp_dir = PathBuf::from(STAMPS_DIR)
-        .join(year.to_string())
-        .join(api_slug);
+    let stamp_dir = options.data_dir.join(year.to_string()).join(api_slug);
     fs::create_dir_all(&stamp_dir)?;
 
     for img in &detail.images {
-        let clean_url = img.path.split('?').next().unwrap_or(&img.path);
-        let img_data = client.fetch_binary(clean_url)?;
-        let img_filename = extract_image_filename(clean_url);
-        let img_path = stamp_dir.join(&img_filename);
-        fs::write(&img_path, &img_data)?;
+        let clean_url = strip_url_query(&img.path);
+        let Some(img_filename) = save_image(client, &stamp_dir, clean_url, options.mode, !quiet)?
+        else {
+            continue;
+        };
         if !quiet {
             print!("{}", osc8_link(clean_url, "."));
             stdout.flush()?;
@@ -741,18 +1154,20 @@ fn scrape_stamp(
         stamp_images.push(img_filename);
     }
 
-    // Handle stamp_pane (sheet image) separately
+    // Handle stamp_pane (sheet image) separately, at the highest resolution available
+    let mut sheet_image_size = None;
     if let Some(pane) = &detail.stamp_pane {
-        let clean_url = pane.path.split('?').next().unwrap_or(&pane.path);
-        let img_data = client.fetch_binary(clean_url)?;
-        let img_filename = extract_image_filename(clean_url);
-        let img_path = stamp_dir.join(&img_filename);
-        fs::write(&img_path, &img_data)?;
-        if !quiet {
-            print!("{}", osc8_link(clean_url, "s"));
-            stdout.flush()?;
+        if let Some(saved) = save_pane_image(client, &stamp_dir, &pane.path, options.mode, !quiet)?
+        {
+            if !quiet {
+                print!("{}", osc8_link(&saved.url, "s"));
+                stdout.flush()?;
+            }
+            sheet_images.push(saved.filename);
+            sheet_image_size = saved
+                .size
+                .map(|(width, height)| format!("{}x{}", width, height));
         }
-        sheet_images.push(img_filename);
     }
 
     if !quiet {
@@ -761,37 +1176,73 @@ fn scrape_stamp(
 
     // Apply stamp_images override if specified
     if let Some(override_images) = stamp_images_override {
-        stamp_images = override_images;
+        if options.prefer.applies(!stamp_images.is_empty()) {
+            stamp_images = override_images;
+        }
+    }
+
+    let enrichment = stamp_images
+        .first()
+        .and_then(|img| load_enrichment(year, api_slug, img));
+
+    // Tags come from overrides, falling back to the enrichment keywords
+    let tags = dedup_tags(
+        tags_override
+            .or_else(|| enrichment.as_ref().map(|e| e.keywords.clone()))
+            .unwrap_or_default(),
+    );
+
+    // Infer rate_type from the enrichment value_type; never overrides the API or overrides
+    if detail.rate_type.is_none() {
+        let inferred = enrichment
+            .and_then(|e| e.value_type)
+            .and_then(|vt| RateType::from_value_type(&vt));
+        if let Some(rate_type) = inferred {
+            eprintln!(
+                "  Inferred rate_type '{}' for '{}' ({}) from enrichment value_type",
+                rate_type.as_str(),
+                slug_link,
+                year
+            );
+            detail.rate_type = Some(rate_type.as_str().to_string());
+        }
     }
 
     // Default rate_type to "First Class" if not specified
     if detail.rate_type.is_none() {
+        warnings.push(format!("'{}' ({}) missing: rate_type", slug_link, year));
         detail.rate_type = Some("First Class".to_string());
     }
 
+    // Store one spelling per rate type ("Additional Postage" becomes "Additional Ounce")
+    detail.rate_type = detail
+        .rate_type
+        .map(|rt| RateType::canonical_str(&rt).to_string());
+
     // Warn about missing required fields not provided by API or overrides
-    if detail.issue_date.is_none() {
-        eprintln!(
-            "  WARNING: '{}' ({}) missing: issue_date",
-            api_slug,
-            year
-        );
+    // (canceled stamps never got an issue date)
+    if detail.issue_date.is_none() && canceled.is_none() {
+        eprintln!("  WARNING: '{}' ({}) missing: issue_date", slug_link, year);
+        warnings.push(format!("'{}' ({}) missing: issue_date", slug_link, year));
     }
 
-    // Generate slug based on rate_type and rate
-    let (computed_slug, computed_forever) = generate_slug(api_slug, year, detail.rate_type.as_deref(), detail.rate.as_deref());
-
-    // Apply slug and forever overrides
-    let slug = slug_override.unwrap_or(computed_slug);
-    let is_forever = forever_override.unwrap_or(computed_forever);
+    // Generate slug based on rate_type and rate (and the forever override, if any)
+    let (computed_slug, is_forever) = generate_slug(
+        api_slug,
+        year,
+        detail.rate_type.as_deref(),
+        detail.rate.as_deref(),
+        forever_override,
+    );
+
+    // Apply slug override; API slugs are used verbatim
+    let slug = match options.slug_format {
+        SlugFormat::New => slug_override.unwrap_or(computed_slug),
+        SlugFormat::Api => api_slug.to_string(),
+    };
 
-    // Parse credits
-    let mut art_director: Option<String> = None;
-    let mut artist: Option<String> = None;
-    let mut designer: Option<String> = None;
-    let mut typographer: Option<String> = None;
-    let mut photographer: Option<String> = None;
-    let mut illustrator: Option<String> = None;
+    // Parse credits, collecting everyone credited with each role
+    let mut credits = Credits::default();
     let mut embedded_credits: Vec<String> = Vec::new();
 
     if let Some(groupings) = &detail.people_groupings {
@@ -819,49 +1270,29 @@ fn scrape_stamp(
                     photographer: has_ph,
                     illustrator: has_il,
                 } => {
-                    for person in &grouping.people {
-                        if has_ad && art_director.is_none() {
-                            art_director = Some(person.name.clone());
-                        }
-                        if has_ar && artist.is_none() {
-                            artist = Some(person.name.clone());
-                        }
-                        if has_de && designer.is_none() {
-                            designer = Some(person.name.clone());
-                        }
-                        if has_ty && typographer.is_none() {
-                            typographer = Some(person.name.clone());
-                        }
-                        if has_ph && photographer.is_none() {
-                            photographer = Some(person.name.clone());
-                        }
-                        if has_il && illustrator.is_none() {
-                            illustrator = Some(person.name.clone());
+                    let mut add = |name: &str| {
+                        for (has_role, names) in [
+                            (has_ad, &mut credits.art_director),
+                            (has_ar, &mut credits.artist),
+                            (has_de, &mut credits.designer),
+                            (has_ty, &mut credits.typographer),
+                            (has_ph, &mut credits.photographer),
+                            (has_il, &mut credits.illustrator),
+                        ] {
+                            if has_role {
+                                names.push(name.to_string());
+                            }
                         }
+                    };
+                    for person in &grouping.people {
+                        add(&person.name);
                     }
 
                     if grouping.people.is_empty() && heading.to_lowercase().contains(" by ") {
                         if let Some(idx) = heading.to_lowercase().find(" by ") {
-                            let name = heading[idx + 4..].trim().to_string();
+                            let name = heading[idx + 4..].trim();
                             if !name.is_empty() {
-                                if has_ad && art_director.is_none() {
-                                    art_director = Some(name.clone());
-                                }
-                                if has_ar && artist.is_none() {
-                                    artist = Some(name.clone());
-                                }
-                                if has_de && designer.is_none() {
-                                    designer = Some(name.clone());
-                                }
-                                if has_ty && typographer.is_none() {
-                                    typographer = Some(name.clone());
-                                }
-                                if has_ph && photographer.is_none() {
-                                    photographer = Some(name.clone());
-                                }
-                                if has_il && illustrator.is_none() {
-                                    illustrator = Some(name.clone());
-                                }
+                                add(name);
                             }
                         }
                     }
@@ -869,6 +1300,7 @@ fn scrape_stamp(
             }
         }
     }
+    credits.normalize();
 
     // Parse issue date and location
     let issue_date = detail
@@ -900,29 +1332,19 @@ fn scrape_stamp(
     let corrected_rate = if let Some(ref override_rate) = rate_override {
         Some(override_rate.clone())
     } else if !stamp_is_forever {
-        // Non-forever stamp: look up historical rate based on issue_date and rate_type
-        match detail.rate_type.as_deref() {
-            Some("First Class") => issue_date
-                .as_ref()
-                .and_then(|d| postal_rates.letter.rate_on_date_str(d))
-                .map(|r| format!("{:.2}", r)),
-            Some("Additional Ounce") | Some("Additional Postage") => issue_date
-                .as_ref()
-                .and_then(|d| postal_rates.ounce.rate_on_date_str(d))
-                .map(|r| format!("{:.2}", r)),
-            Some("Two Ounce") => issue_date
-                .as_ref()
-                .and_then(|d| postal_rates.letter_2oz_str(d))
-                .map(|r| format!("{:.2}", r)),
-            Some("Three Ounce") => issue_date
-                .as_ref()
-                .and_then(|d| postal_rates.letter_3oz_str(d))
-                .map(|r| format!("{:.2}", r)),
-            Some("Postcard") => issue_date
-                .as_ref()
-                .and_then(|d| postal_rates.postcard_str(d))
-                .map(|r| format!("{:.2}", r)),
-            _ => get_corrected_rate(api_slug, detail.rate.as_deref(), detail.rate_type.as_deref()),
+        // Non-forever stamp: look up historical rate based on issue_date and rate_type.
+        // A missing issue_date fails to parse and leaves the rate unset.
+        match postal_rates.rate_for_type_str(
+            detail.rate_type.as_deref().unwrap_or_default(),
+            issue_date.as_deref().unwrap_or_default(),
+        ) {
+            Ok(r) => Some(format!("{:.2}", r)),
+            Err(RateError::UnknownRateType(_)) => get_corrected_rate(
+                api_slug,
+                detail.rate.as_deref(),
+                detail.rate_type.as_deref(),
+            ),
+            Err(_) => None,
         }
     } else {
         // Forever stamp: use current rates
@@ -943,29 +1365,8 @@ fn scrape_stamp(
         StampType::from_str(stamp_type_str)
     };
 
-    // Build credits struct
-    let credits = Credits {
-        art_director: art_director.clone(),
-        artist: artist.clone(),
-        designer: designer.clone(),
-        typographer: typographer.clone(),
-        photographer: photographer.clone(),
-        illustrator: illustrator.clone(),
-    };
-
     // Parse about text
-    let about = detail
-        .about
-        .as_ref()
-        .map(|a| html_to_text(a))
-        .filter(|t| !t.is_empty())
-        .or_else(|| {
-            detail
-                .caption
-                .as_ref()
-                .map(|c| html_to_text(c))
-                .filter(|t| !t.is_empty())
-        });
+    let about = about_text(&detail, boilerplate, description_source.as_deref());
 
     // Build initial metadata struct (products added later)
     let mut metadata = StampMetadata {
@@ -982,20 +1383,26 @@ fn scrape_stamp(
         forever: is_forever,
         stamp_type,
         series: detail.series.as_ref().map(|s| s.name.clone()),
+        tags,
         stamp_images: stamp_images.clone(),
         sheet_image: sheet_images.first().cloned(),
+        sheet_image_size,
         background_color: detail.background_color.clone(),
         credits,
         about,
         products: Vec::new(),
+        hidden,
+        canceled,
+        pair_with,
     };
 
     // Warn if no images
     if stamp_images.is_empty() && sheet_images.is_empty() {
         eprintln!(
             "\nWARNING: No images found for '{}' ({})",
-            api_slug, forever_url
+            slug_link, forever_url
         );
+        warnings.push(format!("'{}' ({}) missing: images", slug_link, year));
     }
 
     // Process products - download images and insert to DB
@@ -1006,17 +1413,13 @@ fn scrape_stamp(
     )?;
 
     if let Some(products) = &detail.product_listings {
-        // Filter to included products and deduplicate by cleaned title
-        // (removes duplicates like "Coil of 100 (BCA)" and "Coil of 100 (APU)")
-        let mut seen_titles = std::collections::HashSet::new();
-        let included_products: Vec<&ProductListing> = products
+        let included_products = included_products(products, &options.products);
+
+        // Pane price used to sanity-check press sheet prices
+        let pane_price = included_products
             .iter()
-            .filter(|p| is_included_product(&p.product_title))
-            .filter(|p| {
-                let clean = clean_product_title(&p.product_title);
-                seen_titles.insert(clean)
-            })
-            .collect();
+            .filter(|p| p.product_title.to_lowercase().contains("pane of"))
+            .find_map(|p| p.price.as_deref().and_then(parse_price));
 
         for product in &included_products {
             let mut image_filenames: Vec<String> = Vec::new();
@@ -1025,11 +1428,12 @@ fn scrape_stamp(
                     let Some(path) = &media_item.path else {
                         continue;
                     };
-                    let clean_url = path.split('?').next().unwrap_or(path);
-                    let img_data = client.fetch_binary(clean_url)?;
-                    let img_filename = extract_image_filename(clean_url);
-                    let img_path = stamp_dir.join(&img_filename);
-                    fs::write(&img_path, &img_data)?;
+                    let clean_url = strip_url_query(path);
+                    let Some(img_filename) =
+                        save_image(client, &stamp_dir, clean_url, options.mode, !quiet)?
+                    else {
+                        continue;
+                    };
                     if !quiet {
                         print!("{}", osc8_link(clean_url, "p"));
                         stdout.flush()?;
@@ -1054,17 +1458,40 @@ fn scrape_stamp(
             let clean_long_title = product.long_title.as_ref().map(|t| clean_product_title(t));
 
             // Parse product metadata from original title (before cleaning)
-            let product_metadata = parse_product_metadata(&product.product_title);
+            let mut product_metadata = parse_product_metadata(&product.product_title);
+
+            // Check press sheet price against the pane price and record pane count
+            let sheet_price = product.price.as_deref().and_then(parse_price);
+            if let (Some(serde_json::Value::Object(meta)), Some(sheet_price), Some(pane_price)) =
+                (product_metadata.as_mut(), sheet_price, pane_price)
+            {
+                if meta.get("format").and_then(|f| f.as_str()) == Some("press-sheet") {
+                    let panes = meta.get("panes").and_then(|p| p.as_u64()).map(|p| p as u32);
+                    if let Some(panes) = check_press_sheet_panes(
+                        api_slug,
+                        &product.product_title,
+                        sheet_price,
+                        pane_price,
+                        panes,
+                    ) {
+                        meta.insert("panes".to_string(), serde_json::Value::Number(panes.into()));
+                    }
+                }
+            }
             let metadata_json = product_metadata
                 .as_ref()
                 .map(|m| serde_json::to_string(m).ok())
                 .flatten();
 
+            // Store a normalized "$X.XX" price; the raw API string is kept alongside
+            let price = product.price.as_deref().map(normalize_price);
+
             // Add to metadata products
             metadata.products.push(Product {
                 title: clean_title.clone(),
                 long_title: clean_long_title.clone(),
-                price: product.price.clone(),
+                price: price.clone(),
+                price_raw: product.price.clone(),
                 postal_store_url: product.postal_store_url.clone(),
                 stamps_forever_url: stamps_forever_url.clone(),
                 images: image_filenames,
@@ -1081,7 +1508,7 @@ fn scrape_stamp(
                     year,
                     clean_title,
                     clean_long_title,
-                    product.price,
+                    price,
                     product.postal_store_url,
                     stamps_forever_url,
                     images_json,
@@ -1091,8 +1518,16 @@ fn scrape_stamp(
         }
     }
 
+    // Images are on disk; leave metadata.conl alone (the caller rolls back the DB rows)
+    if options.mode == ScrapeMode::ImagesOnly {
+        if !quiet {
+            println!(" images only");
+        }
+        return Ok(());
+    }
+
     // Serialize metadata to CONL and write
-    let conl = serde_conl::to_string(&metadata)?;
+    let conl = metadata.to_conl()?;
     let metadata_path = stamp_dir.join("metadata.conl");
     fs::write(&metadata_path, &conl)?;
 
@@ -1103,63 +1538,7 @@ fn scrape_stamp(
         Some(serde_json::to_string(&stamp_images)?)
     };
 
-    // Build JSON for credits object
-    let mut credits_map: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
-    if let Some(ad) = &art_director {
-        credits_map.insert(
-            "art_director".to_string(),
-            serde_json::Value::String(ad.clone()),
-        );
-    }
-    if let Some(ar) = &artist {
-        credits_map.insert("artist".to_string(), serde_json::Value::String(ar.clone()));
-    }
-    if let Some(de) = &designer {
-        credits_map.insert(
-            "designer".to_string(),
-            serde_json::Value::String(de.clone()),
-        );
-    }
-    if let Some(ty) = &typographer {
-        credits_map.insert(
-            "typographer".to_string(),
-            serde_json::Value::String(ty.clone()),
-        );
-    }
-    if let Some(ph) = &photographer {
-        credits_map.insert(
-            "photographer".to_string(),
-            serde_json::Value::String(ph.clone()),
-        );
-    }
-    if let Some(il) = &illustrator {
-        credits_map.insert(
-            "illustrator".to_string(),
-            serde_json::Value::String(il.clone()),
-        );
-    }
-    if !embedded_credits.is_empty() {
-        credits_map.insert("sources".to_string(), serde_json::json!(embedded_credits));
-    }
-    let credits_json = if credits_map.is_empty() {
-        None
-    } else {
-        Some(serde_json::to_string(&credits_map)?)
-    };
-
-    // Extract about text
-    let about_text: Option<String> = detail
-        .about
-        .as_ref()
-        .map(|a| html_to_text(a))
-        .filter(|t| !t.is_empty())
-        .or_else(|| {
-            detail
-                .caption
-                .as_ref()
-                .map(|c| html_to_text(c))
-                .filter(|t| !t.is_empty())
-        });
+    let credits_json = credits_json(&metadata.credits, &embedded_credits)?;
 
     // Parse ISO date for database
     let iso_date: Option<String> = detail
@@ -1191,7 +1570,7 @@ fn scrape_stamp(
             stamp_images_json,
             sheet_images.first(),
             credits_json,
-            about_text,
+            metadata.about,
             detail.background_color,
             is_forever as i32,
         ],
@@ -1209,18 +1588,55 @@ fn scrape_stamp(
     Ok(())
 }
 
-pub fn run_scrape(filter: Option<String>, quiet: bool) -> Result<()> {
-    let client = CachedClient::new()?;
-    let conn = Connection::open("stamps.db")?;
+/// Open the existing stamps database, ensuring tables exist
+pub(crate) fn open_database() -> Result<Connection> {
+    // Don't let Connection::open create an empty database that reports "no stamps found"
+    if !Path::new(DATABASE_FILE).exists() {
+        bail!(
+            "{} not found. Run 'stamps sync' first to build the stamps database.",
+            DATABASE_FILE
+        );
+    }
+
+    let conn = Connection::open(DATABASE_FILE)?;
 
     // Ensure tables exist
     init_database(&conn)?;
 
-    // Load overrides
-    let overrides = load_overrides();
+    Ok(conn)
+}
 
-    // Load historical postal rates
-    let postal_rates = PostalRates::load()?;
+/// Narrow an ordered work list to the stamps after `after_slug`, capped at `limit`
+///
+/// Lets a long run be split deterministically across invocations.
+fn select_window(
+    stamps: Vec<(String, u32)>,
+    after_slug: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<(String, u32)>> {
+    let start = match after_slug {
+        Some(after) => match stamps.iter().position(|(slug, _)| slug == after) {
+            Some(i) => i + 1,
+            None => bail!("--after-slug '{}' is not in the work list", after),
+        },
+        None => 0,
+    };
+    Ok(stamps
+        .into_iter()
+        .skip(start)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect())
+}
+
+pub fn run_scrape(
+    filter: Option<String>,
+    cache_dir: &Path,
+    no_cache: bool,
+    after_slug: Option<&str>,
+    limit: Option<usize>,
+    options: &ScrapeOptions,
+) -> Result<()> {
+    let mut conn = open_database()?;
 
     // Get current year for default range
     let current_year: u32 = 2026;
@@ -1232,7 +1648,7 @@ pub fn run_scrape(filter: Option<String>, quiet: bool) -> Result<()> {
             let mut all_stamps = Vec::new();
             for year in (MIN_SCRAPE_YEAR..=current_year + 1).rev() {
                 let mut stmt = conn.prepare(
-                    "SELECT slug, year FROM stampsforever_stamps WHERE year = ?1 ORDER BY issue_date DESC",
+                    "SELECT slug, year FROM stampsforever_stamps WHERE year = ?1 ORDER BY issue_date DESC, slug",
                 )?;
                 let rows = stmt.query_map([year], |row| {
                     Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
@@ -1257,7 +1673,7 @@ pub fn run_scrape(filter: Option<String>, quiet: bool) -> Result<()> {
                             );
                         }
                         let mut stmt = conn.prepare(
-                            "SELECT slug, year FROM stampsforever_stamps WHERE year = ?1 ORDER BY issue_date DESC",
+                            "SELECT slug, year FROM stampsforever_stamps WHERE year = ?1 ORDER BY issue_date DESC, slug",
                         )?;
                         let rows = stmt.query_map([year], |row| {
                             Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
@@ -1277,7 +1693,7 @@ pub fn run_scrape(filter: Option<String>, quiet: bool) -> Result<()> {
                     );
                 }
                 let mut stmt = conn.prepare(
-                    "SELECT slug, year FROM stampsforever_stamps WHERE year = ?1 ORDER BY issue_date DESC",
+                    "SELECT slug, year FROM stampsforever_stamps WHERE year = ?1 ORDER BY issue_date DESC, slug",
                 )?;
                 let stamps: Vec<(String, u32)> = stmt
                     .query_map([year], |row| {
@@ -1305,20 +1721,1363 @@ pub fn run_scrape(filter: Option<String>, quiet: bool) -> Result<()> {
         bail!("No stamps found matching filter. Run 'stamps sync' first to populate the database.");
     }
 
-    let total = stamps.len();
-    if !quiet {
-        println!("Scraping {} stamps...\n", total);
+    let stamps = select_window(stamps, after_slug, limit)?;
+    scrape_stamps(&mut conn, &stamps, cache_dir, no_cache, options)
+}
+
+/// Re-scrape stamps whose scraped record is missing images or a rate type
+pub fn run_rescrape_missing(
+    cache_dir: &Path,
+    no_cache: bool,
+    after_slug: Option<&str>,
+    limit: Option<usize>,
+    options: &ScrapeOptions,
+) -> Result<()> {
+    let mut conn = open_database()?;
+    let stamps = incomplete_stamps(&conn)?;
+
+    println!("Found {} incomplete records", stamps.len());
+    let stamps = select_window(stamps, after_slug, limit)?;
+    if stamps.is_empty() {
+        return Ok(());
     }
 
-    for (i, (slug, year)) in stamps.iter().enumerate() {
-        if let Err(e) = scrape_stamp(&client, &conn, slug, *year, i + 1, total, quiet, &overrides, &postal_rates) {
-            eprintln!("\nError scraping {}: {}", slug, e);
+    scrape_stamps(&mut conn, &stamps, cache_dir, no_cache, options)
+}
+
+/// (api_slug, year) of scraped stamps missing images or a rate type
+///
+/// Imported stamps (empty `url`) have no API page to rescrape and are skipped.
+pub fn incomplete_stamps(conn: &Connection) -> Result<Vec<(String, u32)>> {
+    let mut stmt = conn.prepare(
+        "SELECT api_slug, year FROM stamps
+         WHERE (stamp_images IS NULL OR stamp_images = '[]' OR rate_type IS NULL) AND url != ''
+         ORDER BY year DESC, issue_date DESC, api_slug",
+    )?;
+    let stamps = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(stamps)
+}
+
+/// Concurrent stamps fetched by `--prefetch`
+const PREFETCH_WORKERS: usize = 8;
+
+/// Every image URL `scrape_stamp` downloads for a stamp
+///
+/// The sheet image contributes each `save_pane_image` candidate, so the full-resolution
+/// original is cached along with the derivative it falls back to.
+fn image_urls(detail: &StampDetail, filter: &ProductFilter) -> Vec<String> {
+    let mut urls: Vec<String> = detail
+        .images
+        .iter()
+        .map(|img| strip_url_query(&img.path).to_string())
+        .collect();
+    if let Some(pane) = &detail.stamp_pane {
+        for candidate in original_image_urls(&pane.path) {
+            let candidate = strip_url_query(&candidate).to_string();
+            if !urls.contains(&candidate) {
+                urls.push(candidate);
+            }
+        }
+    }
+    if let Some(products) = &detail.product_listings {
+        for product in included_products(products, filter) {
+            urls.extend(
+                product
+                    .media
+                    .iter()
+                    .flatten()
+                    .filter_map(|m| m.path.as_deref())
+                    .map(|path| strip_url_query(path).to_string()),
+            );
         }
     }
+    urls
+}
 
+/// Warm the cache with every stamp's detail JSON and images using a bounded worker pool
+///
+/// Failures are only reported here; the scrape loop retries them and records the error.
+fn prefetch(
+    client: &CachedClient,
+    stamps: &[(String, u32)],
+    options: &ScrapeOptions,
+) -> Result<()> {
+    let quiet = options.quiet;
     if !quiet {
-        println!("\nDone!");
+        println!(
+            "Prefetching {} stamps ({} workers)...",
+            stamps.len(),
+            PREFETCH_WORKERS
+        );
     }
 
-    Ok(())
+    let next = AtomicUsize::new(0);
+    let downloaded = AtomicUsize::new(0);
+    let cached = AtomicUsize::new(0);
+    let failures: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..PREFETCH_WORKERS.min(stamps.len()) {
+            scope.spawn(|| {
+                let fetcher: &dyn Fetcher = client;
+                while let Some((slug, _)) = stamps.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    let api_url = format!("{}/{}", STAMPS_API_URL, slug);
+                    let detail: StampDetail = match fetcher.fetch_json(&api_url) {
+                        Ok(detail) => detail,
+                        Err(e) => {
+                            failures.lock().unwrap().push(format!(
+                                "{}: {}",
+                                stamp_link(slug, slug, &io::stderr()),
+                                e
+                            ));
+                            continue;
+                        }
+                    };
+                    for url in image_urls(&detail, &options.products) {
+                        match client.prefetch_binary(&url) {
+                            Ok(true) => downloaded.fetch_add(1, Ordering::SeqCst),
+                            Ok(false) => cached.fetch_add(1, Ordering::SeqCst),
+                            Err(e) => {
+                                failures.lock().unwrap().push(format!(
+                                    "{}: {}",
+                                    stamp_link(slug, slug, &io::stderr()),
+                                    e
+                                ));
+                                continue;
+                            }
+                        };
+                    }
+                }
+            });
+        }
+    });
+
+    let failures = failures.into_inner().unwrap();
+    if !quiet {
+        println!(
+            "Prefetched: {} images downloaded, {} already cached, {} failed\n",
+            downloaded.into_inner(),
+            cached.into_inner(),
+            failures.len()
+        );
+    }
+    for failure in &failures {
+        eprintln!("  Prefetch failed: {}", failure);
+    }
+    Ok(())
+}
+
+/// Scrape each (api_slug, year), committing one transaction per stamp
+///
+/// With `prefetch`, the network phase runs concurrently up front and the loop
+/// reads from the warm cache.
+///
+/// With `strict`, warnings and per-stamp errors are collected and reported
+/// after the last stamp, and the run fails if there were any.
+fn scrape_stamps(
+    conn: &mut Connection,
+    stamps: &[(String, u32)],
+    cache_dir: &Path,
+    no_cache: bool,
+    options: &ScrapeOptions,
+) -> Result<()> {
+    let quiet = options.quiet;
+    let client = CachedClient::new(
+        cache_dir,
+        no_cache,
+        options.request_interval,
+        options.max_image_bytes,
+    )?;
+
+    // Load overrides
+    let overrides = load_overrides();
+
+    // Load historical postal rates
+    let postal_rates = PostalRates::load()?;
+
+    // Load about-text boilerplate to strip
+    let boilerplate = load_boilerplate()?;
+
+    let total = stamps.len();
+    if !quiet {
+        println!("Scraping {} stamps...\n", total);
+    }
+
+    let client = if options.prefetch {
+        prefetch(&client, stamps, options)?;
+        // The cache is now fresh even with --no-cache, so the scrape loop reads from it
+        CachedClient::new(
+            cache_dir,
+            false,
+            options.request_interval,
+            options.max_image_bytes,
+        )?
+    } else {
+        client
+    };
+
+    let mut progress = ProgressBar::new(total, quiet);
+    let mut warnings: Vec<String> = Vec::new();
+    for (i, (slug, year)) in stamps.iter().enumerate() {
+        progress.clear();
+        // One transaction per stamp: products and stamp rows land together,
+        // and a failed scrape rolls back (on drop) instead of leaving partial rows
+        let tx = conn.transaction()?;
+        match scrape_stamp(
+            &client,
+            &tx,
+            slug,
+            *year,
+            i + 1,
+            total,
+            options,
+            &overrides,
+            &postal_rates,
+            &boilerplate,
+            &mut warnings,
+        ) {
+            // --images-only never writes rows; roll back (on drop)
+            Ok(()) if options.mode == ScrapeMode::ImagesOnly => {}
+            Ok(()) => tx.commit()?,
+            Err(e) => {
+                let slug_link = stamp_link(slug, slug, &io::stderr());
+                eprintln!("\nError scraping {}: {}", slug_link, e);
+                warnings.push(format!("'{}' ({}) failed: {}", slug_link, year, e));
+            }
+        }
+        progress.advance(1);
+    }
+
+    if !quiet {
+        println!("\nDone!");
+    }
+
+    if options.strict && !warnings.is_empty() {
+        eprintln!("\n{} warning(s) in strict mode:", warnings.len());
+        for warning in &warnings {
+            eprintln!("  {}", warning);
+        }
+        bail!("Strict mode: {} stamp warning(s)", warnings.len());
+    }
+
+    Ok(())
+}
+
+/// Find a stamp's (api_slug, year) by site slug or API slug, using the database only
+fn resolve_stamp(conn: &Connection, slug: &str) -> Result<(String, u32)> {
+    // Prefer scraped stamps (matches either slug), then fall back to the API listing
+    let found: Option<(String, u32)> = conn
+        .query_row(
+            "SELECT api_slug, year FROM stamps WHERE slug = ?1 OR api_slug = ?1",
+            [slug],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let found = match found {
+        Some(f) => Some(f),
+        None => conn
+            .query_row(
+                "SELECT slug, year FROM stampsforever_stamps WHERE slug = ?1 AND year IS NOT NULL",
+                [slug],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?,
+    };
+    found.with_context(|| format!("Stamp '{}' not found in {}", slug, DATABASE_FILE))
+}
+
+/// Print links to a stamp's local data directory, metadata, and stampsforever.com page
+///
+/// Accepts either the site slug or the API slug. Resolution uses the database only.
+pub fn run_open(slug: &str) -> Result<()> {
+    let conn = open_database()?;
+    let (api_slug, year) = resolve_stamp(&conn, slug)?;
+
+    let stamp_dir = PathBuf::from(STAMPS_DIR)
+        .join(year.to_string())
+        .join(&api_slug);
+    let metadata_path = stamp_dir.join("metadata.conl");
+    let forever_url = format!("https://www.stampsforever.com/stamps/{}", api_slug);
+
+    let dir_str = stamp_dir.to_string_lossy();
+    let metadata_str = metadata_path.to_string_lossy();
+    println!("Directory: {}", osc8_file_link(&dir_str, &dir_str));
+    if metadata_path.exists() {
+        println!(
+            "Metadata:  {}",
+            osc8_file_link(&metadata_str, &metadata_str)
+        );
+    } else {
+        println!("Metadata:  {} (not scraped yet)", metadata_str);
+    }
+    println!("Web:       {}", osc8_link(&forever_url, &forever_url));
+
+    Ok(())
+}
+
+/// The `  field = value` lines of a stamp's entry in a year overrides file
+fn override_fields(content: &str, api_slug: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .skip_while(|line| line.trim_end() != api_slug)
+        .skip(1)
+        .take_while(|line| line.starts_with(' '))
+        .filter_map(|line| line.trim().split_once(" = "))
+        .map(|(field, value)| (field.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Set `fields` on a stamp's entry in a year overrides file, adding the entry if needed
+///
+/// Existing `field = value` lines are replaced in place; comments and other entries
+/// are left untouched.
+fn upsert_override(content: &str, api_slug: &str, fields: &[(&str, String)]) -> String {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let Some(start) = lines.iter().position(|line| line.trim_end() == api_slug) else {
+        let mut content = content.to_string();
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!("\n{}\n", api_slug));
+        for (field, value) in fields {
+            content.push_str(&format!("  {} = {}\n", field, value));
+        }
+        return content;
+    };
+
+    let mut end = start + 1;
+    while lines.get(end).is_some_and(|line| line.starts_with(' ')) {
+        end += 1;
+    }
+    for (field, value) in fields {
+        let line = format!("  {} = {}", field, value);
+        let prefix = format!("{} = ", field);
+        match (start + 1..end).find(|&i| lines[i].trim_start().starts_with(&prefix)) {
+            Some(i) => lines[i] = line,
+            None => {
+                lines.insert(end, line);
+                end += 1;
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Ask for a value until `parse` accepts it; a blank answer (or end of input) keeps the current one
+fn prompt(
+    input: &mut impl BufRead,
+    label: &str,
+    current: Option<&str>,
+    parse: impl Fn(&str) -> std::result::Result<String, String>,
+) -> Result<Option<String>> {
+    loop {
+        print!("{} [{}]: ", label, current.unwrap_or(""));
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            println!();
+            return Ok(None);
+        }
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Ok(None);
+        }
+        match parse(answer) {
+            Ok(value) => return Ok(Some(value)),
+            Err(e) => println!("  {}", e),
+        }
+    }
+}
+
+/// A rate type by number in `VALID_RATE_TYPES` (1-based) or by name (any case)
+fn parse_rate_type_choice(answer: &str) -> std::result::Result<String, String> {
+    let by_number = answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| VALID_RATE_TYPES.get(n.checked_sub(1)?));
+    by_number
+        .or_else(|| {
+            VALID_RATE_TYPES
+                .iter()
+                .find(|rt| rt.eq_ignore_ascii_case(answer))
+        })
+        .map(|rt| rt.to_string())
+        .ok_or_else(|| {
+            format!(
+                "Unknown rate_type '{}'; enter 1-{} or a name from the list",
+                answer,
+                VALID_RATE_TYPES.len()
+            )
+        })
+}
+
+/// Show a stamp's API data and prompt on stdin for overrides, saved to enrichment/stamps/<year>.conl
+///
+/// Prompts for rate_type (validated against `VALID_RATE_TYPES`), rate, issue_date and
+/// issue_location; a blank answer keeps the current override.
+pub fn run_fix(slug: &str, cache_dir: &Path, no_cache: bool) -> Result<()> {
+    let conn = open_database()?;
+    let (api_slug, year) = resolve_stamp(&conn, slug)?;
+
+    let client = CachedClient::new(
+        cache_dir,
+        no_cache,
+        DEFAULT_REQUEST_INTERVAL,
+        DEFAULT_MAX_IMAGE_BYTES,
+    )?;
+    let fetcher: &dyn Fetcher = &client;
+    let detail: StampDetail = fetcher.fetch_json(&format!("{}/{}", STAMPS_API_URL, api_slug))?;
+
+    let path = PathBuf::from(OVERRIDES_DIR).join(format!("{}.conl", year));
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => format!(
+            "; Overrides for {}\n; Format: api-slug followed by indented field = value\n",
+            year
+        ),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let current = override_fields(&content, &api_slug);
+    let current = |field: &str| {
+        current
+            .iter()
+            .find(|(f, _)| f == field)
+            .map(|(_, v)| v.as_str())
+    };
+
+    println!(
+        "{} ({}, {})",
+        detail.name,
+        stamp_link(&api_slug, &api_slug, &io::stdout()),
+        year
+    );
+    for (field, api_value) in [
+        ("rate_type", &detail.rate_type),
+        ("rate", &detail.rate),
+        ("issue_date", &detail.issue_date),
+        ("issue_location", &detail.issue_location),
+    ] {
+        let override_note = current(field)
+            .map(|v| format!("  (override: {})", v))
+            .unwrap_or_default();
+        println!(
+            "  {:<15} {}{}",
+            field,
+            api_value.as_deref().unwrap_or("-"),
+            override_note
+        );
+    }
+
+    println!("\nRate types:");
+    for (i, rate_type) in VALID_RATE_TYPES.iter().enumerate() {
+        println!("  {:>2}. {}", i + 1, rate_type);
+    }
+    println!("Press Enter to keep the current value.\n");
+
+    let mut input = io::stdin().lock();
+    let mut fields: Vec<(&str, String)> = Vec::new();
+    let answers = [
+        (
+            "rate_type",
+            prompt(
+                &mut input,
+                "rate_type",
+                current("rate_type"),
+                parse_rate_type_choice,
+            )?,
+        ),
+        (
+            "rate",
+            prompt(&mut input, "rate", current("rate"), |answer| {
+                let answer = answer.trim_start_matches('$');
+                answer
+                    .parse::<f64>()
+                    .map(|_| answer.to_string())
+                    .map_err(|_| format!("'{}' isn't a number (e.g. 7.35)", answer))
+            })?,
+        ),
+        (
+            "issue_date",
+            prompt(&mut input, "issue_date", current("issue_date"), |answer| {
+                NaiveDate::parse_from_str(answer, "%Y-%m-%d")
+                    .map(|date| date.to_string())
+                    .map_err(|_| format!("'{}' isn't a YYYY-MM-DD date", answer))
+            })?,
+        ),
+        (
+            "issue_location",
+            prompt(
+                &mut input,
+                "issue_location",
+                current("issue_location"),
+                |answer| Ok(answer.to_string()),
+            )?,
+        ),
+    ];
+    for (field, answer) in answers {
+        if let Some(value) = answer {
+            if current(field) != Some(value.as_str()) {
+                fields.push((field, value));
+            }
+        }
+    }
+
+    if fields.is_empty() {
+        println!("No changes.");
+        return Ok(());
+    }
+    fs::create_dir_all(OVERRIDES_DIR)?;
+    fs::write(&path, upsert_override(&content, &api_slug, &fields))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!(
+        "Updated {}. Re-scrape with: stamps scrape {}",
+        path.display(),
+        api_slug
+    );
+    Ok(())
+}
+
+/// Case-insensitive name (and optionally series) search over scraped stamps
+pub fn run_search(query: &str, year: Option<u32>, include_series: bool) -> Result<()> {
+    let conn = open_database()?;
+
+    // Match the query literally: escape LIKE wildcards
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let pattern = format!("%{}%", escaped);
+
+    let mut stmt = conn.prepare(
+        "SELECT slug, api_slug, year, rate_type FROM stamps
+         WHERE (name LIKE ?1 ESCAPE '\\' OR (?2 AND series LIKE ?1 ESCAPE '\\'))
+           AND (?3 IS NULL OR year = ?3)
+         ORDER BY year DESC, slug",
+    )?;
+    let rows: Vec<(String, String, u32, Option<String>)> = stmt
+        .query_map((&pattern, include_series, year), |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    if rows.is_empty() {
+        println!("No stamps matching '{}'", query);
+        return Ok(());
+    }
+
+    let slug_width = rows
+        .iter()
+        .map(|(slug, ..)| slug.len())
+        .max()
+        .unwrap_or(4)
+        .max(4);
+    println!("{:<slug_width$}  Year  Rate Type", "Slug");
+    for (slug, api_slug, year, rate_type) in &rows {
+        println!(
+            "{}{}  {}  {}",
+            stamp_link(api_slug, slug, &io::stdout()),
+            " ".repeat(slug_width - slug.len()),
+            year,
+            rate_type.as_deref().unwrap_or("-")
+        );
+    }
+    println!("\n{} match(es)", rows.len());
+
+    Ok(())
+}
+
+/// Report years from `MIN_SCRAPE_YEAR` to next year with no scraped stamps, or fewer than `min_count`
+///
+/// Read-only; the API listing count is shown alongside to tell a failed scrape
+/// from a year that simply has few stamps.
+pub fn run_years(min_count: u32) -> Result<()> {
+    let conn = open_database()?;
+    let last_year = chrono::Datelike::year(&chrono::Local::now()) as u32 + 1;
+
+    let counts = |sql: &str| -> Result<HashMap<u32, u32>> {
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    };
+    let scraped = counts("SELECT year, COUNT(*) FROM stamps GROUP BY year")?;
+    let listed = counts(
+        "SELECT year, COUNT(*) FROM stampsforever_stamps WHERE year IS NOT NULL GROUP BY year",
+    )?;
+
+    let mut flagged = 0;
+    println!("Year  Scraped  Listed");
+    for year in MIN_SCRAPE_YEAR..=last_year {
+        let count = scraped.get(&year).copied().unwrap_or(0);
+        let status = if count == 0 {
+            "missing"
+        } else if count < min_count {
+            "sparse"
+        } else {
+            continue;
+        };
+        flagged += 1;
+        println!(
+            "{}  {:>7}  {:>6}  {}",
+            year,
+            count,
+            listed.get(&year).copied().unwrap_or(0),
+            status
+        );
+    }
+
+    println!(
+        "\n{} of {} years ({}-{}) missing or under {} stamps",
+        flagged,
+        last_year - MIN_SCRAPE_YEAR + 1,
+        MIN_SCRAPE_YEAR,
+        last_year,
+        min_count
+    );
+    Ok(())
+}
+
+/// Why a stamp's stored rate disagrees with the rate history, if it does
+///
+/// Forever stamps store today's rate; others store the rate on their issue date.
+/// Rate types without a history (and stamps with nothing to compare) are skipped.
+fn rate_mismatch(
+    postal_rates: &PostalRates,
+    rate_type: &str,
+    rate: &str,
+    issue_date: Option<&str>,
+    forever: bool,
+    today: NaiveDate,
+) -> Option<String> {
+    let rate: f64 = rate.parse().ok()?;
+    let date = if forever {
+        today
+    } else {
+        NaiveDate::parse_from_str(issue_date?, "%Y-%m-%d").ok()?
+    };
+    let expected = postal_rates.rate_for_type(rate_type, date).ok()?;
+    if (rate - expected).abs() < 0.005 {
+        return None;
+    }
+    Some(format!(
+        "{} rate ${:.2}, expected ${:.2} on {}",
+        rate_type, rate, expected, date
+    ))
+}
+
+/// Check each scraped stamp's rate against the rate history (read-only)
+///
+/// Catches data-entry errors in `rate` overrides, e.g. a Two Ounce stamp that
+/// isn't the 1oz letter rate plus an additional ounce. Fails if any disagree.
+pub fn run_validate_rates() -> Result<()> {
+    let conn = open_database()?;
+    let postal_rates = PostalRates::load()?;
+    let today = chrono::Local::now().date_naive();
+
+    let mut stmt = conn.prepare(
+        "SELECT slug, api_slug, year, rate_type, rate, issue_date, forever FROM stamps
+         WHERE rate_type IS NOT NULL AND rate IS NOT NULL
+         ORDER BY year, slug",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, u32>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, Option<String>>(5)?,
+            row.get::<_, bool>(6)?,
+        ))
+    })?;
+
+    let mut checked = 0;
+    let mut mismatches = 0;
+    for row in rows {
+        let (slug, api_slug, year, rate_type, rate, issue_date, forever) = row?;
+        checked += 1;
+        if let Some(reason) = rate_mismatch(
+            &postal_rates,
+            &rate_type,
+            &rate,
+            issue_date.as_deref(),
+            forever,
+            today,
+        ) {
+            mismatches += 1;
+            println!(
+                "{} ({}): {}",
+                stamp_link(&api_slug, &slug, &io::stdout()),
+                year,
+                reason
+            );
+        }
+    }
+
+    if mismatches > 0 {
+        bail!(
+            "{} of {} stamps have a rate inconsistent with the rate history",
+            mismatches,
+            checked
+        );
+    }
+    println!("All {} stamp rates match the rate history", checked);
+    Ok(())
+}
+
+/// Collect every http(s) URL in a cached API response (image and media paths)
+fn collect_urls(value: &serde_json::Value, urls: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(s) if s.starts_with("http://") || s.starts_with("https://") => {
+            urls.push(s.clone());
+        }
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_urls(v, urls)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| collect_urls(v, urls)),
+        _ => {}
+    }
+}
+
+/// Cache files referenced by one cached stamp API response
+fn referenced_cache_paths(cache_dir: &Path, response_path: &Path) -> Vec<PathBuf> {
+    let Some(value) = fs::read_to_string(response_path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+    else {
+        return Vec::new();
+    };
+    let mut urls = Vec::new();
+    collect_urls(&value, &mut urls);
+    urls.iter()
+        .map(|url| url_cache_path(cache_dir, url))
+        .collect()
+}
+
+/// Delete cached API responses for slugs no longer in `stampsforever_stamps`
+///
+/// Images referenced only by the pruned responses are deleted too; images
+/// still referenced by a current stamp are kept.
+pub fn run_cache_prune(cache_dir: &Path, dry_run: bool) -> Result<()> {
+    let conn = open_database()?;
+    let mut stmt = conn.prepare("SELECT slug FROM stampsforever_stamps")?;
+    let current: HashSet<String> = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .filter_map(|r| r.ok())
+        .collect();
+    drop(stmt);
+
+    // An empty table would mark every cached response as orphaned
+    if current.is_empty() {
+        bail!("No stamps in stampsforever_stamps. Run 'stamps sync' first.");
+    }
+
+    let api_dir = url_cache_path(cache_dir, STAMPS_API_URL);
+    if !api_dir.is_dir() {
+        println!("No cached stamp responses in {}", api_dir.display());
+        return Ok(());
+    }
+
+    let mut orphaned: Vec<PathBuf> = Vec::new();
+    let mut orphaned_images: HashSet<PathBuf> = HashSet::new();
+    let mut kept_images: HashSet<PathBuf> = HashSet::new();
+
+    for entry in fs::read_dir(&api_dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let slug = path.file_name().unwrap().to_string_lossy().to_string();
+        let images = referenced_cache_paths(cache_dir, &path);
+        if current.contains(&slug) {
+            kept_images.extend(images);
+        } else {
+            orphaned_images.extend(images);
+            orphaned.push(path);
+        }
+    }
+
+    let mut to_remove: Vec<PathBuf> = orphaned_images
+        .into_iter()
+        .filter(|p| !kept_images.contains(p) && p.is_file())
+        .collect();
+    to_remove.sort();
+    orphaned.sort();
+    let image_count = to_remove.len();
+    to_remove.splice(0..0, orphaned.iter().cloned());
+
+    let mut bytes = 0u64;
+    for path in &to_remove {
+        bytes += fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if dry_run {
+            println!("Would remove {}", path.display());
+        } else {
+            fs::remove_file(path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            println!("Removed {}", path.display());
+        }
+    }
+
+    println!(
+        "{} {} orphaned responses and {} images ({:.1} MB)",
+        if dry_run { "Would prune" } else { "Pruned" },
+        orphaned.len(),
+        image_count,
+        bytes as f64 / 1_000_000.0
+    );
+    Ok(())
+}
+
+/// Override keys with no matching API slug in `stampsforever_stamps` for their year
+///
+/// Each orphan comes with the slug's actual year if it exists in another (a misfiled entry).
+fn orphan_overrides(
+    conn: &Connection,
+    overrides: &HashMap<u32, HashMap<String, StampOverrides>>,
+) -> Result<Vec<(u32, String, Option<u32>)>> {
+    let mut stmt = conn.prepare("SELECT year FROM stampsforever_stamps WHERE slug = ?1")?;
+    let mut orphans = Vec::new();
+    for (&year, stamps) in overrides {
+        for slug in stamps.keys() {
+            let actual: Option<u32> = stmt
+                .query_row([slug], |row| row.get(0))
+                .optional()?
+                .flatten();
+            if actual != Some(year) {
+                orphans.push((year, slug.clone(), actual));
+            }
+        }
+    }
+    orphans.sort();
+    Ok(orphans)
+}
+
+/// Report override keys in enrichment/stamps/<year>.conl that match no stamp that year
+///
+/// A misspelled or stale key is otherwise silently ignored. Fails if any are found.
+pub fn run_overrides_check() -> Result<()> {
+    let conn = open_database()?;
+    let count: usize = conn.query_row("SELECT COUNT(*) FROM stampsforever_stamps", [], |row| {
+        row.get(0)
+    })?;
+    // An empty table would make every override look orphaned
+    if count == 0 {
+        bail!("No stamps in stampsforever_stamps. Run 'stamps sync' first.");
+    }
+
+    let overrides = load_overrides();
+    let total: usize = overrides.values().map(|o| o.len()).sum();
+    let orphans = orphan_overrides(&conn, &overrides)?;
+    for (year, slug, actual) in &orphans {
+        let path = PathBuf::from(OVERRIDES_DIR).join(format!("{}.conl", year));
+        match actual {
+            Some(actual) => println!(
+                "{}: {} is a {} stamp, not {}",
+                path.display(),
+                slug,
+                actual,
+                year
+            ),
+            None => println!("{}: {} matches no stamp", path.display(), slug),
+        }
+    }
+
+    if !orphans.is_empty() {
+        bail!("{} of {} overrides match no stamp", orphans.len(), total);
+    }
+    println!("All {} overrides match a stamp", total);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::FixtureFetcher;
+
+    #[test]
+    fn test_html_to_text_decodes_entities() {
+        assert_eq!(
+            html_to_text("<p>Valentine&#8217;s Day</p>"),
+            "Valentine\u{2019}s Day"
+        );
+        assert_eq!(html_to_text("Valentine&amp;#8217;s"), "Valentine\u{2019}s");
+        assert_eq!(html_to_text("Birds &amp; Blooms"), "Birds & Blooms");
+        assert_eq!(html_to_text("1920s&mdash;1930s"), "1920s\u{2014}1930s");
+        assert_eq!(html_to_text("Caf&#xE9;&nbsp;Society"), "Caf\u{e9} Society");
+        assert_eq!(
+            html_to_text("First-Class Mail&amp;reg;"),
+            "First-Class Mail\u{ae}"
+        );
+        assert_eq!(
+            decode_html_entities("AT&T & co &bogus;"),
+            "AT&T & co &bogus;"
+        );
+    }
+
+    #[test]
+    fn test_strip_boilerplate() {
+        let phrases = vec![
+            "This Forever stamp will always be equal in value to the current First-Class Mail® one-ounce price.".to_string(),
+            "Like a Forever® stamp, this stamp will always be valid for the rate printed on it.".to_string(),
+        ];
+        assert_eq!(
+            strip_boilerplate(
+                "A stamp about frogs. This Forever stamp will always be equal in value to the current First-Class Mail® one-ounce price. ",
+                &phrases
+            ),
+            "A stamp about frogs."
+        );
+        // Stacked phrases are all removed
+        assert_eq!(
+            strip_boilerplate(
+                "Peonies. Like a Forever® stamp, this stamp will always be valid for the rate printed on it. This Forever stamp will always be equal in value to the current First-Class Mail® one-ounce price.",
+                &phrases
+            ),
+            "Peonies."
+        );
+        // Phrases only match at the end
+        assert_eq!(
+            strip_boilerplate(
+                "Like a Forever® stamp, this stamp will always be valid for the rate printed on it. It shows a tulip.",
+                &phrases
+            ),
+            "Like a Forever® stamp, this stamp will always be valid for the rate printed on it. It shows a tulip."
+        );
+        // Whitespace is normalized: wrapped lines, doubled spaces and &nbsp; all match
+        assert_eq!(
+            strip_boilerplate(
+                "Frogs. This Forever stamp will always be\nequal in value to the current  First-Class\u{a0}Mail® one-ounce price.",
+                &phrases
+            ),
+            "Frogs."
+        );
+        assert_eq!(
+            strip_boilerplate("Frogs. Like a Forever®stamp, this stamp will always be valid for the rate printed on it.", &phrases),
+            "Frogs. Like a Forever®stamp, this stamp will always be valid for the rate printed on it."
+        );
+        assert_eq!(
+            strip_boilerplate("No boilerplate here.", &[]),
+            "No boilerplate here."
+        );
+    }
+
+    #[test]
+    fn test_image_urls_from_fixture() {
+        let api_url = format!("{}/golden-poppies", STAMPS_API_URL);
+        let fetcher = FixtureFetcher::default().with(
+            &api_url,
+            r#"{
+                "slug": "golden-poppies",
+                "name": "Golden Poppies",
+                "images": [{"path": "https://cdn.example/stamp.png?w=400"}],
+                "stamp_pane": {"path": "https://cdn.example/pane-800x600.png?derivative_type=large"},
+                "product_listings": [
+                    {"product_title": "Pane of 20", "media": [{"path": "https://cdn.example/p1.jpg"}, {"url": "video"}]},
+                    {"product_title": "Pane of 20", "media": [{"path": "https://cdn.example/dupe.jpg"}]},
+                    {"product_title": "First Day Cover", "media": [{"path": "https://cdn.example/fdc.jpg"}]}
+                ]
+            }"#,
+        );
+        let fetcher: &dyn Fetcher = &fetcher;
+
+        let detail: StampDetail = fetcher.fetch_json(&api_url).unwrap();
+        assert_eq!(
+            image_urls(&detail, &ProductFilter::default()),
+            vec![
+                "https://cdn.example/stamp.png",
+                "https://cdn.example/pane.png",
+                "https://cdn.example/pane-800x600.png",
+                "https://cdn.example/p1.jpg",
+            ]
+        );
+        assert!(fetcher.fetch_text("https://cdn.example/other").is_err());
+    }
+
+    /// Run `scrape_stamp` on 2025's `api_slug`, writing stamp folders under `data_dir`
+    fn scrape_fixture(
+        fetcher: &FixtureFetcher,
+        api_slug: &str,
+        overrides: HashMap<String, StampOverrides>,
+        prefer: Precedence,
+        data_dir: &Path,
+    ) -> (Connection, Vec<String>) {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_database(&conn).unwrap();
+        let options = ScrapeOptions {
+            quiet: true,
+            strict: false,
+            mode: ScrapeMode::Full,
+            prefetch: false,
+            products: ProductFilter::default(),
+            slug_format: SlugFormat::New,
+            prefer,
+            request_interval: Duration::ZERO,
+            max_image_bytes: DEFAULT_MAX_IMAGE_BYTES,
+            data_dir: data_dir.to_path_buf(),
+        };
+        let mut warnings = Vec::new();
+        scrape_stamp(
+            fetcher,
+            &conn,
+            api_slug,
+            2025,
+            1,
+            1,
+            &options,
+            &HashMap::from([(2025, overrides)]),
+            &PostalRates::load().unwrap(),
+            &[],
+            &mut warnings,
+        )
+        .unwrap();
+        (conn, warnings)
+    }
+
+    const FIXTURE_HARBOR_LIGHTS: &str = r#"{
+        "slug": "harbor-lights",
+        "name": "Harbor Lights",
+        "issue_date": "March 1, 2025",
+        "issue_location": "Portland, ME",
+        "rate_type": "Forever",
+        "series": {"name": "Lighthouse"},
+        "images": [{"path": "https://cdn.example/harbor.png?w=400"}],
+        "people_groupings": [{"heading": "Designer", "people": [{"name": "Ana Rivera"}]}],
+        "product_listings": [
+            {"product_title": "Pane of 20", "price": "$15.60", "media": [{"path": "https://cdn.example/pane.jpg"}]}
+        ]
+    }"#;
+
+    fn harbor_lights_fetcher() -> FixtureFetcher {
+        FixtureFetcher::default()
+            .with(
+                &format!("{}/harbor-lights", STAMPS_API_URL),
+                FIXTURE_HARBOR_LIGHTS,
+            )
+            .with("https://cdn.example/harbor.png", "stamp image")
+            .with("https://cdn.example/pane.jpg", "product image")
+    }
+
+    #[test]
+    fn test_scrape_stamp_writes_metadata() {
+        let dir = std::env::temp_dir().join(format!("usps-scrape-{}", std::process::id()));
+        let (conn, warnings) = scrape_fixture(
+            &harbor_lights_fetcher(),
+            "harbor-lights",
+            HashMap::new(),
+            Precedence::default(),
+            &dir,
+        );
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        let stamp_dir = dir.join("2025/harbor-lights");
+        assert_eq!(
+            fs::read(stamp_dir.join("harbor.png")).unwrap(),
+            b"stamp image"
+        );
+        assert_eq!(
+            fs::read(stamp_dir.join("pane.jpg")).unwrap(),
+            b"product image"
+        );
+        let conl = fs::read_to_string(stamp_dir.join("metadata.conl")).unwrap();
+        for line in [
+            "name = Harbor Lights\n",
+            "slug = harbor-lights-forever-2025\n",
+            "issue_date = 2025-03-01\n",
+            "issue_location = Portland, ME\n",
+            "rate_type = Forever\n",
+            "series = Lighthouse\n",
+        ] {
+            assert!(conl.contains(line), "missing {:?} in\n{}", line, conl);
+        }
+        assert!(conl.contains("harbor.png") && conl.contains("Ana Rivera"));
+        assert!(conl.contains("$15.60"));
+
+        let (slug, products): (String, usize) = conn
+            .query_row(
+                "SELECT slug, (SELECT COUNT(*) FROM products WHERE stamp_slug = stamps.slug)
+                 FROM stamps WHERE api_slug = 'harbor-lights'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((slug.as_str(), products), ("harbor-lights-forever-2025", 1));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scrape_stamp_precedence() {
+        let overrides = || {
+            HashMap::from([(
+                "harbor-lights".to_string(),
+                StampOverrides {
+                    issue_location: Some("Augusta, ME".to_string()),
+                    rate: Some("0.73".to_string()),
+                    ..StampOverrides::default()
+                },
+            )])
+        };
+        let scrape = |prefer: Precedence| {
+            let dir = std::env::temp_dir().join(format!(
+                "usps-precedence-{:?}-{}",
+                prefer,
+                std::process::id()
+            ));
+            scrape_fixture(
+                &harbor_lights_fetcher(),
+                "harbor-lights",
+                overrides(),
+                prefer,
+                &dir,
+            );
+            let conl = fs::read_to_string(dir.join("2025/harbor-lights/metadata.conl")).unwrap();
+            fs::remove_dir_all(&dir).unwrap();
+            conl
+        };
+
+        // Overrides win by default
+        let conl = scrape(Precedence::Override);
+        assert!(conl.contains("issue_location = Augusta, ME\n"));
+        assert!(conl.contains("rate = 0.73\n"));
+
+        // With --prefer api they only fill fields the API left empty (it has no rate)
+        let conl = scrape(Precedence::Api);
+        assert!(conl.contains("issue_location = Portland, ME\n"));
+        assert!(conl.contains("rate = 0.73\n"));
+    }
+
+    #[test]
+    fn test_scrape_stamp_renames_series() {
+        let dir = std::env::temp_dir().join(format!("usps-series-{}", std::process::id()));
+        let overrides = HashMap::from([(
+            "harbor-lights".to_string(),
+            StampOverrides {
+                series: Some("Lighthouses".to_string()),
+                ..StampOverrides::default()
+            },
+        )]);
+        let (conn, _) = scrape_fixture(
+            &harbor_lights_fetcher(),
+            "harbor-lights",
+            overrides,
+            Precedence::default(),
+            &dir,
+        );
+
+        // The API's "Lighthouse" is renamed in both metadata.conl and the database
+        let conl = fs::read_to_string(dir.join("2025/harbor-lights/metadata.conl")).unwrap();
+        assert!(conl.contains("series = Lighthouses\n"));
+        let series: String = conn
+            .query_row("SELECT series FROM stamps", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(series, "Lighthouses");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_pane_image() {
+        let dir = std::env::temp_dir().join(format!("usps-pane-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let png = |width: u32, height: u32| {
+            let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+            bytes.extend_from_slice(&width.to_be_bytes());
+            bytes.extend_from_slice(&height.to_be_bytes());
+            bytes.extend_from_slice(&[8, 6, 0, 0, 0, 0, 0, 0, 0]);
+            bytes
+        };
+        let derivative = "https://cdn.example/pane-800x600.png?derivative_type=large";
+        let save = |fetcher: &FixtureFetcher| {
+            save_pane_image(fetcher, &dir, derivative, ScrapeMode::Full, false)
+                .unwrap()
+                .unwrap()
+        };
+
+        // The original is preferred when it exists
+        let fetcher = FixtureFetcher::default()
+            .with("https://cdn.example/pane.png", png(4000, 3000))
+            .with("https://cdn.example/pane-800x600.png", png(800, 600));
+        let saved = save(&fetcher);
+        assert_eq!(saved.filename, "pane.png");
+        assert_eq!(saved.size, Some((4000, 3000)));
+
+        // An error page instead of an image falls back to the derivative
+        let fetcher = FixtureFetcher::default()
+            .with("https://cdn.example/pane.png", "<html>Not Found</html>")
+            .with("https://cdn.example/pane-800x600.png", png(800, 600));
+        fs::remove_file(dir.join("pane.png")).unwrap();
+        let saved = save(&fetcher);
+        assert_eq!(saved.url, "https://cdn.example/pane-800x600.png");
+        assert_eq!(saved.size, Some((800, 600)));
+        assert!(!dir.join("pane.png").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_about_text_description_source() {
+        let detail: StampDetail = serde_json::from_str(
+            r#"{
+                "slug": "golden-poppies",
+                "name": "Golden Poppies",
+                "about": "<p>The about text.</p>",
+                "caption": "<p>The caption.</p>",
+                "images": []
+            }"#,
+        )
+        .unwrap();
+        let about = Some("The about text.".to_string());
+        let caption = Some("The caption.".to_string());
+        assert_eq!(about_text(&detail, &[], None), about);
+        assert_eq!(about_text(&detail, &[], Some("about")), about);
+        assert_eq!(about_text(&detail, &[], Some("caption")), caption);
+
+        // The preferred field falls back to the other when it's empty
+        let about_only = StampDetail {
+            caption: None,
+            ..detail
+        };
+        assert_eq!(about_text(&about_only, &[], Some("caption")), about);
+    }
+
+    #[test]
+    fn test_rate_mismatch() {
+        let rates = PostalRates::load().unwrap();
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let today = date("2025-07-14");
+        let check = |rate_type, rate, issue_date, forever| {
+            rate_mismatch(&rates, rate_type, rate, issue_date, forever, today)
+        };
+
+        // Two Ounce = 1oz letter ($0.55) + additional ounce ($0.20) in mid-2021
+        assert_eq!(check("Two Ounce", "0.75", Some("2021-06-01"), false), None);
+        assert_eq!(
+            check("Two Ounce", "0.78", Some("2021-06-01"), false).as_deref(),
+            Some("Two Ounce rate $0.78, expected $0.75 on 2021-06-01")
+        );
+        // Forever stamps are checked against today's rates
+        assert_eq!(
+            check("Two Ounce", "0.75", Some("2021-06-01"), true).as_deref(),
+            Some("Two Ounce rate $0.75, expected $1.07 on 2025-07-14")
+        );
+        // Nothing to compare: no history for the type, or no issue date
+        assert_eq!(check("Definitive", "5.00", Some("2021-06-01"), false), None);
+        assert_eq!(check("Two Ounce", "0.78", None, false), None);
+    }
+
+    #[test]
+    fn test_credits_json() {
+        let credits = Credits {
+            typographer: vec!["Kim Lee".to_string()],
+            art_director: vec!["Greg Breeding".to_string()],
+            designer: vec!["Ana Rivera".to_string(), "Jane Doe".to_string()],
+            ..Credits::default()
+        };
+        assert_eq!(
+            credits_json(&credits, &["Greg Breeding".to_string()])
+                .unwrap()
+                .as_deref(),
+            Some(
+                r#"{"art_director":"Greg Breeding","designer":["Ana Rivera","Jane Doe"],"sources":["Greg Breeding"],"typographer":"Kim Lee"}"#
+            )
+        );
+        assert_eq!(credits_json(&Credits::default(), &[]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_orphan_overrides() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_database(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO stampsforever_stamps (slug, name, url, year) VALUES
+                ('love-2025', 'Love', '', 2025),
+                ('flags', 'Flags', '', 2024);",
+        )
+        .unwrap();
+
+        let entries = |slugs: &[&str]| {
+            slugs
+                .iter()
+                .map(|slug| (slug.to_string(), StampOverrides::default()))
+                .collect::<HashMap<_, _>>()
+        };
+        let overrides = HashMap::from([
+            (2025, entries(&["love-2025", "lvoe-2025", "flags"])),
+            (2024, entries(&["flags"])),
+        ]);
+        assert_eq!(
+            orphan_overrides(&conn, &overrides).unwrap(),
+            [
+                (2025, "flags".to_string(), Some(2024)),
+                (2025, "lvoe-2025".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_upsert_override() {
+        let content = "; Overrides for 2019\n\nhealing-ptsd\n  rate_type = Semipostal\n  issued = 2019-12-02\n\njoshua-tree\n  rate = 7.35\n";
+        assert_eq!(
+            override_fields(content, "healing-ptsd"),
+            vec![
+                ("rate_type".to_string(), "Semipostal".to_string()),
+                ("issued".to_string(), "2019-12-02".to_string()),
+            ]
+        );
+
+        // Existing fields are replaced in place, new ones appended to the entry
+        let fields = [
+            ("rate_type", "Forever".to_string()),
+            ("rate", "0.55".to_string()),
+        ];
+        assert_eq!(
+            upsert_override(content, "healing-ptsd", &fields),
+            "; Overrides for 2019\n\nhealing-ptsd\n  rate_type = Forever\n  issued = 2019-12-02\n  rate = 0.55\n\njoshua-tree\n  rate = 7.35\n"
+        );
+        // A stamp without an entry gets a new one at the end
+        assert_eq!(
+            upsert_override(content, "bethesda-fountain", &fields[1..]),
+            format!("{}\nbethesda-fountain\n  rate = 0.55\n", content)
+        );
+
+        assert_eq!(parse_rate_type_choice("1").as_deref(), Ok("Forever"));
+        assert_eq!(
+            parse_rate_type_choice("two ounce").as_deref(),
+            Ok("Two Ounce")
+        );
+        assert!(parse_rate_type_choice("0").is_err());
+        assert!(parse_rate_type_choice("Forevr").is_err());
+    }
+
+    #[test]
+    fn test_issued_canceled() {
+        assert!(is_canceled("canceled"));
+        assert!(is_canceled(" Cancelled "));
+        assert!(is_canceled("false"));
+        assert!(!is_canceled("2019-12-02"));
+        assert!(!is_canceled("true"));
+    }
+
+    #[test]
+    fn test_product_filter() {
+        let default = ProductFilter::default();
+        assert!(default.includes("Pane of 20"));
+        assert!(default.includes("Press Sheet with Die-cuts"));
+        assert!(default.includes("Coil of 100 PSA (BCA)"));
+        assert!(!default.includes("Digital Color Postmark First Day Cover"));
+        assert!(!default.includes("Strip of 5"));
+        assert!(!default.includes("Framed Art"));
+
+        let opted = ProductFilter {
+            include_first_day_covers: true,
+            include_strips: true,
+            include_press_sheets: false,
+        };
+        assert!(opted.includes("Digital Color Postmark First Day Cover"));
+        assert!(opted.includes("Strip of 5"));
+        assert!(!opted.includes("Press Sheet with Die-cuts"));
+        assert!(opted.includes("Pane of 20"));
+    }
 }
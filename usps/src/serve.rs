@@ -0,0 +1,201 @@
+//! Minimal blocking static file server for previewing the generated site
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+/// Serve `root` on localhost until interrupted, opening a browser once bound if `open`
+pub fn run_serve(root: &Path, port: u16, open: bool) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .with_context(|| format!("Failed to bind to port {}", port))?;
+    println!(
+        "Serving {} at http://localhost:{}/ (Ctrl+C to stop)",
+        root.display(),
+        port
+    );
+    if open {
+        open_in_browser(&format!("http://localhost:{}/", port));
+    }
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Connection failed: {}", e);
+                continue;
+            }
+        };
+        if let Err(e) = handle_connection(stream, root) {
+            eprintln!("Request failed: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Best-effort: open `target` (a URL or file path) in the default browser
+///
+/// Only warns on failure, since the site has already been built by then.
+pub fn open_in_browser(target: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    match command.arg(target).status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!("Warning: Failed to open {} ({})", target, status),
+        Err(e) => eprintln!("Warning: Failed to open {}: {}", target, e),
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, root: &Path) -> Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // Drain headers; we don't use any of them
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let target = parts.next().unwrap_or("/");
+
+    if method != "GET" && method != "HEAD" {
+        return respond(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            b"Method Not Allowed",
+            true,
+        );
+    }
+
+    let url_path = percent_decode(target.split(['?', '#']).next().unwrap_or("/"));
+    let Some(file_path) = resolve_path(root, &url_path) else {
+        return respond(
+            &mut stream,
+            "404 Not Found",
+            "text/plain",
+            b"Not Found",
+            true,
+        );
+    };
+
+    // Redirect /foo to /foo/ so relative links inside index.html resolve
+    if file_path.is_dir() {
+        let location = format!("{}/", url_path.trim_end_matches('/'));
+        let response = format!(
+            "HTTP/1.1 301 Moved Permanently\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            location
+        );
+        stream.write_all(response.as_bytes())?;
+        return Ok(());
+    }
+
+    let status = match fs::read(&file_path) {
+        Ok(body) => {
+            let content_type = content_type(&file_path);
+            respond(&mut stream, "200 OK", content_type, &body, method == "GET")?;
+            "200"
+        }
+        Err(_) => {
+            respond(
+                &mut stream,
+                "404 Not Found",
+                "text/plain",
+                b"Not Found",
+                true,
+            )?;
+            "404"
+        }
+    };
+    println!("{} {} {}", status, method, url_path);
+
+    Ok(())
+}
+
+/// Map a URL path onto `root`, resolving `/foo/` to `/foo/index.html`
+///
+/// Returns None for paths that try to escape the root (e.g. `..`).
+fn resolve_path(root: &Path, url_path: &str) -> Option<PathBuf> {
+    let relative = Path::new(url_path.trim_start_matches('/'));
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+
+    let path = root.join(relative);
+    if url_path.ends_with('/') {
+        Some(path.join("index.html"))
+    } else {
+        Some(path)
+    }
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Decode `%XX` escapes (e.g. `%20` in image filenames)
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok());
+        if let (b'%', Some(hex)) = (bytes[i], hex) {
+            if let Ok(b) = u8::from_str_radix(hex, 16) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+    include_body: bool,
+) -> Result<()> {
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    stream.write_all(header.as_bytes())?;
+    if include_body {
+        stream.write_all(body)?;
+    }
+    Ok(())
+}
@@ -1,8 +1,12 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use chrono::{Local, NaiveDate};
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::fmt::Write as FmtWrite;
 use std::fs;
+use std::path::Path;
+use usps_rates::rates::{PostalRates, RateHistory, RATES_DIR};
 
 const DOMESTIC_CSV_URL: &str = "https://www.usps.com/business/prices/2025/m-fcm-eddm-retail.csv";
 const INTERNATIONAL_HTML_URL: &str = "https://pe.usps.com/text/dmm300/Notice123.htm";
@@ -45,6 +49,86 @@ struct InternationalRates {
     large_envelope_1oz: f64,
 }
 
+/// Output format for `simple`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Markdown,
+}
+
+fn format_dollars(rate: f64) -> String {
+    format!("${:.2}", rate)
+}
+
+/// Render rates as GitHub-flavored markdown tables
+fn rates_to_markdown(rates: &PostageRates) -> String {
+    let mut md = String::new();
+    let letter = &rates.domestic.letter;
+
+    // Weight keys are strings like "3.5oz"; order them numerically
+    let mut weights: Vec<&String> = letter.stamped.keys().chain(letter.metered.keys()).collect();
+    let weight_value = |w: &str| w.trim_end_matches("oz").parse::<f64>().unwrap_or(f64::MAX);
+    weights.sort_by(|a, b| weight_value(a).total_cmp(&weight_value(b)));
+    weights.dedup();
+
+    let effective = |date: &str| {
+        if date.is_empty() {
+            String::new()
+        } else {
+            format!(" (effective {})", date)
+        }
+    };
+    let cell = |rate: Option<&f64>| rate.map(|r| format_dollars(*r)).unwrap_or_default();
+
+    let _ = writeln!(
+        md,
+        "### Domestic Letters{}\n",
+        effective(&rates.domestic.effective_date)
+    );
+    md.push_str("| Weight | Stamped | Metered |\n|---|---|---|\n");
+    for weight in weights {
+        let _ = writeln!(
+            md,
+            "| {} oz | {} | {} |",
+            weight.trim_end_matches("oz"),
+            cell(letter.stamped.get(weight)),
+            cell(letter.metered.get(weight))
+        );
+    }
+
+    let _ = writeln!(md, "\n### Domestic Other\n");
+    md.push_str("| Item | Rate |\n|---|---|\n");
+    for (label, rate) in [
+        ("Postcard", rates.domestic.postcard),
+        ("Additional ounce", rates.domestic.additional_ounce),
+        (
+            "Nonmachinable surcharge",
+            rates.domestic.nonmachinable_surcharge,
+        ),
+    ] {
+        let _ = writeln!(md, "| {} | {} |", label, format_dollars(rate));
+    }
+
+    let intl = &rates.international;
+    let _ = writeln!(
+        md,
+        "\n### International{}\n",
+        effective(&intl.effective_date)
+    );
+    md.push_str("| Item | Rate |\n|---|---|\n");
+    for (label, rate) in [
+        ("Global Forever", intl.global_forever),
+        ("Letter (1 oz)", intl.letter_1oz),
+        ("Postcard", intl.postcard),
+        ("Additional ounce", intl.additional_ounce),
+        ("Large envelope (1 oz)", intl.large_envelope_1oz),
+    ] {
+        let _ = writeln!(md, "| {} | {} |", label, format_dollars(rate));
+    }
+
+    md
+}
+
 fn fetch_url(url: &str) -> Result<String> {
     let client = reqwest::blocking::Client::builder()
         .user_agent("Mozilla/5.0 (compatible; USPSRateScraper/1.0)")
@@ -55,6 +139,9 @@ fn fetch_url(url: &str) -> Result<String> {
     Ok(text)
 }
 
+/// Rows of the domestic CSV quoted in the layout-change error
+const CSV_PREVIEW_ROWS: usize = 5;
+
 fn parse_domestic_csv(csv_content: &str) -> Result<DomesticRates> {
     let mut letter_stamped: BTreeMap<String, f64> = BTreeMap::new();
     let mut letter_metered: BTreeMap<String, f64> = BTreeMap::new();
@@ -157,6 +244,19 @@ fn parse_domestic_csv(csv_content: &str) -> Result<DomesticRates> {
         }
     }
 
+    // Missing section markers parse as zeros rather than failing; don't write those out
+    if postcard == 0.0 || letter_stamped.is_empty() {
+        let preview: Vec<&str> = csv_content.lines().take(CSV_PREVIEW_ROWS).collect();
+        bail!(
+            "Domestic CSV format likely changed: found {} stamped letter rates and postcard rate {:.2}. \
+             Expected \"LETTERS\" and \"Postcard\" rows. First {} rows:\n{}",
+            letter_stamped.len(),
+            postcard,
+            preview.len(),
+            preview.join("\n")
+        );
+    }
+
     Ok(DomesticRates {
         effective_date,
         letter: LetterRates {
@@ -230,17 +330,21 @@ fn parse_international_html(html_content: &str) -> Result<InternationalRates> {
     })
 }
 
-pub fn run_simple() -> Result<()> {
+fn fetch_domestic_rates() -> Result<DomesticRates> {
     println!("Fetching USPS domestic rates...");
     let domestic_csv = fetch_url(DOMESTIC_CSV_URL).context("Failed to fetch domestic CSV")?;
 
+    println!("Parsing domestic rates...");
+    parse_domestic_csv(&domestic_csv).context("Failed to parse domestic CSV")
+}
+
+pub fn run_simple(format: OutputFormat) -> Result<()> {
+    let domestic = fetch_domestic_rates()?;
+
     println!("Fetching USPS international rates...");
     let international_html =
         fetch_url(INTERNATIONAL_HTML_URL).context("Failed to fetch international HTML")?;
 
-    println!("Parsing domestic rates...");
-    let domestic = parse_domestic_csv(&domestic_csv).context("Failed to parse domestic CSV")?;
-
     println!("Parsing international rates...");
     let international = parse_international_html(&international_html)
         .context("Failed to parse international HTML")?;
@@ -261,7 +365,247 @@ pub fn run_simple() -> Result<()> {
     println!("Rates written to rates.json");
 
     // Also print to stdout
-    println!("\n{}", json);
+    match format {
+        OutputFormat::Json => println!("\n{}", json),
+        OutputFormat::Markdown => println!("\n{}", rates_to_markdown(&rates)),
+    }
 
     Ok(())
 }
+
+/// Compare live USPS domestic rates against the recorded history in enrichment/rates/
+///
+/// A mismatch means a rate change hasn't been recorded yet and a new entry
+/// needs to be appended to the corresponding CONL file.
+pub fn run_rates_diff() -> Result<()> {
+    let domestic = fetch_domestic_rates()?;
+    let postal_rates = PostalRates::load()?;
+    let today = Local::now().date_naive();
+
+    let letter_1oz = domestic
+        .letter
+        .stamped
+        .get("1oz")
+        .copied()
+        .context("Domestic CSV has no 1oz stamped letter rate")?;
+
+    let checks = [
+        (
+            "letter",
+            letter_1oz,
+            postal_rates.letter.rate_on_date(today).ok(),
+        ),
+        (
+            "ounce",
+            domestic.additional_ounce,
+            postal_rates.ounce.rate_on_date(today).ok(),
+        ),
+        (
+            "postcard",
+            domestic.postcard,
+            postal_rates.postcard(today).ok(),
+        ),
+    ];
+
+    println!(
+        "\nComparing live rates (effective {}) to history as of {}:",
+        domestic.effective_date, today
+    );
+    let mut mismatches = 0;
+    for (name, live, recorded) in checks {
+        match recorded {
+            Some(recorded) if (recorded - live).abs() < 0.005 => {
+                println!("  {:<10} ${:.2} OK", name, live);
+            }
+            Some(recorded) => {
+                println!(
+                    "  {:<10} ${:.2} live, ${:.2} recorded  MISMATCH",
+                    name, live, recorded
+                );
+                mismatches += 1;
+            }
+            None => {
+                println!(
+                    "  {:<10} ${:.2} live, no recorded rate  MISMATCH",
+                    name, live
+                );
+                mismatches += 1;
+            }
+        }
+    }
+
+    if mismatches > 0 {
+        bail!(
+            "{} rate(s) differ from history. Append the new rates to {}/<name>.conl",
+            mismatches,
+            RATES_DIR
+        );
+    }
+
+    println!("All recorded rates match.");
+    Ok(())
+}
+
+/// Append `date = rate` to a rate history file unless its latest entry already has that rate
+///
+/// Returns whether a line was written. History files stay in date order, so a new
+/// rate dated on or before the latest entry is an error.
+fn record_rate(name: &str, path: &Path, date: NaiveDate, rate: f64) -> Result<bool> {
+    let history = RateHistory::load_from_path(name, path)?;
+    if let Some((latest_date, latest_rate)) = history.latest() {
+        if (latest_rate - rate).abs() < 0.005 {
+            return Ok(false);
+        }
+        if date <= latest_date {
+            bail!(
+                "{}: ${:.2} on {} isn't after the latest entry ({} = {:.2})",
+                path.display(),
+                rate,
+                date,
+                latest_date,
+                latest_rate
+            );
+        }
+    }
+
+    let mut content = fs::read_to_string(path)?;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    writeln!(content, "{} = {:.2}", date.format("%Y-%m-%d"), rate)?;
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(true)
+}
+
+/// Fold the domestic rates from a `simple` rates.json into enrichment/rates/ as of `date`
+///
+/// Idempotent: a rate matching the latest recorded entry is left alone.
+pub fn run_rates_record(input: &Path, date: Option<NaiveDate>) -> Result<()> {
+    let content =
+        fs::read_to_string(input).with_context(|| format!("Failed to read {}", input.display()))?;
+    let rates: PostageRates = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", input.display()))?;
+    let date = date.unwrap_or_else(|| Local::now().date_naive());
+
+    let domestic = &rates.domestic;
+    let letter_1oz = domestic
+        .letter
+        .stamped
+        .get("1oz")
+        .copied()
+        .with_context(|| format!("{} has no 1oz stamped letter rate", input.display()))?;
+
+    println!("Recording rates from {} as of {}:", input.display(), date);
+    for (name, rate) in [
+        ("letter", letter_1oz),
+        ("ounce", domestic.additional_ounce),
+        ("postcard", domestic.postcard),
+    ] {
+        let path = Path::new(RATES_DIR).join(format!("{}.conl", name));
+        if record_rate(name, &path, date, rate)? {
+            println!("  {:<10} ${:.2} added to {}", name, rate, path.display());
+        } else {
+            println!("  {:<10} ${:.2} unchanged", name, rate);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_rates() -> PostageRates {
+        let weights = |rates: &[(&str, f64)]| {
+            rates
+                .iter()
+                .map(|(w, r)| (w.to_string(), *r))
+                .collect::<BTreeMap<_, _>>()
+        };
+        PostageRates {
+            sources: Sources {
+                domestic_csv: DOMESTIC_CSV_URL.to_string(),
+                international_html: INTERNATIONAL_HTML_URL.to_string(),
+            },
+            domestic: DomesticRates {
+                effective_date: "7/13/2025".to_string(),
+                letter: LetterRates {
+                    stamped: weights(&[
+                        ("1oz", 0.78),
+                        ("2oz", 1.07),
+                        ("3.5oz", 1.65),
+                        ("3oz", 1.36),
+                    ]),
+                    metered: weights(&[
+                        ("1oz", 0.74),
+                        ("2oz", 1.03),
+                        ("3.5oz", 1.61),
+                        ("3oz", 1.32),
+                    ]),
+                },
+                postcard: 0.61,
+                additional_ounce: 0.29,
+                nonmachinable_surcharge: 0.49,
+            },
+            international: InternationalRates {
+                effective_date: String::new(),
+                global_forever: 1.70,
+                letter_1oz: 1.70,
+                postcard: 1.70,
+                additional_ounce: 0.29,
+                large_envelope_1oz: 3.15,
+            },
+        }
+    }
+
+    #[test]
+    fn test_rates_to_markdown() {
+        let md = rates_to_markdown(&sample_rates());
+
+        assert!(md.contains("### Domestic Letters (effective 7/13/2025)"));
+        assert!(md.contains("### International\n"));
+        assert!(md.contains("| 1 oz | $0.78 | $0.74 |"));
+        assert!(md.contains("| Postcard | $0.61 |"));
+        assert!(md.contains("| Global Forever | $1.70 |"));
+
+        // Weights are ordered numerically, not lexically ("3.5oz" < "3oz")
+        let three = md.find("| 3 oz |").unwrap();
+        let three_half = md.find("| 3.5 oz |").unwrap();
+        assert!(three < three_half);
+    }
+
+    #[test]
+    fn test_parse_domestic_csv_layout_check() {
+        let csv = "LETTERS\n1,0.78\n2,1.07\nPostcard,0.61\n";
+        let rates = parse_domestic_csv(csv).unwrap();
+        assert_eq!(rates.letter.stamped.get("1oz"), Some(&0.78));
+        assert_eq!(rates.postcard, 0.61);
+
+        let err = parse_domestic_csv("Letters (stamped)\n1,0.78\nPost Card,0.61\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("format likely changed"));
+        assert!(err.contains("Letters (stamped)"));
+    }
+
+    #[test]
+    fn test_record_rate() {
+        let path = std::env::temp_dir().join(format!("usps-record-{}.conl", std::process::id()));
+        fs::write(&path, "; letter\n2024-07-14 = 0.73").unwrap();
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+
+        // Same rate as the latest entry: nothing to add
+        assert!(!record_rate("letter", &path, date("2025-01-01"), 0.73).unwrap());
+        assert!(record_rate("letter", &path, date("2025-07-13"), 0.78).unwrap());
+        // Re-running is a no-op
+        assert!(!record_rate("letter", &path, date("2025-07-14"), 0.78).unwrap());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "; letter\n2024-07-14 = 0.73\n2025-07-13 = 0.78\n"
+        );
+        // A changed rate can't be dated before the latest entry
+        assert!(record_rate("letter", &path, date("2025-07-13"), 0.80).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,216 @@
+//! Slug transformation from stampsforever.com API slugs
+
+use crate::types::RateType;
+
+/// Generate the new slug format based on rate_type and rate
+/// Format: "{base}-{denomination}-{year}" for denominated, "{base}-{value_type}-{year}" for forever
+///
+/// `forever` (from a `forever` override) replaces the year-based forever rules.
+pub fn generate_slug(
+    api_slug: &str,
+    year: u32,
+    rate_type: Option<&str>,
+    rate: Option<&str>,
+    forever: Option<bool>,
+) -> (String, bool) {
+    // Use RateType::is_forever(year) to determine if this is a forever stamp
+    // This respects the year-based rules for when forever stamps became available
+    let is_forever = forever.unwrap_or_else(|| {
+        rate_type
+            .map(|rt| RateType::from_str(rt).is_forever(year))
+            .unwrap_or(false)
+    });
+
+    // Clean the API slug to get base name (remove year suffix if present)
+    let year_suffix = format!("-{}", year);
+    let base_slug = if api_slug.ends_with(&year_suffix) {
+        &api_slug[..api_slug.len() - year_suffix.len()]
+    } else {
+        api_slug
+    };
+
+    // Strip disambiguation suffix (-2, -3, etc.)
+    let base_slug = if let Some(last_dash) = base_slug.rfind('-') {
+        let suffix = &base_slug[last_dash + 1..];
+        if suffix.len() == 1
+            && suffix
+                .chars()
+                .next()
+                .map(|c| c.is_ascii_digit())
+                .unwrap_or(false)
+        {
+            &base_slug[..last_dash]
+        } else {
+            base_slug
+        }
+    } else {
+        base_slug
+    };
+
+    // Strip denomination prefix (e.g., "10c-poppies" -> "poppies", "2-floral" -> "floral")
+    let base_slug = if let Some(idx) = base_slug.find('-') {
+        let prefix = &base_slug[..idx];
+        if prefix.ends_with('c')
+            && prefix[..prefix.len() - 1]
+                .chars()
+                .all(|c| c.is_ascii_digit())
+        {
+            // Remove cent prefix like "10c-"
+            &base_slug[idx + 1..]
+        } else if prefix.chars().all(|c| c.is_ascii_digit()) {
+            // Remove dollar prefix like "2-"
+            &base_slug[idx + 1..]
+        } else {
+            base_slug
+        }
+    } else {
+        base_slug
+    };
+
+    // Build the new slug
+    let slug = if is_forever {
+        // Use rate_type for slug, fall back to "forever"
+        let value_type = rate_type.unwrap_or("Forever");
+        let vt_slug = value_type.to_lowercase().replace(' ', "-");
+
+        // Handle special case: don't duplicate "semipostal" in "alzheimers-semipostal"
+        if base_slug.ends_with("-semipostal") && vt_slug == "semipostal" {
+            format!("{}-{}", base_slug, year)
+        } else {
+            format!("{}-{}-{}", base_slug, vt_slug, year)
+        }
+    } else {
+        // Denominated stamp: include denomination in slug
+        match rate.and_then(denomination_slug) {
+            Some(d) => format!("{}-{}-{}", base_slug, d, year),
+            None => format!("{}-{}", base_slug, year),
+        }
+    };
+
+    (slug, is_forever)
+}
+
+/// Slug form of a face value: "5.00" -> "5d", "6.70" -> "6d70c", "0.46" -> "46c"
+///
+/// Accepts the formats seen in the API and overrides: an optional `$`, thousands
+/// separators, zero to two decimal places ("64", "0.1", "$5") or a cents suffix
+/// ("10c", "10¢"). Returns None for anything else, or a zero value.
+fn denomination_slug(rate: &str) -> Option<String> {
+    let rate = rate.trim().trim_start_matches('$').replace(',', "");
+    let cents: u64 = if let Some(cents) = rate.strip_suffix(['c', '¢']) {
+        cents.trim_end().parse().ok()?
+    } else {
+        let (dollars, fraction) = rate.split_once('.').unwrap_or((&rate, ""));
+        // Extra places must be zeros ("5.000"); "0.105" isn't a stamp value
+        let (fraction, extra) = fraction.split_at(fraction.len().min(2));
+        if !extra.chars().all(|c| c == '0')
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+            || (dollars.is_empty() && fraction.is_empty())
+        {
+            return None;
+        }
+        let dollars: u64 = if dollars.is_empty() {
+            0
+        } else {
+            dollars.parse().ok()?
+        };
+        // "0.1" is ten cents, not one
+        let fraction: u64 = format!("{:0<2}", fraction).parse().ok()?;
+        dollars * 100 + fraction
+    };
+
+    match (cents / 100, cents % 100) {
+        (0, 0) => None,
+        // Sub-dollar: just cents (e.g., "46c" not "0d46c")
+        (0, cents) => Some(format!("{}c", cents)),
+        (dollars, 0) => Some(format!("{}d", dollars)),
+        (dollars, cents) => Some(format!("{}d{:02}c", dollars, cents)),
+    }
+}
+
+/// Slugify a name for URL use
+pub fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Trim and dedupe topic tags, keeping the first spelling of each slug
+/// ("Birds" and "birds" would otherwise share a /topics/ page)
+pub fn dedup_tags<I: IntoIterator<Item = String>>(tags: I) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    tags.into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| {
+            let slug = slugify(t);
+            !slug.is_empty() && seen.insert(slug)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denomination_slug() {
+        let cases = [
+            ("5.00", Some("5d")),
+            ("6.70", Some("6d70c")),
+            ("6.7", Some("6d70c")),
+            ("0.46", Some("46c")),
+            ("0.10", Some("10c")),
+            ("0.1", Some("10c")),
+            (".05", Some("5c")),
+            ("64", Some("64d")),
+            ("$5", Some("5d")),
+            ("$1,000.00", Some("1000d")),
+            ("5.000", Some("5d")),
+            ("10c", Some("10c")),
+            ("3¢", Some("3c")),
+            (" 0.78 ", Some("78c")),
+            ("0.00", None),
+            ("0.105", None),
+            ("1.2.3", None),
+            ("five", None),
+            ("", None),
+        ];
+        for (rate, expected) in cases {
+            assert_eq!(denomination_slug(rate).as_deref(), expected, "{:?}", rate);
+        }
+    }
+
+    #[test]
+    fn test_generate_slug_denominated() {
+        let slug = |api_slug, rate| generate_slug(api_slug, 2000, Some("Definitive"), rate, None).0;
+        assert_eq!(
+            slug("joseph-w-stilwell-2000", Some("0.1")),
+            "joseph-w-stilwell-10c-2000"
+        );
+        assert_eq!(slug("10c-poppies-2000", Some("$5")), "poppies-5d-2000");
+        assert_eq!(slug("dolphin-2000", Some("64")), "dolphin-64d-2000");
+        // Unparseable rates fall back to the year alone
+        assert_eq!(slug("dolphin-2000", Some("n/a")), "dolphin-2000");
+        assert_eq!(slug("dolphin-2000", None), "dolphin-2000");
+    }
+
+    #[test]
+    fn test_dedup_tags() {
+        let tags = [
+            "Birds",
+            " birds ",
+            "Space",
+            "",
+            "--",
+            "Bald Eagle",
+            "bald-eagle",
+        ]
+        .map(String::from);
+        assert_eq!(dedup_tags(tags), ["Birds", "Space", "Bald Eagle"]);
+    }
+}
@@ -3,8 +3,11 @@ use rusqlite::Connection;
 use serde::Deserialize;
 use std::collections::HashSet;
 use std::fs;
+use std::path::Path;
 
-use crate::{detect_stamp_type, init_database, parse_date_to_iso, MIN_SCRAPE_YEAR, STAMPS_API_URL};
+use crate::fetch::{http_client, Fetcher};
+use crate::init_database;
+use usps_rates::{detect_stamp_type, parse_date_to_iso, MIN_SCRAPE_YEAR, STAMPS_API_URL};
 
 const EXCLUDE_FILE: &str = "enrichment/exclude.conl";
 
@@ -36,7 +39,6 @@ fn load_excluded_slugs() -> HashSet<String> {
 #[derive(Debug, Deserialize)]
 struct StampsApiResponse {
     data: Vec<StampData>,
-    #[allow(dead_code)]
     meta: PaginationMeta,
 }
 
@@ -49,7 +51,6 @@ struct StampData {
     rate_type: Option<String>,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 struct PaginationMeta {
     last_page: u32,
@@ -72,9 +73,57 @@ fn parse_year(date_str: &str) -> Option<u32> {
     None
 }
 
-pub fn run_sync(output: &str) -> Result<()> {
+/// Fetch every page of the stamp-issuances API at `api_url`
+///
+/// Pages are fetched one at a time; a large `per_page` fetches everything in one request.
+fn fetch_catalog(fetcher: &dyn Fetcher, api_url: &str, per_page: u32) -> Result<Vec<StampData>> {
+    let mut stamps = Vec::new();
+    let mut page = 1;
+    loop {
+        let url = format!("{}?per_page={}&page={}", api_url, per_page, page);
+
+        println!("Fetching stamps from API (page {})...", page);
+        let response: StampsApiResponse = fetcher
+            .fetch_json(&url)
+            .context("Failed to fetch stamps API")?;
+        stamps.extend(response.data);
+
+        if page >= response.meta.last_page {
+            println!(
+                "Fetched {} stamps across {} page(s)",
+                response.meta.total, page
+            );
+            return Ok(stamps);
+        }
+        page += 1;
+    }
+}
+
+/// Load a saved API response (e.g. `?per_page=5000`) instead of fetching
+fn load_dump(path: &Path) -> Result<Vec<StampData>> {
+    println!("Reading stamps from {}...", path.display());
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let response: StampsApiResponse = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse stamps JSON: {}", path.display()))?;
+
+    if response.meta.last_page > 1 || (response.data.len() as u32) < response.meta.total {
+        eprintln!(
+            "Warning: {} has {} of {} stamps; save the dump with a larger per_page",
+            path.display(),
+            response.data.len(),
+            response.meta.total
+        );
+    }
+    Ok(response.data)
+}
+
+/// Sync the stampsforever_stamps table from the live API, or from `source`
+///
+/// `source` is an http(s) API URL, or a local JSON dump given as a path or `file://` URL.
+pub fn run_sync(output: &str, per_page: u32, source: Option<&str>) -> Result<()> {
     // Create/open SQLite database
-    let conn = Connection::open(output)?;
+    let mut conn = Connection::open(output)?;
 
     init_database(&conn)?;
 
@@ -84,25 +133,21 @@ pub fn run_sync(output: &str) -> Result<()> {
         println!("Loaded {} excluded slugs from {}", excluded_slugs.len(), EXCLUDE_FILE);
     }
 
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("Mozilla/5.0 (compatible; USPSStampScraper/1.0)")
-        .build()?;
-
-    // Fetch all stamps in a single request (API supports up to 5000 per page)
-    let url = format!("{}?per_page=5000", STAMPS_API_URL);
-
-    println!("Fetching stamps from API...");
-    let response: StampsApiResponse = client
-        .get(&url)
-        .send()
-        .context("Failed to fetch stamps API")?
-        .json()
-        .context("Failed to parse stamps JSON")?;
+    let stamps = match source {
+        None => fetch_catalog(&http_client()?, STAMPS_API_URL, per_page)?,
+        Some(url) if url.starts_with("https://") || url.starts_with("http://") => {
+            fetch_catalog(&http_client()?, url, per_page)?
+        }
+        Some(path) => load_dump(Path::new(path.strip_prefix("file://").unwrap_or(path)))?,
+    };
 
     let mut total_inserted = 0u32;
     let mut total_excluded = 0u32;
 
-    for stamp in &response.data {
+    // Batch all inserts into a single transaction (rolled back on drop if we bail early)
+    let tx = conn.transaction()?;
+
+    for stamp in &stamps {
         // Skip explicitly excluded slugs
         if excluded_slugs.contains(&stamp.slug) {
             total_excluded += 1;
@@ -122,7 +167,10 @@ pub fn run_sync(output: &str) -> Result<()> {
         // Skip excluded rate types (duck stamps, presorted)
         if let Some(ref rt) = stamp.rate_type {
             match rt.as_str() {
-                "Federal Duck Stamp" | "Presorted Standard" | "Presorted First-Class" | "Nonprofit" => continue,
+                "Federal Duck Stamp"
+                | "Presorted Standard"
+                | "Presorted First-Class"
+                | "Nonprofit" => continue,
                 _ => {}
             }
         }
@@ -135,7 +183,7 @@ pub fn run_sync(output: &str) -> Result<()> {
         // Detect stamp type (stamp, card, envelope)
         let stamp_type = detect_stamp_type(&stamp.name);
 
-        let result = conn.execute(
+        let result = tx.execute(
             "INSERT OR REPLACE INTO stampsforever_stamps (slug, name, url, rate, year, issue_date, issue_location, type)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             (
@@ -156,9 +204,57 @@ pub fn run_sync(output: &str) -> Result<()> {
         }
     }
 
+    tx.commit().context("Failed to commit stamps transaction")?;
+
     println!(
         "Done! Inserted {} stamps into {} ({} excluded by slug)",
         total_inserted, output, total_excluded
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::FixtureFetcher;
+
+    fn page_json(slug: &str, last_page: u32) -> String {
+        format!(
+            r#"{{"data": [{{"slug": "{}", "name": "Stamp", "issue_date": "June 17, 2025"}}],
+                "meta": {{"last_page": {}, "total": 2}}}}"#,
+            slug, last_page
+        )
+    }
+
+    #[test]
+    fn test_fetch_catalog_pages() {
+        let fetcher = FixtureFetcher::default()
+            .with(
+                &format!("{}?per_page=1&page=1", STAMPS_API_URL),
+                page_json("first", 2),
+            )
+            .with(
+                &format!("{}?per_page=1&page=2", STAMPS_API_URL),
+                page_json("second", 2),
+            );
+
+        let slugs: Vec<String> = fetch_catalog(&fetcher, STAMPS_API_URL, 1)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.slug)
+            .collect();
+        assert_eq!(slugs, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_load_dump() {
+        let path = std::env::temp_dir().join(format!("usps-dump-{}.json", std::process::id()));
+        fs::write(&path, page_json("first", 1)).unwrap();
+
+        let stamps = load_dump(&path).unwrap();
+        assert_eq!(stamps.len(), 1);
+        assert_eq!(stamps[0].slug, "first");
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,6 +1,9 @@
 //! Stamp metadata types with CONL serialization support
 
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
 
 /// Rate type for stamps (determines pricing structure)
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -80,6 +83,35 @@ impl RateType {
         }
     }
 
+    /// Canonical spelling of a rate type string, e.g. "Additional Postage" -> "Additional Ounce"
+    ///
+    /// Unrecognized values are returned unchanged.
+    pub fn canonical_str(s: &str) -> &str {
+        match RateType::from_str(s) {
+            RateType::Other => s,
+            rate_type => rate_type.as_str(),
+        }
+    }
+
+    /// Map an enrichment `value_type` (read off the stamp image) to a rate type
+    ///
+    /// Returns None for "denominated" and unrecognized values, which carry no
+    /// rate type information.
+    pub fn from_value_type(value_type: &str) -> Option<Self> {
+        match value_type.trim().to_lowercase().as_str() {
+            "forever" => Some(RateType::Forever),
+            "global forever" => Some(RateType::GlobalForever),
+            "postcard forever" => Some(RateType::Postcard),
+            "additional ounce" => Some(RateType::AdditionalOunce),
+            "two ounce" => Some(RateType::TwoOunce),
+            "three ounce" => Some(RateType::ThreeOunce),
+            "nonmachinable" => Some(RateType::Nonmachineable),
+            "priority mail" => Some(RateType::PriorityMail),
+            "priority mail express" => Some(RateType::PriorityMailExpress),
+            _ => None,
+        }
+    }
+
     /// Returns true if this rate type represents a "forever" stamp for the given year
     ///
     /// Forever stamp availability by rate type:
@@ -116,6 +148,67 @@ impl RateType {
             | RateType::Other => false,
         }
     }
+
+    /// Which group of site pages this rate type's stamps belong to
+    pub fn category(&self) -> RateCategory {
+        match self {
+            RateType::Forever | RateType::Semipostal => RateCategory::Forever,
+            RateType::AdditionalOunce | RateType::TwoOunce | RateType::ThreeOunce => {
+                RateCategory::AdditionalPostage
+            }
+            RateType::Nonmachineable => RateCategory::Nonmachinable,
+            RateType::International | RateType::GlobalForever => RateCategory::Global,
+            RateType::Postcard => RateCategory::Postcard,
+            RateType::PriorityMail | RateType::PriorityMailExpress => RateCategory::Priority,
+            RateType::Definitive | RateType::FirstClass => RateCategory::Denominated,
+            RateType::PresortedFirstClass | RateType::PresortedStandard | RateType::Nonprofit => {
+                RateCategory::Presorted
+            }
+            RateType::Other => RateCategory::Other,
+        }
+    }
+}
+
+/// Site grouping for rate types, shared by the year pages, category pages and badges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateCategory {
+    /// Letter-rate forever stamps
+    Forever,
+    /// Extra-ounce forever stamps
+    AdditionalPostage,
+    Nonmachinable,
+    /// International forever stamps
+    Global,
+    Postcard,
+    /// Always a dollar value, never forever
+    Priority,
+    /// Usually a face value; First Class stamps from 2011 are forever
+    Denominated,
+    /// Bulk mail rates, placed by the stamp's own denomination
+    Presorted,
+    /// Unrecognized types, placed by the stamp's own denomination
+    Other,
+}
+
+impl RateCategory {
+    /// Category page slug and short badge label, for forever categories with their own page
+    pub fn forever_page(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            RateCategory::Forever => Some(("forever-stamps", "Forever")),
+            RateCategory::AdditionalPostage => {
+                Some(("additional-postage-forever-stamps", "Additional Postage"))
+            }
+            RateCategory::Nonmachinable => {
+                Some(("non-machinable-forever-stamps", "Non-Machinable"))
+            }
+            RateCategory::Global => Some(("global-forever-stamps", "Global")),
+            RateCategory::Postcard => Some(("postcard-forever-stamps", "Postcard")),
+            RateCategory::Priority
+            | RateCategory::Denominated
+            | RateCategory::Presorted
+            | RateCategory::Other => None,
+        }
+    }
 }
 
 /// Type of postal item
@@ -137,6 +230,11 @@ impl StampType {
         }
     }
 
+    /// The default type, omitted from `metadata.conl`
+    pub fn is_stamp(&self) -> bool {
+        *self == StampType::Stamp
+    }
+
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "card" => StampType::Card,
@@ -147,30 +245,76 @@ impl StampType {
 }
 
 /// Credits for a stamp (art director, designer, etc.)
+///
+/// Each role lists everyone credited with it, sorted so re-scrapes are stable even if the
+/// API reorders people. A single name is written as a plain value, several as a list.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Credits {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub art_director: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub artist: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub designer: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub typographer: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub photographer: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub illustrator: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "one_or_many")]
+    pub art_director: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "one_or_many")]
+    pub artist: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "one_or_many")]
+    pub designer: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "one_or_many")]
+    pub typographer: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "one_or_many")]
+    pub photographer: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "one_or_many")]
+    pub illustrator: Vec<String>,
 }
 
 impl Credits {
     pub fn is_empty(&self) -> bool {
-        self.art_director.is_none()
-            && self.artist.is_none()
-            && self.designer.is_none()
-            && self.typographer.is_none()
-            && self.photographer.is_none()
-            && self.illustrator.is_none()
+        self.art_director.is_empty()
+            && self.artist.is_empty()
+            && self.designer.is_empty()
+            && self.typographer.is_empty()
+            && self.photographer.is_empty()
+            && self.illustrator.is_empty()
+    }
+
+    /// Sort and dedup every role so the order doesn't depend on the API
+    pub fn normalize(&mut self) {
+        for names in [
+            &mut self.art_director,
+            &mut self.artist,
+            &mut self.designer,
+            &mut self.typographer,
+            &mut self.photographer,
+            &mut self.illustrator,
+        ] {
+            names.sort();
+            names.dedup();
+        }
+    }
+}
+
+/// (De)serialize a list of names as a plain value when there's only one
+mod one_or_many {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    pub fn serialize<S: Serializer>(names: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+        match names {
+            [name] => name.serialize(serializer),
+            _ => names.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<String>, D::Error> {
+        Ok(match OneOrMany::deserialize(deserializer)? {
+            OneOrMany::One(name) => vec![name],
+            OneOrMany::Many(names) => names,
+        })
     }
 }
 
@@ -180,8 +324,12 @@ pub struct Product {
     pub title: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub long_title: Option<String>,
+    /// Normalized "$X.XX" price
     #[serde(skip_serializing_if = "Option::is_none")]
     pub price: Option<String>,
+    /// Price string as returned by the API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_raw: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub postal_store_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -214,18 +362,27 @@ pub struct StampMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extra_cost: Option<f64>,
 
+    /// Omitted when true, matching the generator's default on read
+    #[serde(default = "default_forever", skip_serializing_if = "is_forever")]
     pub forever: bool,
 
-    #[serde(rename = "type")]
+    #[serde(rename = "type", default, skip_serializing_if = "StampType::is_stamp")]
     pub stamp_type: StampType,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub series: Option<String>,
 
+    /// Topics for /topics/ pages: overrides, else enrichment keywords
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub stamp_images: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sheet_image: Option<String>,
+    /// Pixel size of `sheet_image` as `WIDTHxHEIGHT`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sheet_image_size: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub background_color: Option<String>,
@@ -238,4 +395,160 @@ pub struct StampMetadata {
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub products: Vec<Product>,
+
+    /// Suppress this stamp from the generated site (set via overrides)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden: Option<bool>,
+
+    /// Announced but never issued (set via an `issued = canceled` override)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canceled: Option<bool>,
+
+    /// Slug of the same design issued at another rate (set via overrides)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pair_with: Option<String>,
+}
+
+fn default_forever() -> bool {
+    true
+}
+
+fn is_forever(forever: &bool) -> bool {
+    *forever
+}
+
+impl StampMetadata {
+    /// Parse a `metadata.conl` file's contents
+    pub fn from_conl(content: &str) -> Result<Self> {
+        serde_conl::from_str(content).context("Failed to parse stamp metadata")
+    }
+
+    /// Serialize to the `metadata.conl` format
+    pub fn to_conl(&self) -> Result<String> {
+        Ok(serde_conl::to_string(self)?)
+    }
+
+    /// Read and parse a `metadata.conl` file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_conl(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_type_canonical_str() {
+        assert_eq!(
+            RateType::canonical_str("Additional Postage"),
+            "Additional Ounce"
+        );
+        assert_eq!(
+            RateType::canonical_str("Additional Ounce"),
+            "Additional Ounce"
+        );
+        assert_eq!(RateType::canonical_str("Global Forever"), "Global Forever");
+        assert_eq!(RateType::canonical_str("Denominated"), "Denominated");
+    }
+
+    #[test]
+    fn test_rate_type_category() {
+        let cases = [
+            (RateType::Forever, RateCategory::Forever),
+            (RateType::Semipostal, RateCategory::Forever),
+            (RateType::AdditionalOunce, RateCategory::AdditionalPostage),
+            (RateType::TwoOunce, RateCategory::AdditionalPostage),
+            (RateType::ThreeOunce, RateCategory::AdditionalPostage),
+            (RateType::Nonmachineable, RateCategory::Nonmachinable),
+            (RateType::International, RateCategory::Global),
+            (RateType::GlobalForever, RateCategory::Global),
+            (RateType::Postcard, RateCategory::Postcard),
+            (RateType::PriorityMail, RateCategory::Priority),
+            (RateType::PriorityMailExpress, RateCategory::Priority),
+            (RateType::Definitive, RateCategory::Denominated),
+            (RateType::FirstClass, RateCategory::Denominated),
+            (RateType::PresortedFirstClass, RateCategory::Presorted),
+            (RateType::PresortedStandard, RateCategory::Presorted),
+            (RateType::Nonprofit, RateCategory::Presorted),
+            (RateType::Other, RateCategory::Other),
+        ];
+        for (rate_type, category) in cases {
+            assert_eq!(rate_type.category(), category, "{:?}", rate_type);
+            // Every variant round-trips through its name
+            assert_eq!(RateType::from_str(rate_type.as_str()), rate_type);
+        }
+        assert_eq!(
+            RateType::from_str("Additional Postage").category(),
+            RateCategory::AdditionalPostage
+        );
+        assert_eq!(
+            RateCategory::Global.forever_page(),
+            Some(("global-forever-stamps", "Global"))
+        );
+        assert_eq!(RateCategory::Denominated.forever_page(), None);
+    }
+
+    #[test]
+    fn test_rate_type_from_value_type() {
+        let cases = [
+            ("forever", Some(RateType::Forever)),
+            ("Global Forever", Some(RateType::GlobalForever)),
+            ("postcard forever", Some(RateType::Postcard)),
+            ("additional ounce", Some(RateType::AdditionalOunce)),
+            ("two ounce", Some(RateType::TwoOunce)),
+            ("three ounce", Some(RateType::ThreeOunce)),
+            ("nonmachinable", Some(RateType::Nonmachineable)),
+            ("priority mail", Some(RateType::PriorityMail)),
+            ("priority mail express", Some(RateType::PriorityMailExpress)),
+            ("denominated", None),
+            ("", None),
+        ];
+        for (value_type, expected) in cases {
+            assert_eq!(
+                RateType::from_value_type(value_type),
+                expected,
+                "{}",
+                value_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_credits_one_or_many() {
+        let credits: Credits = serde_conl::from_str(
+            "art_director = William Gicker\ndesigner\n  = Ana Rivera\n  = Bryan Duefrene\n",
+        )
+        .unwrap();
+        assert_eq!(credits.art_director, ["William Gicker"]);
+        assert_eq!(credits.designer, ["Ana Rivera", "Bryan Duefrene"]);
+        assert!(credits.photographer.is_empty());
+
+        // A single name round-trips as a plain value, so existing files don't change
+        let conl = serde_conl::to_string(&credits).unwrap();
+        assert!(conl.contains("art_director = William Gicker\n"));
+        assert!(conl.contains("designer\n  = Ana Rivera\n  = Bryan Duefrene\n"));
+    }
+
+    #[test]
+    fn test_metadata_omits_defaults() {
+        let compact = "name = Love\nslug = love-2025\napi_slug = love\nurl = https://www.stampsforever.com/stamps/love\nyear = 2025\n";
+        let metadata = StampMetadata::from_conl(compact).unwrap();
+        assert!(metadata.forever);
+        assert_eq!(metadata.stamp_type, StampType::Stamp);
+        assert!(metadata.stamp_images.is_empty() && metadata.credits.is_empty());
+        let conl = metadata.to_conl().unwrap();
+        assert!(!conl.contains("forever =") && !conl.contains("type ="));
+        assert!(!conl.contains("stamp_images") && !conl.contains("credits"));
+
+        let card = StampMetadata {
+            forever: false,
+            stamp_type: StampType::Card,
+            ..metadata
+        };
+        let conl = card.to_conl().unwrap();
+        assert!(conl.contains("forever = false\n") && conl.contains("type = card\n"));
+    }
 }
@@ -1,4 +1,5 @@
 use std::fs;
+use std::io::IsTerminal;
 
 /// Create an OSC8 hyperlink for terminal output
 pub fn osc8_link(url: &str, text: &str) -> String {
@@ -12,3 +13,177 @@ pub fn osc8_file_link(path: &str, text: &str) -> String {
         .unwrap_or_else(|_| path.to_string());
     format!("\x1b]8;;file://{}\x1b\\{}\x1b]8;;\x1b\\", abs_path, text)
 }
+
+/// OSC8 link from `text` to a stamp's stampsforever.com page
+///
+/// Plain `text` when `stream` isn't a terminal, so piped and logged output stays clean.
+pub fn stamp_link(api_slug: &str, text: &str, stream: &impl IsTerminal) -> String {
+    if stream.is_terminal() {
+        osc8_link(
+            &format!("https://www.stampsforever.com/stamps/{}", api_slug),
+            text,
+        )
+    } else {
+        text.to_string()
+    }
+}
+
+/// Strip query params and fragments from a URL (e.g., CDN `?derivative_type=large#frag`)
+pub fn strip_url_query(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+/// URLs to try for the largest version of an image, best first
+///
+/// Drops the query (`?derivative_type=large`, `?w=800`) and a `-1024x768` size suffix on
+/// the file name, ending with `url` itself so there's always something to fall back to.
+pub fn original_image_urls(url: &str) -> Vec<String> {
+    let stripped = strip_url_query(url);
+    let mut urls = Vec::new();
+    if let Some(original) = strip_size_suffix(stripped) {
+        urls.push(original);
+    }
+    urls.push(stripped.to_string());
+    urls.push(url.to_string());
+    urls.dedup();
+    urls
+}
+
+/// `.../pane-1024x768.png` -> `.../pane.png`
+fn strip_size_suffix(url: &str) -> Option<String> {
+    let (dir, name) = url.rsplit_once('/')?;
+    let (stem, ext) = name.rsplit_once('.')?;
+    let (base, size) = stem.rsplit_once('-')?;
+    let (width, height) = size.split_once('x')?;
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    (!base.is_empty() && is_number(width) && is_number(height))
+        .then(|| format!("{}/{}.{}", dir, base, ext))
+}
+
+/// Extract the image filename from a URL, ignoring query params and fragments
+pub fn extract_image_filename(url: &str) -> String {
+    strip_url_query(url)
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("image.png")
+        .to_string()
+}
+
+/// Parse a product price string like "$11.75", "11.75" or "$1,234.00" into dollars
+pub fn parse_price(price: &str) -> Option<f64> {
+    price
+        .trim()
+        .trim_start_matches('$')
+        .replace(',', "")
+        .parse()
+        .ok()
+        .filter(|dollars: &f64| dollars.is_finite())
+}
+
+/// Normalize a raw price string to `$X,XXX.XX`, keeping it as-is if it doesn't parse
+pub fn normalize_price(price: &str) -> String {
+    let Some(dollars) = parse_price(price) else {
+        return price.trim().to_string();
+    };
+    let fixed = format!("{:.2}", dollars);
+    let (whole, cents) = fixed.split_once('.').unwrap_or((&fixed, "00"));
+    let (sign, digits) = match whole.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", whole),
+    };
+
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("${}{}.{}", sign, grouped, cents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_price() {
+        assert_eq!(parse_price("$11.75"), Some(11.75));
+        assert_eq!(parse_price("11.75"), Some(11.75));
+        assert_eq!(parse_price("$1,234.00"), Some(1234.0));
+        assert_eq!(parse_price(" $7 "), Some(7.0));
+        assert_eq!(parse_price("Free"), None);
+        assert_eq!(parse_price("NaN"), None);
+        assert_eq!(parse_price("inf"), None);
+    }
+
+    #[test]
+    fn test_normalize_price() {
+        assert_eq!(normalize_price("11.75"), "$11.75");
+        assert_eq!(normalize_price("$1,234.00"), "$1,234.00");
+        assert_eq!(normalize_price("1234567.5"), "$1,234,567.50");
+        assert_eq!(normalize_price("$7"), "$7.00");
+        assert_eq!(normalize_price("Sold out"), "Sold out");
+        assert_eq!(normalize_price("NaN"), "NaN");
+    }
+
+    #[test]
+    fn test_extract_image_filename() {
+        assert_eq!(
+            extract_image_filename("https://cdn.example.com/stamps/image.webp?derivative_type=large#frag"),
+            "image.webp"
+        );
+        assert_eq!(
+            extract_image_filename("https://cdn.example.com/stamps/image.gif#frag"),
+            "image.gif"
+        );
+        assert_eq!(
+            extract_image_filename("https://cdn.example.com/stamps/image.png"),
+            "image.png"
+        );
+        assert_eq!(
+            extract_image_filename("https://cdn.example.com/stamps/image.jpeg?w=800"),
+            "image.jpeg"
+        );
+        assert_eq!(extract_image_filename("https://cdn.example.com/stamps/"), "image.png");
+    }
+
+    #[test]
+    fn test_strip_url_query() {
+        assert_eq!(
+            strip_url_query("https://cdn.example.com/a/image.webp?derivative_type=large#frag"),
+            "https://cdn.example.com/a/image.webp"
+        );
+        assert_eq!(
+            strip_url_query("https://cdn.example.com/a/image.webp#frag?x=1"),
+            "https://cdn.example.com/a/image.webp"
+        );
+    }
+
+    #[test]
+    fn test_original_image_urls() {
+        assert_eq!(
+            original_image_urls(
+                "https://cdn.example.com/a/pane-1024x768.png?derivative_type=large"
+            ),
+            [
+                "https://cdn.example.com/a/pane.png",
+                "https://cdn.example.com/a/pane-1024x768.png",
+                "https://cdn.example.com/a/pane-1024x768.png?derivative_type=large",
+            ]
+        );
+        assert_eq!(
+            original_image_urls("https://cdn.example.com/a/pane.png?w=800"),
+            [
+                "https://cdn.example.com/a/pane.png",
+                "https://cdn.example.com/a/pane.png?w=800",
+            ]
+        );
+        // Names that merely contain a dash or an x are left alone
+        assert_eq!(
+            original_image_urls("https://cdn.example.com/a/love-xoxo.png"),
+            ["https://cdn.example.com/a/love-xoxo.png"]
+        );
+    }
+}